@@ -0,0 +1,177 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+use tokio::sync::Semaphore;
+
+use crate::{
+    audio::{AudioFrame, features::TrackAnalyzer, processor::DecoderCommand},
+    config::AppConfig,
+    protocol::{analysis::AudioAnalysis, tracks::TrackInfo},
+    routeplanner::RoutePlanner,
+    sources::SourceManager,
+};
+
+/// Decodes tracks on the background decode pool and caches their tempo,
+/// key and loudness.
+pub struct AnalysisManager {
+    source_manager: Arc<SourceManager>,
+    player_config: crate::config::player::PlayerConfig,
+    cache: Mutex<AnalysisCache>,
+    permits: Semaphore,
+    cache_size: usize,
+    max_duration_ms: u64,
+    timeout: Duration,
+}
+
+/// Results by key; the oldest is evicted first.
+#[derive(Default)]
+struct AnalysisCache {
+    by_key: HashMap<String, AudioAnalysis>,
+    order: VecDeque<String>,
+}
+
+impl AnalysisCache {
+    fn insert(&mut self, key: String, analysis: AudioAnalysis, capacity: usize) {
+        if self.by_key.insert(key.clone(), analysis).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.by_key.remove(&oldest);
+            }
+        }
+    }
+}
+
+fn cache_key(track: &TrackInfo) -> String {
+    format!("{}:{}", track.source_name, track.identifier)
+}
+
+impl AnalysisManager {
+    pub fn new(config: &AppConfig, source_manager: Arc<SourceManager>) -> Self {
+        let analysis = &config.analysis;
+        let mut player_config = config.player.clone();
+        player_config.background_decode = true;
+        Self {
+            source_manager,
+            player_config,
+            cache: Mutex::default(),
+            permits: Semaphore::new(analysis.concurrency.max(1)),
+            cache_size: analysis.cache_size,
+            max_duration_ms: analysis.max_duration_secs * 1000,
+            timeout: Duration::from_secs(analysis.timeout_secs),
+        }
+    }
+
+    /// A previously computed result for `track`, without decoding anything.
+    pub fn cached(&self, track: &TrackInfo) -> Option<AudioAnalysis> {
+        self.cache.lock().by_key.get(&cache_key(track)).cloned()
+    }
+
+    /// Analyses `track`, or returns the cached result.
+    pub async fn analyze(
+        &self,
+        track: &TrackInfo,
+        routeplanner: Option<Arc<dyn RoutePlanner>>,
+    ) -> Result<AudioAnalysis, String> {
+        if let Some(analysis) = self.cached(track) {
+            return Ok(analysis);
+        }
+
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|_| "Analysis is shutting down".to_owned())?;
+        // Another request may have finished the same track while this one
+        // waited for a permit.
+        if let Some(analysis) = self.cached(track) {
+            return Ok(analysis);
+        }
+
+        let analysis = tokio::time::timeout(self.timeout, self.run(track, routeplanner))
+            .await
+            .map_err(|_| format!("Analysis timed out after {}s", self.timeout.as_secs()))??;
+
+        if self.cache_size > 0 {
+            self.cache
+                .lock()
+                .insert(cache_key(track), analysis.clone(), self.cache_size);
+        }
+        Ok(analysis)
+    }
+
+    async fn run(
+        &self,
+        track: &TrackInfo,
+        routeplanner: Option<Arc<dyn RoutePlanner>>,
+    ) -> Result<AudioAnalysis, String> {
+        let playable = self
+            .source_manager
+            .resolve_track(track, routeplanner)
+            .await
+            .map_err(|e| format!("Failed to resolve track: {}", e))?;
+
+        let (frame_rx, cmd_tx, err_rx) = playable.start_decoding(self.player_config.clone());
+        let max_duration_ms = self.max_duration_ms;
+
+        // `cmd_tx` stays with this future, so a timeout drops it and stops the
+        // decoder, which in turn ends the blocking loop.
+        let analyzer = tokio::task::spawn_blocking(move || {
+            let mut analyzer = TrackAnalyzer::new();
+            while analyzer.duration_ms() < max_duration_ms {
+                match frame_rx.recv() {
+                    Ok(AudioFrame::Pcm(pcm) | AudioFrame::Passthrough { pcm, .. }) => {
+                        analyzer.push(&pcm);
+                    }
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+            analyzer
+        })
+        .await
+        .map_err(|e| format!("Analysis task failed: {}", e))?;
+        let _ = cmd_tx.send(DecoderCommand::Stop);
+
+        if analyzer.duration_ms() == 0 {
+            return Err(err_rx
+                .try_recv()
+                .unwrap_or_else(|_| "Track produced no audio".to_owned()));
+        }
+        Ok(analyzer.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analysis(analyzed_ms: u64) -> AudioAnalysis {
+        AudioAnalysis {
+            bpm: None,
+            key: None,
+            camelot: None,
+            loudness: None,
+            peak: None,
+            analyzed_ms,
+        }
+    }
+
+    #[test]
+    fn test_cache_evicts_the_oldest_result() {
+        let mut cache = AnalysisCache::default();
+        for i in 0..3 {
+            cache.insert(i.to_string(), analysis(i), 2);
+        }
+        cache.insert("1".to_string(), analysis(10), 2);
+
+        assert!(!cache.by_key.contains_key("0"));
+        assert_eq!(cache.by_key["1"].analyzed_ms, 10);
+        assert_eq!(cache.order, ["1", "2"]);
+    }
+}
@@ -23,6 +23,20 @@ pub const MAX_POOL_BYTES: usize = 2 * 1_024 * 1_024;
 pub const MAX_BUCKET_ENTRIES: usize = 8;
 pub const POOL_IDLE_CLEAR_SECS: u64 = 180;
 
+// ── Decoder worker pool ──────────────────────────────────────────────────────
+
+pub const DECODE_SLICE_PACKETS: usize = 8;
+pub const DECODE_IDLE_WAIT_MS: u64 = 5;
+pub const DECODE_LOAD_WINDOW_MS: u64 = 1_000;
+/// A worker stuck in one step for this long hands its other jobs to peers.
+pub const DECODE_STALL_MS: u64 = 200;
+
+// ── Playback ─────────────────────────────────────────────────────────────────
+
+/// How long a seek request waits for the decoder before replying with the
+/// requested position instead of the landed one.
+pub const SEEK_REPLY_TIMEOUT_MS: u64 = 2_000;
+
 // ── Audio mixer layers ───────────────────────────────────────────────────────
 
 pub const MAX_LAYERS: usize = 5;
@@ -38,6 +52,8 @@ pub const MAX_FETCH_RETRIES: u32 = 5;
 pub const WORKER_IDLE_MS: u64 = 50;
 pub const FETCH_WAIT_MS: u64 = 250;
 pub const PROBE_TIMEOUT_SECS: u64 = 10;
+/// A read that has waited this long for data fails instead of waiting on.
+pub const READ_STALL_TIMEOUT_SECS: u64 = 30;
 
 // ── HttpSource ───────────────────────────────────────────────────────────────
 
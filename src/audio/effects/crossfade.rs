@@ -6,11 +6,7 @@
 use flume::Receiver;
 
 use super::fade::FadeCurve;
-use crate::audio::{
-    RingBuffer,
-    buffer::PooledBuffer,
-    constants::{HALF_PI, INT16_MAX_F, INT16_MIN_F},
-};
+use crate::audio::{RingBuffer, buffer::PooledBuffer, constants::HALF_PI};
 
 pub struct CrossfadeController {
     sample_rate: u32,
@@ -105,7 +101,7 @@ impl CrossfadeController {
 
     /// Mix the buffered next track into `frame` if crossfade is active.
     /// Returns true if the fade finished during this call.
-    pub fn process(&mut self, frame: &mut [i16]) -> bool {
+    pub fn process(&mut self, frame: &mut [f32]) -> bool {
         let (elapsed, duration, curve) = match &self.active_fade {
             Some(s) => (s.elapsed_ms, s.duration_ms as f32, s.curve),
             None => return false,
@@ -152,8 +148,7 @@ impl CrossfadeController {
         let mut g_in = in_start;
 
         for (sample, &next_val) in frame.iter_mut().zip(next_samples_raw.iter()) {
-            let mixed = (*sample as f32 * g_out) + (next_val as f32 * g_in);
-            *sample = mixed.clamp(INT16_MIN_F, INT16_MAX_F) as i16;
+            *sample = *sample * g_out + next_val as f32 * g_in;
             g_out += step_out;
             g_in += step_in;
         }
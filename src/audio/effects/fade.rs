@@ -3,11 +3,13 @@
 //! Used by `FlowController` for crossfade fade-in / fade-out gain on the
 //! current track.
 
-use crate::audio::constants::{INT16_MAX_F, INT16_MIN_F};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum FadeCurve {
     Linear,
+    #[default]
     Sinusoidal,
 }
 
@@ -29,7 +31,7 @@ pub struct FadeEffect {
     fade_samples_elapsed: usize,
     fade_active: bool,
     curve: FadeCurve,
-    _channels: usize,
+    channels: usize,
 }
 
 impl FadeEffect {
@@ -42,7 +44,7 @@ impl FadeEffect {
             fade_samples_elapsed: 0,
             fade_active: false,
             curve: FadeCurve::Sinusoidal,
-            _channels: channels,
+            channels,
         }
     }
 
@@ -62,7 +64,9 @@ impl FadeEffect {
         }
         self.start_gain = self.current_gain;
         self.target_gain = target;
-        self.fade_samples_total = (sample_rate as u64 * duration_ms / 1000) as usize;
+        // Frames are interleaved, so the ramp is counted in samples of all channels.
+        self.fade_samples_total =
+            (sample_rate as u64 * duration_ms / 1000) as usize * self.channels;
         self.fade_samples_elapsed = 0;
         self.fade_active = self.fade_samples_total > 0;
         self.curve = curve;
@@ -72,12 +76,21 @@ impl FadeEffect {
         self.current_gain
     }
 
+    pub fn target_gain(&self) -> f32 {
+        self.target_gain
+    }
+
     pub fn is_done(&self) -> bool {
         !self.fade_active
     }
 
+    /// True when no ramp is running and the gain is 1.0.
+    pub fn is_unity(&self) -> bool {
+        !self.fade_active && (self.current_gain - 1.0).abs() < f32::EPSILON
+    }
+
     /// Process `frame` in-place.
-    pub fn process(&mut self, frame: &mut [i16]) {
+    pub fn process(&mut self, frame: &mut [f32]) {
         let sample_count = frame.len();
         if sample_count == 0 {
             return;
@@ -120,8 +133,7 @@ impl FadeEffect {
         let mut gain = gain_start;
 
         for s in frame.iter_mut() {
-            let out = (*s as f32 * gain).clamp(INT16_MIN_F, INT16_MAX_F);
-            *s = out.round() as i16;
+            *s *= gain;
             gain += step;
         }
     }
@@ -0,0 +1,106 @@
+//! `OutputLimiter` — last stage before the Opus encoder.
+//!
+//! Filters and effects run in `f32` without clamping, so peaks may exceed
+//! full scale. They are soft-limited once here and the mix is quantized to
+//! i16 with TPDF dither.
+
+use crate::audio::constants::{INT16_MAX_F, INT16_MIN_F};
+
+const LUT_SIZE: usize = 1024;
+/// Overshoot covered by the lookup table, in multiples of the headroom.
+const LUT_RANGE: f32 = 2.5;
+
+pub struct OutputLimiter {
+    threshold: f32,
+    headroom: f32,
+    softness: f32,
+    lut: [f32; LUT_SIZE],
+    seed: u32,
+}
+
+impl Default for OutputLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutputLimiter {
+    pub fn new() -> Self {
+        let softness = 0.4_f32;
+        let threshold = 0.95 * INT16_MAX_F;
+
+        let mut lut = [0.0_f32; LUT_SIZE];
+        for (i, val) in lut.iter_mut().enumerate() {
+            let overshoot = i as f32 / (LUT_SIZE - 1) as f32 * LUT_RANGE;
+            *val = 1.0 - (-overshoot * softness).exp();
+        }
+
+        Self {
+            threshold,
+            headroom: INT16_MAX_F - threshold,
+            softness,
+            lut,
+            seed: 0x9E37_79B9,
+        }
+    }
+
+    #[inline(always)]
+    fn limit(&self, value: f32) -> f32 {
+        let abs = value.abs();
+        if abs <= self.threshold {
+            return value;
+        }
+
+        let overshoot = (abs - self.threshold) / self.headroom;
+        let idx = (overshoot * (LUT_SIZE - 1) as f32 / LUT_RANGE) as usize;
+        let softened = match self.lut.get(idx) {
+            Some(v) => *v,
+            None => 1.0 - (-overshoot * self.softness).exp(),
+        };
+        value.signum() * (self.threshold + self.headroom * softened)
+    }
+
+    /// Uniform noise in `[-0.5, 0.5)` LSB (xorshift32).
+    #[inline(always)]
+    fn noise(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed as f32 / u32::MAX as f32 - 0.5
+    }
+
+    /// Limits `input` and writes it to `output` as i16. Digital silence is
+    /// left undithered.
+    pub fn process(&mut self, input: &[f32], output: &mut [i16]) {
+        for (out, &sample) in output.iter_mut().zip(input) {
+            if sample == 0.0 {
+                *out = 0;
+                continue;
+            }
+            let dither = self.noise() + self.noise();
+            *out = (self.limit(sample) + dither)
+                .round()
+                .clamp(INT16_MIN_F, INT16_MAX_F) as i16;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peaks_are_limited_and_quiet_samples_kept() {
+        let mut limiter = OutputLimiter::new();
+        let input = [0.0, 1000.0, -1000.0, 33_000.0, 40_000.0, -90_000.0];
+        let mut output = [0i16; 6];
+        limiter.process(&input, &mut output);
+
+        assert_eq!(output[0], 0);
+        assert!((output[1] - 1000).abs() <= 1);
+        assert!((output[2] + 1000).abs() <= 1);
+        assert!(output[3] > 31_000 && output[3] < output[4]);
+        assert!(output[4] < i16::MAX);
+        assert!(output[5] < -32_000);
+    }
+}
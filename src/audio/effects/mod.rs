@@ -1,5 +1,6 @@
 pub mod crossfade;
 pub mod fade;
+pub mod limiter;
 pub mod tape;
 pub mod volume;
 
@@ -15,7 +16,7 @@ pub trait TransitionEffect: Send {
     #[allow(clippy::too_many_arguments)]
     fn process(
         &mut self,
-        mix_buf: &mut [i32],
+        mix_buf: &mut [f32],
         i: &mut usize,
         out_len: usize,
         vol: f32,
@@ -79,16 +79,14 @@ impl TapeEffect {
         }
     }
 
-    pub fn process(&mut self, frame: &mut [i16]) {
+    pub fn process(&mut self, frame: &mut [f32]) {
         if frame.is_empty() || !self.is_active() {
             return;
         }
 
         let channels = self.channels;
 
-        for &s in frame.iter() {
-            self.input_buffer.push(s as f32 / 32767.0);
-        }
+        self.input_buffer.extend_from_slice(frame);
 
         let mut out_idx = 0;
         let sample_duration_ms = 1000.0 / self.sample_rate as f32;
@@ -109,19 +107,13 @@ impl TapeEffect {
             }
 
             if self.current_rate <= 0.01 && self.tape.is_none() {
-                while out_idx < frame.len() {
-                    frame[out_idx] = 0;
-                    out_idx += 1;
-                }
+                frame[out_idx..].fill(0.0);
                 break;
             }
 
             let i_pos = (self.read_pos.floor() as usize / channels) * channels;
             if i_pos + channels * 3 >= self.input_buffer.len() {
-                while out_idx < frame.len() {
-                    frame[out_idx] = 0;
-                    out_idx += 1;
-                }
+                frame[out_idx..].fill(0.0);
                 break;
             }
 
@@ -144,7 +136,7 @@ impl TapeEffect {
                         + (-p0 + 3.0 * p1 - 3.0 * p2 + p3) * frac * frac * frac);
 
                 if out_idx < frame.len() {
-                    frame[out_idx] = (val * 32767.0).clamp(-32768.0, 32767.0).round() as i16;
+                    frame[out_idx] = val;
                     out_idx += 1;
                 }
             }
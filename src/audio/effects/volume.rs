@@ -1,9 +1,6 @@
-//! `VolumeEffect` — per-sample gain with sinusoidal fade transitions and a
-//! soft exponential limiter.
+//! `VolumeEffect` — per-sample gain with sinusoidal fade transitions.
 //!
-//! Soft limiter prevents clipping.
-
-use crate::audio::constants::{INT16_MAX_F, INT16_MIN_F};
+//! Peaks above full scale are left to the `OutputLimiter` before encoding.
 
 /// Fade curve shapes.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -12,7 +9,7 @@ pub enum FadeCurve {
     Sinusoidal,
 }
 
-/// Per-frame volume processor with soft limiter and sinusoidal fade support.
+/// Per-frame volume processor with sinusoidal fade support.
 pub struct VolumeEffect {
     /// Currently applied gain (tracks toward `target_volume` during a fade).
     current_volume: f32,
@@ -24,31 +21,12 @@ pub struct VolumeEffect {
     fade_active: bool,
     fade_curve: FadeCurve,
 
-    _limiter_threshold: f32, // 0.0 – 1.0 relative to INT16_MAX
-    limiter_softness: f32,
-
-    threshold_value: f32,
-    limit_headroom: f32,
-
-    limiter_lut: [f32; 1024],
-
     _sample_rate: u32,
     channels: usize,
 }
 
 impl VolumeEffect {
     pub fn new(volume: f32, sample_rate: u32, channels: usize) -> Self {
-        let limiter_threshold = 0.95_f32;
-        let limiter_softness = 0.4_f32;
-        let threshold_value = limiter_threshold * INT16_MAX_F;
-        let limit_headroom = INT16_MAX_F - threshold_value;
-
-        let mut limiter_lut = [0.0_f32; 1024];
-        for (i, val) in limiter_lut.iter_mut().enumerate() {
-            let overshoot = i as f32 / 1023.0 * 2.5; // Max overshoot factor 2.5x headroom
-            *val = 1.0 - (-overshoot * limiter_softness).exp();
-        }
-
         let fade_frames_total = (sample_rate as usize * 1000) / 1000;
 
         Self {
@@ -59,11 +37,6 @@ impl VolumeEffect {
             fade_frames_elapsed: fade_frames_total, // start "done"
             fade_active: false,
             fade_curve: FadeCurve::Sinusoidal,
-            _limiter_threshold: limiter_threshold,
-            limiter_softness,
-            threshold_value,
-            limit_headroom,
-            limiter_lut,
             _sample_rate: sample_rate,
             channels,
         }
@@ -97,6 +70,11 @@ impl VolumeEffect {
         self.current_volume
     }
 
+    /// True when settled at full volume, so frames pass through untouched.
+    pub fn is_unity(&self) -> bool {
+        !self.fade_active && (self.target_volume - 1.0).abs() < f32::EPSILON
+    }
+
     fn curve_value(&self, t: f32) -> f32 {
         match self.fade_curve {
             FadeCurve::Linear => t,
@@ -104,29 +82,7 @@ impl VolumeEffect {
         }
     }
 
-    #[inline(always)]
-    fn apply_limiter(&self, value: f32) -> f32 {
-        let abs = value.abs();
-        if abs <= self.threshold_value || self.limit_headroom <= 0.0 {
-            return value;
-        }
-
-        let overshoot_raw = (abs - self.threshold_value) / self.limit_headroom;
-        // Normalize overshoot to LUT range [0, 2.5]
-        let lut_idx = (overshoot_raw * 1023.0 / 2.5) as usize;
-
-        let softened = if lut_idx < 1024 {
-            self.limiter_lut[lut_idx]
-        } else {
-            // Beyond LUT, effectively fully softened (approaches 1.0)
-            1.0 - (-overshoot_raw * self.limiter_softness).exp()
-        };
-
-        let limited = self.threshold_value + self.limit_headroom * softened;
-        value.signum() * limited.min(INT16_MAX_F)
-    }
-
-    pub fn process(&mut self, frame: &mut [i16]) {
+    pub fn process(&mut self, frame: &mut [f32]) {
         let sample_count = frame.len();
         if sample_count == 0 {
             return;
@@ -169,13 +125,7 @@ impl VolumeEffect {
         let mut gain = gain_start;
 
         for s in frame.iter_mut() {
-            let scaled = *s as f32 * gain;
-            if scaled.abs() > self.threshold_value {
-                let limited = self.apply_limiter(scaled);
-                *s = limited.clamp(INT16_MIN_F, INT16_MAX_F) as i16;
-            } else {
-                *s = scaled as i16;
-            }
+            *s *= gain;
             gain += step;
         }
     }
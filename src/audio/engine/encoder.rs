@@ -1,9 +1,23 @@
-use audiopus::{Application, Bitrate, Channels, SampleRate, coder::Encoder as OpusEncoder};
+use audiopus::{Application, Bitrate, Channels, SampleRate, Signal, coder::Encoder as OpusEncoder};
 
-use crate::common::types::AnyResult;
+use crate::{
+    audio::effects::limiter::OutputLimiter,
+    common::types::AnyResult,
+    config::player::{EncoderConfig, EncoderSignal},
+    gateway::constants::SILENCE_FRAME,
+};
+
+/// Below half an i16 step a frame dithers to nothing audible.
+const DTX_THRESHOLD: f32 = 0.5;
 
 pub struct Encoder {
     encoder: OpusEncoder,
+    limiter: OutputLimiter,
+    pcm: Vec<i16>,
+    /// Settings fully applied to the encoder.
+    config: EncoderConfig,
+    /// Last settings the encoder rejected, already reported.
+    rejected: Option<EncoderConfig>,
 }
 
 impl Encoder {
@@ -14,7 +28,61 @@ impl Encoder {
         encoder
             .set_bitrate(Bitrate::Auto)
             .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
-        Ok(Self { encoder })
+        Ok(Self {
+            encoder,
+            limiter: OutputLimiter::new(),
+            pcm: Vec::new(),
+            config: EncoderConfig::default(),
+            rejected: None,
+        })
+    }
+
+    /// Applies per-player settings; a no-op once they are in effect. Settings
+    /// the encoder rejects are retried on every call but reported only once.
+    pub fn configure(&mut self, config: &EncoderConfig) -> AnyResult<()> {
+        if self.config == *config {
+            return Ok(());
+        }
+        match self.apply(config) {
+            Ok(()) => {
+                self.config = *config;
+                self.rejected = None;
+                Ok(())
+            }
+            Err(_) if self.rejected == Some(*config) => Ok(()),
+            Err(e) => {
+                self.rejected = Some(*config);
+                Err(e)
+            }
+        }
+    }
+
+    fn apply(&mut self, config: &EncoderConfig) -> AnyResult<()> {
+        let map = |e: audiopus::Error| Box::new(e) as Box<dyn std::error::Error + Send + Sync>;
+
+        let bitrate = match config.effective_bitrate() {
+            Some(bps) => Bitrate::BitsPerSecond(bps as i32),
+            None => Bitrate::Auto,
+        };
+        self.encoder.set_bitrate(bitrate).map_err(map)?;
+        self.encoder.set_inband_fec(config.fec).map_err(map)?;
+        self.encoder
+            .set_packet_loss_perc(config.packet_loss_percent.min(100))
+            .map_err(map)?;
+        self.encoder
+            .set_signal(match config.signal {
+                EncoderSignal::Auto => Signal::Auto,
+                EncoderSignal::Music => Signal::Music,
+                EncoderSignal::Voice => Signal::Voice,
+            })
+            .map_err(map)?;
+        Ok(())
+    }
+
+    /// Whether a source Opus packet can stand in for an encoded frame under
+    /// the current settings.
+    pub fn accepts_passthrough(&self, packet: &[u8]) -> bool {
+        !self.config.fec && self.config.allows_packet(packet.len())
     }
 
     pub fn encode(&mut self, input: &[i16], output: &mut [u8]) -> AnyResult<usize> {
@@ -24,6 +92,31 @@ impl Encoder {
             .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
         Ok(size)
     }
+
+    /// Drops the encoder's history, so audio from before a stretch of
+    /// passthrough packets does not leak into the next encoded frame.
+    pub fn reset(&mut self) {
+        let _ = self.encoder.reset_state();
+    }
+
+    /// Encodes an unclamped `f32` mix, limiting and dithering it to i16 first.
+    /// With DTX on, a silent frame becomes Discord's bare silence packet.
+    pub fn encode_float(&mut self, input: &[f32], output: &mut [u8]) -> AnyResult<usize> {
+        if self.config.dtx
+            && output.len() >= SILENCE_FRAME.len()
+            && input.iter().all(|s| s.abs() < DTX_THRESHOLD)
+        {
+            output[..SILENCE_FRAME.len()].copy_from_slice(&SILENCE_FRAME);
+            return Ok(SILENCE_FRAME.len());
+        }
+
+        let mut pcm = std::mem::take(&mut self.pcm);
+        pcm.resize(input.len(), 0);
+        self.limiter.process(input, &mut pcm);
+        let result = self.encode(&pcm, output);
+        self.pcm = pcm;
+        result
+    }
 }
 
 #[cfg(test)]
@@ -107,6 +200,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encoder_configure_and_dtx() {
+        let mut encoder = Encoder::new().unwrap();
+        let config = EncoderConfig {
+            bitrate: Some(128_000),
+            channel_bitrate: Some(64_000),
+            fec: true,
+            packet_loss_percent: 10,
+            dtx: true,
+            signal: EncoderSignal::Music,
+        };
+        assert_eq!(config.effective_bitrate(), Some(64_000));
+        encoder.configure(&config).unwrap();
+        assert!(!encoder.accepts_passthrough(&[0u8; 100]));
+
+        let mut output = vec![0u8; 4000];
+        let size = encoder.encode_float(&[0.0; 960 * 2], &mut output).unwrap();
+        assert_eq!(&output[..size], &SILENCE_FRAME);
+
+        // Input is on the 16-bit scale; anything that rounds to zero is silent.
+        let size = encoder.encode_float(&[0.25; 960 * 2], &mut output).unwrap();
+        assert_eq!(&output[..size], &SILENCE_FRAME);
+
+        let size = encoder
+            .encode_float(&[1000.0; 960 * 2], &mut output)
+            .unwrap();
+        assert_ne!(&output[..size], &SILENCE_FRAME);
+    }
+
     #[test]
     fn test_encoder_output_varies_with_input() {
         let mut encoder1 = Encoder::new().unwrap();
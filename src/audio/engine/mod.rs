@@ -8,6 +8,22 @@ use crate::audio::frame::AudioFrame;
 
 pub trait Engine: Send {
     fn push(&mut self, frame: AudioFrame) -> bool;
+
+    /// Pushes `frame` only if the buffer has room, handing it back otherwise.
+    fn try_push(&mut self, frame: AudioFrame) -> Result<(), AudioFrame> {
+        self.push(frame);
+        Ok(())
+    }
+
+    /// Fraction of the downstream buffer currently occupied (0.0 – 1.0).
+    fn fill_level(&self) -> f32 {
+        0.0
+    }
+
+    /// Returns true once the consumer has gone away.
+    fn is_closed(&self) -> bool {
+        false
+    }
 }
 
 pub type BoxedEngine = Box<dyn Engine>;
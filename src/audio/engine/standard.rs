@@ -1,4 +1,4 @@
-use flume::Sender;
+use flume::{Sender, TrySendError};
 
 use super::Engine;
 use crate::audio::AudioFrame;
@@ -17,4 +17,23 @@ impl Engine for StandardEngine {
     fn push(&mut self, frame: AudioFrame) -> bool {
         self.frame_tx.send(frame).is_ok()
     }
+
+    fn try_push(&mut self, frame: AudioFrame) -> Result<(), AudioFrame> {
+        match self.frame_tx.try_send(frame) {
+            Err(TrySendError::Full(frame)) => Err(frame),
+            // A closed channel is reported through `is_closed`.
+            Ok(()) | Err(TrySendError::Disconnected(_)) => Ok(()),
+        }
+    }
+
+    fn fill_level(&self) -> f32 {
+        match self.frame_tx.capacity() {
+            Some(cap) if cap > 0 => self.frame_tx.len() as f32 / cap as f32,
+            _ => 0.0,
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.frame_tx.is_disconnected()
+    }
 }
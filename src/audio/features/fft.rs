@@ -0,0 +1,102 @@
+use std::f32::consts::PI;
+
+/// Radix-2 real-input FFT returning Hann-windowed magnitude spectra.
+pub struct Spectrum {
+    size: usize,
+    window: Vec<f32>,
+    twiddles: Vec<(f32, f32)>,
+    bit_rev: Vec<usize>,
+    re: Vec<f32>,
+    im: Vec<f32>,
+}
+
+impl Spectrum {
+    /// `size` must be a power of two.
+    pub fn new(size: usize) -> Self {
+        assert!(size.is_power_of_two() && size >= 2);
+        let bits = size.trailing_zeros();
+        Self {
+            size,
+            window: (0..size)
+                .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / size as f32).cos())
+                .collect(),
+            twiddles: (0..size / 2)
+                .map(|k| {
+                    let angle = -2.0 * PI * k as f32 / size as f32;
+                    (angle.cos(), angle.sin())
+                })
+                .collect(),
+            bit_rev: (0..size)
+                .map(|i| i.reverse_bits() >> (usize::BITS - bits))
+                .collect(),
+            re: vec![0.0; size],
+            im: vec![0.0; size],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of bins written by [`Self::magnitudes`].
+    pub fn bins(&self) -> usize {
+        self.size / 2 + 1
+    }
+
+    /// Windows `input` (zero-padded to the FFT size) and writes the magnitude
+    /// of each bin from DC to Nyquist into `out`.
+    pub fn magnitudes(&mut self, input: &[f32], out: &mut [f32]) {
+        for i in 0..self.size {
+            let j = self.bit_rev[i];
+            self.re[j] = input.get(i).copied().unwrap_or(0.0) * self.window[i];
+            self.im[j] = 0.0;
+        }
+
+        let mut len = 2;
+        while len <= self.size {
+            let half = len / 2;
+            let step = self.size / len;
+            for start in (0..self.size).step_by(len) {
+                for k in 0..half {
+                    let (wr, wi) = self.twiddles[k * step];
+                    let a = start + k;
+                    let b = a + half;
+                    let tr = self.re[b] * wr - self.im[b] * wi;
+                    let ti = self.re[b] * wi + self.im[b] * wr;
+                    self.re[b] = self.re[a] - tr;
+                    self.im[b] = self.im[a] - ti;
+                    self.re[a] += tr;
+                    self.im[a] += ti;
+                }
+            }
+            len *= 2;
+        }
+
+        for (k, m) in out.iter_mut().take(self.bins()).enumerate() {
+            *m = (self.re[k] * self.re[k] + self.im[k] * self.im[k]).sqrt();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sine_peaks_in_its_bin() {
+        let mut spectrum = Spectrum::new(256);
+        let input: Vec<f32> = (0..256)
+            .map(|i| (2.0 * PI * 16.0 * i as f32 / 256.0).sin())
+            .collect();
+        let mut out = vec![0.0; spectrum.bins()];
+        spectrum.magnitudes(&input, &mut out);
+
+        let peak = out
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i);
+        assert_eq!(peak, Some(16));
+        assert!(out[16] > 10.0 * out[40]);
+    }
+}
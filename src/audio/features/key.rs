@@ -0,0 +1,118 @@
+//! Musical key from a chromagram matched against Krumhansl-Kessler profiles.
+
+const MAJOR_PROFILE: [f64; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+const MINOR_PROFILE: [f64; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+const PITCH_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+/// Lowest and highest frequencies folded into the chromagram (C2 to C7).
+pub const CHROMA_MIN_HZ: f32 = 65.4;
+pub const CHROMA_MAX_HZ: f32 = 2093.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    /// Pitch class of the tonic, 0 = C.
+    pub tonic: usize,
+    pub minor: bool,
+}
+
+impl Key {
+    pub fn name(&self) -> String {
+        let mode = if self.minor { "minor" } else { "major" };
+        format!("{} {}", PITCH_NAMES[self.tonic], mode)
+    }
+
+    /// Position on the Camelot wheel used for harmonic mixing, e.g. `8A`.
+    pub fn camelot(&self) -> String {
+        // Minor keys share a number with their relative major.
+        let major_tonic = if self.minor {
+            (self.tonic + 3) % 12
+        } else {
+            self.tonic
+        };
+        let number = (major_tonic * 7 + 7) % 12 + 1;
+        format!("{}{}", number, if self.minor { 'A' } else { 'B' })
+    }
+}
+
+/// Pitch class of a frequency, 0 = C.
+pub fn pitch_class(hz: f32) -> usize {
+    let midi = 69.0 + 12.0 * (hz / 440.0).log2();
+    (midi.round() as i64).rem_euclid(12) as usize
+}
+
+fn correlation(a: &[f64; 12], b: &[f64; 12]) -> f64 {
+    let mean_a = a.iter().sum::<f64>() / 12.0;
+    let mean_b = b.iter().sum::<f64>() / 12.0;
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    if var_a <= 0.0 || var_b <= 0.0 {
+        return 0.0;
+    }
+    cov / (var_a * var_b).sqrt()
+}
+
+/// The key whose profile best matches `chroma`, or `None` when the
+/// chromagram is flat.
+pub fn estimate_key(chroma: &[f64; 12]) -> Option<Key> {
+    let mut best: Option<(Key, f64)> = None;
+    for tonic in 0..12 {
+        let mut rotated = [0.0; 12];
+        for (i, r) in rotated.iter_mut().enumerate() {
+            *r = chroma[(i + tonic) % 12];
+        }
+        for (minor, profile) in [(false, &MAJOR_PROFILE), (true, &MINOR_PROFILE)] {
+            let score = correlation(&rotated, profile);
+            if best.is_none_or(|(_, s)| score > s) {
+                best = Some((Key { tonic, minor }, score));
+            }
+        }
+    }
+    best.filter(|&(_, score)| score > 0.0).map(|(key, _)| key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triads_and_camelot() {
+        // C, E and G dominate: C major.
+        let mut chroma = [0.1; 12];
+        chroma[0] = 1.0;
+        chroma[4] = 0.8;
+        chroma[7] = 0.9;
+        let key = estimate_key(&chroma).unwrap();
+        assert_eq!(key.name(), "C major");
+        assert_eq!(key.camelot(), "8B");
+
+        // A, C and E: A minor, which shares 8 with C major.
+        let mut chroma = [0.1; 12];
+        chroma[9] = 1.0;
+        chroma[0] = 0.8;
+        chroma[4] = 0.9;
+        let key = estimate_key(&chroma).unwrap();
+        assert_eq!(key.name(), "A minor");
+        assert_eq!(key.camelot(), "8A");
+    }
+
+    #[test]
+    fn test_pitch_class_of_a440() {
+        assert_eq!(pitch_class(440.0), 9);
+        assert_eq!(pitch_class(261.63), 0);
+    }
+
+    #[test]
+    fn test_flat_chroma_has_no_key() {
+        assert_eq!(estimate_key(&[1.0; 12]), None);
+    }
+}
@@ -0,0 +1,154 @@
+//! Integrated loudness (ITU-R BS.1770 / EBU R128) and sample peak.
+
+/// 100 ms at 48 kHz; gating blocks are four of these.
+const SUB_BLOCK: usize = 4800;
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+
+#[derive(Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    z: [f64; 2],
+}
+
+impl Biquad {
+    const fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Self { b, a, z: [0.0; 2] }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[0] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[1] * y;
+        y
+    }
+}
+
+/// K-weighting at 48 kHz: a high shelf followed by a high-pass.
+fn k_weighting() -> [Biquad; 2] {
+    [
+        Biquad::new(
+            [
+                1.535_124_859_586_97,
+                -2.691_696_189_406_38,
+                1.198_392_810_852_85,
+            ],
+            [-1.690_659_293_182_41, 0.732_480_774_215_85],
+        ),
+        Biquad::new(
+            [1.0, -2.0, 1.0],
+            [-1.990_047_454_833_98, 0.990_072_250_366_21],
+        ),
+    ]
+}
+
+fn lufs(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.log10()
+}
+
+pub struct LoudnessMeter {
+    filters: [[Biquad; 2]; 2],
+    sum: f64,
+    count: usize,
+    /// K-weighted power of each 100 ms sub-block, summed over channels.
+    sub_blocks: Vec<f64>,
+    peak: f32,
+}
+
+impl Default for LoudnessMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoudnessMeter {
+    pub fn new() -> Self {
+        Self {
+            filters: [k_weighting(), k_weighting()],
+            sum: 0.0,
+            count: 0,
+            sub_blocks: Vec::new(),
+            peak: 0.0,
+        }
+    }
+
+    /// Feeds interleaved stereo samples in `[-1, 1]`.
+    pub fn push(&mut self, samples: &[f32]) {
+        for pair in samples.chunks_exact(2) {
+            for (ch, &s) in pair.iter().enumerate() {
+                self.peak = self.peak.max(s.abs());
+                let [shelf, high_pass] = &mut self.filters[ch];
+                let y = high_pass.process(shelf.process(s as f64));
+                self.sum += y * y;
+            }
+            self.count += 1;
+            if self.count == SUB_BLOCK {
+                self.sub_blocks.push(self.sum / SUB_BLOCK as f64);
+                self.sum = 0.0;
+                self.count = 0;
+            }
+        }
+    }
+
+    /// Gated integrated loudness in LUFS, or `None` for silence or audio
+    /// shorter than one 400 ms block.
+    pub fn integrated(&self) -> Option<f64> {
+        let blocks: Vec<f64> = self
+            .sub_blocks
+            .windows(4)
+            .map(|w| w.iter().sum::<f64>() / 4.0)
+            .filter(|&p| p > 0.0 && lufs(p) > ABSOLUTE_GATE_LUFS)
+            .collect();
+        if blocks.is_empty() {
+            return None;
+        }
+
+        let mean = blocks.iter().sum::<f64>() / blocks.len() as f64;
+        let relative_gate = lufs(mean) + RELATIVE_GATE_LU;
+        let gated: Vec<f64> = blocks
+            .into_iter()
+            .filter(|&p| lufs(p) > relative_gate)
+            .collect();
+        if gated.is_empty() {
+            return None;
+        }
+        Some(lufs(gated.iter().sum::<f64>() / gated.len() as f64))
+    }
+
+    /// Highest absolute sample in dBFS, or `None` for digital silence.
+    pub fn peak_db(&self) -> Option<f64> {
+        (self.peak > 0.0).then(|| 20.0 * (self.peak as f64).log10())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_scale_stereo_sine_reads_about_zero_lufs() {
+        // A 1 kHz sine at 0 dBFS in both channels measures -3.01 LUFS per
+        // channel, so about 0 LUFS for the pair.
+        let mut meter = LoudnessMeter::new();
+        let samples: Vec<f32> = (0..48_000 * 2)
+            .flat_map(|i| {
+                let s = (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 48_000.0).sin();
+                [s, s]
+            })
+            .collect();
+        meter.push(&samples);
+
+        let lufs = meter.integrated().unwrap();
+        assert!(lufs.abs() < 0.2, "got {lufs}");
+        assert!(meter.peak_db().unwrap().abs() < 0.01);
+    }
+
+    #[test]
+    fn test_silence_has_no_loudness() {
+        let mut meter = LoudnessMeter::new();
+        meter.push(&vec![0.0; 48_000 * 2]);
+        assert_eq!(meter.integrated(), None);
+        assert_eq!(meter.peak_db(), None);
+    }
+}
@@ -0,0 +1,249 @@
+//! Live level and spectrum metering of a player's output.
+
+use super::Spectrum;
+use crate::{
+    audio::constants::{MIXER_CHANNELS, TARGET_SAMPLE_RATE},
+    config::player::MeterConfig,
+    protocol::analysis::AudioLevels,
+};
+
+const FFT_SIZE: usize = 1024;
+/// Mixer output is on the 16-bit scale; levels are relative to its full scale.
+const FULL_SCALE: f32 = 32_768.0;
+/// Floor for reported levels; anything quieter reads as this.
+const FLOOR_DB: f32 = -96.0;
+const LOWEST_BAND_HZ: f32 = 40.0;
+const HIGHEST_BAND_HZ: f32 = 16_000.0;
+
+fn to_db(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return FLOOR_DB;
+    }
+    (20.0 * amplitude.log10()).max(FLOOR_DB)
+}
+
+/// Accumulates stereo frames and reports their levels once per interval.
+/// The spectrum is only computed when a report is due, so an enabled meter
+/// costs little more than a sum of squares per sample.
+pub struct LevelMeter {
+    config: MeterConfig,
+    spectrum: Spectrum,
+    /// Most recent mono samples, oldest first.
+    recent: Vec<f32>,
+    magnitudes: Vec<f32>,
+    /// FFT bin range `[start, end)` of each band.
+    bands: Vec<(usize, usize)>,
+    sum_sq: [f64; MIXER_CHANNELS],
+    peak: [f32; MIXER_CHANNELS],
+    frames: usize,
+    interval_frames: usize,
+}
+
+impl Default for LevelMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LevelMeter {
+    pub fn new() -> Self {
+        let spectrum = Spectrum::new(FFT_SIZE);
+        let bins = spectrum.bins();
+        let mut meter = Self {
+            config: MeterConfig::default(),
+            spectrum,
+            recent: Vec::with_capacity(FFT_SIZE),
+            magnitudes: vec![0.0; bins],
+            bands: Vec::new(),
+            sum_sq: [0.0; MIXER_CHANNELS],
+            peak: [0.0; MIXER_CHANNELS],
+            frames: 0,
+            interval_frames: 0,
+        };
+        meter.apply(MeterConfig::default());
+        meter
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Applies `config`, restarting the current interval if it changed.
+    pub fn configure(&mut self, config: &MeterConfig) {
+        if self.config != *config {
+            self.apply(*config);
+        }
+    }
+
+    fn apply(&mut self, config: MeterConfig) {
+        self.config = config;
+        self.interval_frames = (config.interval_ms() * TARGET_SAMPLE_RATE as u64 / 1000) as usize;
+        self.bands = band_bins(config.bands(), self.spectrum.bins());
+        self.reset();
+    }
+
+    fn reset(&mut self) {
+        self.recent.clear();
+        self.sum_sq = [0.0; MIXER_CHANNELS];
+        self.peak = [0.0; MIXER_CHANNELS];
+        self.frames = 0;
+    }
+
+    /// Feeds interleaved stereo samples on the 16-bit scale. Returns the
+    /// levels of the interval that just completed, if any.
+    pub fn push(&mut self, pcm: &[f32]) -> Option<AudioLevels> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        for pair in pcm.chunks_exact(MIXER_CHANNELS) {
+            for (ch, &s) in pair.iter().enumerate() {
+                let s = s / FULL_SCALE;
+                self.sum_sq[ch] += (s * s) as f64;
+                self.peak[ch] = self.peak[ch].max(s.abs());
+            }
+        }
+        self.frames += pcm.len() / MIXER_CHANNELS;
+
+        let mono = pcm
+            .chunks_exact(MIXER_CHANNELS)
+            .map(|pair| (pair[0] + pair[1]) * 0.5 / FULL_SCALE);
+        let keep = (pcm.len() / MIXER_CHANNELS).min(FFT_SIZE);
+        let overflow = (self.recent.len() + keep).saturating_sub(FFT_SIZE);
+        self.recent.drain(..overflow);
+        self.recent
+            .extend(mono.skip(pcm.len() / MIXER_CHANNELS - keep));
+
+        if self.frames < self.interval_frames {
+            return None;
+        }
+
+        let levels = self.report();
+        self.sum_sq = [0.0; MIXER_CHANNELS];
+        self.peak = [0.0; MIXER_CHANNELS];
+        self.frames = 0;
+        Some(levels)
+    }
+
+    fn report(&mut self) -> AudioLevels {
+        let frames = self.frames.max(1) as f64;
+        let rms = self.sum_sq.map(|sum| to_db((sum / frames).sqrt() as f32));
+        let peak = self.peak.map(to_db);
+
+        self.spectrum.magnitudes(&self.recent, &mut self.magnitudes);
+        // A full-scale sine peaks at a quarter of the FFT size under a Hann
+        // window, so this scales bins to amplitudes in dBFS.
+        let scale = 4.0 / FFT_SIZE as f32;
+        let spectrum = self
+            .bands
+            .iter()
+            .map(|&(start, end)| {
+                let peak = self.magnitudes[start..end]
+                    .iter()
+                    .fold(0.0f32, |acc, &m| acc.max(m));
+                to_db(peak * scale)
+            })
+            .collect();
+
+        AudioLevels {
+            rms,
+            peak,
+            spectrum,
+        }
+    }
+}
+
+/// Splits the spectrum into `count` log-spaced bands, each covering at least
+/// one bin.
+fn band_bins(count: usize, bins: usize) -> Vec<(usize, usize)> {
+    let hz_per_bin = TARGET_SAMPLE_RATE as f32 / FFT_SIZE as f32;
+    let ratio = (HIGHEST_BAND_HZ / LOWEST_BAND_HZ).powf(1.0 / count as f32);
+    let mut start = (LOWEST_BAND_HZ / hz_per_bin).floor() as usize;
+    (1..=count)
+        .map(|i| {
+            let edge = LOWEST_BAND_HZ * ratio.powi(i as i32);
+            let end = ((edge / hz_per_bin).ceil() as usize)
+                .max(start + 1)
+                .min(bins);
+            let band = (start.min(end - 1), end);
+            start = end;
+            band
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(interval_ms: u64, bands: usize) -> MeterConfig {
+        MeterConfig {
+            enabled: true,
+            interval_ms,
+            bands,
+        }
+    }
+
+    fn sine(hz: f32, amplitude: f32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .flat_map(|i| {
+                let s = amplitude
+                    * FULL_SCALE
+                    * (2.0 * std::f32::consts::PI * hz * i as f32 / TARGET_SAMPLE_RATE as f32)
+                        .sin();
+                [s, s]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reports_once_per_interval() {
+        let mut meter = LevelMeter::new();
+        meter.configure(&enabled(100, 16));
+        let frame = sine(1000.0, 0.5, 960);
+
+        let reports = (0..20).filter_map(|_| meter.push(&frame)).count();
+        assert_eq!(reports, 4);
+    }
+
+    #[test]
+    fn test_sine_levels_and_spectrum() {
+        let mut meter = LevelMeter::new();
+        meter.configure(&enabled(20, 24));
+        let levels = meter.push(&sine(1000.0, 0.5, 960)).unwrap();
+
+        // A sine's RMS is 3 dB below its peak.
+        assert!((levels.peak[0] - -6.02).abs() < 0.1, "{:?}", levels.peak);
+        assert!((levels.rms[1] - -9.03).abs() < 0.1, "{:?}", levels.rms);
+        assert_eq!(levels.spectrum.len(), 24);
+
+        let loudest = levels
+            .spectrum
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i)
+            .unwrap();
+        let (start, end) = band_bins(24, FFT_SIZE / 2 + 1)[loudest];
+        let hz_per_bin = TARGET_SAMPLE_RATE as f32 / FFT_SIZE as f32;
+        assert!((start as f32 * hz_per_bin..=end as f32 * hz_per_bin).contains(&1000.0));
+        assert!((levels.spectrum[loudest] - -6.02).abs() < 1.5);
+    }
+
+    #[test]
+    fn test_disabled_meter_stays_quiet() {
+        let mut meter = LevelMeter::new();
+        assert!(!meter.is_enabled());
+        assert!(meter.push(&sine(1000.0, 0.5, 960 * 10)).is_none());
+    }
+
+    #[test]
+    fn test_bands_are_contiguous_and_non_empty() {
+        let bands = band_bins(64, FFT_SIZE / 2 + 1);
+        assert_eq!(bands.len(), 64);
+        for pair in bands.windows(2) {
+            assert!(pair[0].0 < pair[0].1);
+            assert!(pair[0].1 <= pair[1].0 + 1);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Features measured on decoded audio: live levels and spectrum, and the
+//! tempo, key, loudness and peak reported by track analysis.
+
+pub mod fft;
+pub mod key;
+pub mod loudness;
+pub mod meter;
+pub mod tempo;
+
+pub use fft::Spectrum;
+pub use loudness::LoudnessMeter;
+pub use meter::LevelMeter;
+
+use crate::{
+    audio::constants::{MIXER_CHANNELS, TARGET_SAMPLE_RATE},
+    protocol::analysis::AudioAnalysis,
+};
+
+/// Tempo and key work on a mono mix decimated to 12 kHz.
+const DECIMATION: usize = 4;
+const ANALYSIS_RATE: f32 = (TARGET_SAMPLE_RATE as usize / DECIMATION) as f32;
+const FFT_SIZE: usize = 2048;
+const HOP: usize = 256;
+
+/// Accumulates 48 kHz stereo PCM and reports its features at the end.
+pub struct TrackAnalyzer {
+    loudness: LoudnessMeter,
+    spectrum: Spectrum,
+    float: Vec<f32>,
+    decimate_sum: f32,
+    decimate_count: usize,
+    window: Vec<f32>,
+    magnitudes: Vec<f32>,
+    previous: Vec<f32>,
+    onsets: Vec<f32>,
+    chroma: [f64; 12],
+    /// Pitch class of each FFT bin inside the chroma range.
+    bin_classes: Vec<Option<usize>>,
+    frames: u64,
+}
+
+impl Default for TrackAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrackAnalyzer {
+    pub fn new() -> Self {
+        let spectrum = Spectrum::new(FFT_SIZE);
+        let bins = spectrum.bins();
+        let bin_classes = (0..bins)
+            .map(|bin| {
+                let hz = bin as f32 * ANALYSIS_RATE / FFT_SIZE as f32;
+                (key::CHROMA_MIN_HZ..=key::CHROMA_MAX_HZ)
+                    .contains(&hz)
+                    .then(|| key::pitch_class(hz))
+            })
+            .collect();
+        Self {
+            loudness: LoudnessMeter::new(),
+            spectrum,
+            float: Vec::new(),
+            decimate_sum: 0.0,
+            decimate_count: 0,
+            window: Vec::with_capacity(FFT_SIZE),
+            magnitudes: vec![0.0; bins],
+            previous: vec![0.0; bins],
+            onsets: Vec::new(),
+            chroma: [0.0; 12],
+            bin_classes,
+            frames: 0,
+        }
+    }
+
+    /// Milliseconds of audio pushed so far.
+    pub fn duration_ms(&self) -> u64 {
+        self.frames * 1000 / TARGET_SAMPLE_RATE as u64
+    }
+
+    /// Feeds interleaved 48 kHz stereo PCM.
+    pub fn push(&mut self, pcm: &[i16]) {
+        let mut float = std::mem::take(&mut self.float);
+        float.clear();
+        float.extend(pcm.iter().map(|&s| s as f32 / 32768.0));
+        self.loudness.push(&float);
+
+        for pair in float.chunks_exact(MIXER_CHANNELS) {
+            self.frames += 1;
+            self.decimate_sum += (pair[0] + pair[1]) * 0.5;
+            self.decimate_count += 1;
+            if self.decimate_count == DECIMATION {
+                let sample = self.decimate_sum / DECIMATION as f32;
+                self.decimate_sum = 0.0;
+                self.decimate_count = 0;
+                self.push_mono(sample);
+            }
+        }
+        self.float = float;
+    }
+
+    fn push_mono(&mut self, sample: f32) {
+        self.window.push(sample);
+        if self.window.len() < FFT_SIZE {
+            return;
+        }
+
+        self.spectrum.magnitudes(&self.window, &mut self.magnitudes);
+        self.window.drain(..HOP);
+
+        // Spectral flux on a log scale: how much louder each bin got.
+        let mut flux = 0.0;
+        for (bin, (&m, prev)) in self
+            .magnitudes
+            .iter()
+            .zip(self.previous.iter_mut())
+            .enumerate()
+        {
+            let level = (1.0 + m).ln();
+            flux += (level - *prev).max(0.0);
+            *prev = level;
+            if let Some(class) = self.bin_classes[bin] {
+                self.chroma[class] += (m * m) as f64;
+            }
+        }
+        self.onsets.push(flux);
+    }
+
+    pub fn finish(self) -> AudioAnalysis {
+        let round = |v: f64, places: i32| {
+            let scale = 10f64.powi(places);
+            (v * scale).round() / scale
+        };
+        let key = key::estimate_key(&self.chroma);
+        let onset_rate = ANALYSIS_RATE as f64 / HOP as f64;
+
+        AudioAnalysis {
+            bpm: tempo::estimate_bpm(&self.onsets, onset_rate).map(|v| round(v, 1)),
+            key: key.map(|k| k.name()),
+            camelot: key.map(|k| k.camelot()),
+            loudness: self.loudness.integrated().map(|v| round(v, 2)),
+            peak: self.loudness.peak_db().map(|v| round(v, 2)),
+            analyzed_ms: self.duration_ms(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pulsed_chord_analysis() {
+        // A C major chord pulsed at 120 BPM for 20 seconds.
+        let notes = [261.63f32, 329.63, 392.0];
+        let mut analyzer = TrackAnalyzer::new();
+        let rate = TARGET_SAMPLE_RATE as f32;
+        let pcm: Vec<i16> = (0..TARGET_SAMPLE_RATE as usize * 20)
+            .flat_map(|i| {
+                let t = i as f32 / rate;
+                let envelope = (-(t % 0.5) * 12.0).exp();
+                let tone: f32 = notes
+                    .iter()
+                    .map(|&f| (2.0 * std::f32::consts::PI * f * t).sin())
+                    .sum();
+                let s = (tone / 3.0 * envelope * 16_000.0) as i16;
+                [s, s]
+            })
+            .collect();
+        for chunk in pcm.chunks(1920) {
+            analyzer.push(chunk);
+        }
+
+        assert_eq!(analyzer.duration_ms(), 20_000);
+        let analysis = analyzer.finish();
+        let bpm = analysis.bpm.unwrap();
+        assert!((bpm - 120.0).abs() < 2.0, "got {bpm}");
+        assert_eq!(analysis.key.as_deref(), Some("C major"));
+        assert!(analysis.loudness.is_some());
+        assert!(analysis.peak.unwrap() < 0.0);
+    }
+}
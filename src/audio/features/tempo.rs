@@ -0,0 +1,87 @@
+//! Tempo from the autocorrelation of a spectral-flux onset envelope.
+
+const MIN_BPM: f64 = 60.0;
+const MAX_BPM: f64 = 200.0;
+/// Tempo the octave weighting centres on; halves and doubles of a beat are
+/// otherwise equally likely.
+const PREFERRED_BPM: f64 = 120.0;
+/// Seconds of onsets needed for an estimate.
+const MIN_SECONDS: f64 = 6.0;
+
+/// Estimates beats per minute from an onset envelope sampled at `rate` Hz.
+pub fn estimate_bpm(onsets: &[f32], rate: f64) -> Option<f64> {
+    if (onsets.len() as f64) < MIN_SECONDS * rate {
+        return None;
+    }
+
+    let mean = onsets.iter().map(|&o| o as f64).sum::<f64>() / onsets.len() as f64;
+    let envelope: Vec<f64> = onsets.iter().map(|&o| o as f64 - mean).collect();
+
+    let min_lag = (rate * 60.0 / MAX_BPM).floor().max(1.0) as usize;
+    let max_lag = (rate * 60.0 / MIN_BPM).ceil() as usize;
+    if max_lag + 1 >= envelope.len() {
+        return None;
+    }
+
+    let correlation: Vec<f64> = (min_lag - 1..=max_lag + 1)
+        .map(|lag| {
+            envelope
+                .iter()
+                .zip(&envelope[lag..])
+                .map(|(a, b)| a * b)
+                .sum::<f64>()
+                / (envelope.len() - lag) as f64
+        })
+        .collect();
+
+    let weight = |lag: f64| {
+        let octaves = (rate * 60.0 / lag / PREFERRED_BPM).log2();
+        (-0.5 * octaves * octaves).exp()
+    };
+
+    let (best, score) = (1..correlation.len() - 1)
+        .map(|i| (i, correlation[i] * weight((min_lag - 1 + i) as f64)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    if score <= 0.0 {
+        return None;
+    }
+
+    // Parabolic interpolation around the peak for a fractional lag.
+    let (y0, y1, y2) = (
+        correlation[best - 1],
+        correlation[best],
+        correlation[best + 1],
+    );
+    let denom = y0 - 2.0 * y1 + y2;
+    let offset = if denom.abs() > f64::EPSILON {
+        (0.5 * (y0 - y2) / denom).clamp(-0.5, 0.5)
+    } else {
+        0.0
+    };
+    let lag = (min_lag - 1 + best) as f64 + offset;
+    Some(rate * 60.0 / lag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_click_track_tempo() {
+        let rate = 46.875;
+        let onsets: Vec<f32> = (0..(rate * 30.0) as usize)
+            .map(|i| {
+                let beat = (i as f64 / rate * 2.0).fract();
+                if beat < 1.0 / rate * 2.0 { 1.0 } else { 0.0 }
+            })
+            .collect();
+
+        let bpm = estimate_bpm(&onsets, rate).unwrap();
+        assert!((bpm - 120.0).abs() < 2.0, "got {bpm}");
+    }
+
+    #[test]
+    fn test_too_short_for_an_estimate() {
+        assert_eq!(estimate_bpm(&[1.0; 100], 46.875), None);
+    }
+}
@@ -24,19 +24,16 @@ impl ChannelMixFilter {
 }
 
 impl AudioFilter for ChannelMixFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         let num_frames = samples.len() / 2;
 
         for frame in 0..num_frames {
             let offset = frame * 2;
-            let left = samples[offset] as f64;
-            let right = samples[offset + 1] as f64;
+            let left = samples[offset];
+            let right = samples[offset + 1];
 
-            let new_left = left * self.left_to_left as f64 + right * self.right_to_left as f64;
-            let new_right = left * self.left_to_right as f64 + right * self.right_to_right as f64;
-
-            samples[offset] = (new_left as i32).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
-            samples[offset + 1] = (new_right as i32).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+            samples[offset] = left * self.left_to_left + right * self.right_to_left;
+            samples[offset + 1] = left * self.left_to_right + right * self.right_to_right;
         }
     }
 
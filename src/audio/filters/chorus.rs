@@ -57,7 +57,7 @@ impl ChorusFilter {
 }
 
 impl AudioFilter for ChorusFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         if self.rate == 0.0 || self.depth == 0.0 || self.mix == 0.0 {
             return;
         }
@@ -68,8 +68,8 @@ impl AudioFilter for ChorusFilter {
         let center_delay_samples2 = center_delay_samples * 1.2;
 
         for chunk in samples.chunks_exact_mut(2) {
-            let left_in = chunk[0] as f32;
-            let right_in = chunk[1] as f32;
+            let left_in = chunk[0];
+            let right_in = chunk[1];
 
             let lfo1_l = self.lfos[0].get_value() as f32;
             let lfo1_r = self.lfos[1].get_value() as f32;
@@ -104,8 +104,8 @@ impl AudioFilter for ChorusFilter {
                 (right_in + delayed2_r * self.feedback).clamp(i16::MIN as f32, i16::MAX as f32),
             );
 
-            chunk[0] = final_left.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
-            chunk[1] = final_right.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+            chunk[0] = final_left;
+            chunk[1] = final_right;
         }
     }
 
@@ -32,14 +32,14 @@ impl CompressorFilter {
 }
 
 impl AudioFilter for CompressorFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         let attack_coef = (-1.0 / (self.attack * 48000.0)).exp();
         let release_coef = (-1.0 / (self.release * 48000.0)).exp();
         let makeup_gain = db_to_gain(self.makeup_gain);
 
         for chunk in samples.chunks_exact_mut(2) {
-            let left_in = chunk[0] as f32 / 32768.0;
-            let right_in = chunk[1] as f32 / 32768.0;
+            let left_in = chunk[0] / 32768.0;
+            let right_in = chunk[1] / 32768.0;
 
             let abs_sample = left_in.abs().max(right_in.abs());
 
@@ -58,8 +58,8 @@ impl AudioFilter for CompressorFilter {
 
             let gain = db_to_gain(reduction_db) * makeup_gain;
 
-            chunk[0] = (left_in * gain * 32768.0).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
-            chunk[1] = (right_in * gain * 32768.0).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+            chunk[0] = left_in * gain * 32768.0;
+            chunk[1] = right_in * gain * 32768.0;
         }
     }
 
@@ -39,7 +39,7 @@ impl DistortionFilter {
 }
 
 impl AudioFilter for DistortionFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         let num_frames = samples.len() / 2;
 
         for frame in 0..num_frames {
@@ -71,8 +71,7 @@ impl AudioFilter for DistortionFilter {
                 }
 
                 distorted = (distorted * self.scale as f64 + self.offset as f64) * MAX_INT_16;
-                samples[offset_idx + ch] =
-                    (distorted as i32).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+                samples[offset_idx + ch] = distorted as f32;
             }
         }
     }
@@ -8,7 +8,7 @@ pub struct EchoFilter {
 
     // Buffer layout: interleaved L/R samples, so length is frames * 2.
     // 1 second at 48000Hz = 96000 samples.
-    buffer: VecDeque<i16>,
+    buffer: VecDeque<f32>,
     delay_samples: usize,
 }
 
@@ -22,7 +22,7 @@ impl EchoFilter {
         let samples = frames * 2;
 
         let mut buffer = VecDeque::with_capacity(samples);
-        buffer.extend(std::iter::repeat_n(0, samples));
+        buffer.extend(std::iter::repeat_n(0.0, samples));
 
         Self {
             echo_length: length,
@@ -34,19 +34,19 @@ impl EchoFilter {
 }
 
 impl AudioFilter for EchoFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         if self.echo_length <= 0.0 || self.decay <= 0.0 {
             return;
         }
 
         for sample in samples.iter_mut() {
-            let delayed = self.buffer.pop_front().unwrap_or(0);
+            let delayed = self.buffer.pop_front().unwrap_or(0.0);
 
-            let mixed = (*sample as f32) + (delayed as f32 * self.decay);
-            let out = mixed.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
-            *sample = out;
+            *sample += delayed * self.decay;
 
-            self.buffer.push_back(out);
+            // Bounded so a decay of 1.0 can't build up without limit.
+            self.buffer
+                .push_back(sample.clamp(i16::MIN as f32, i16::MAX as f32));
         }
     }
 
@@ -57,6 +57,6 @@ impl AudioFilter for EchoFilter {
     fn reset(&mut self) {
         self.buffer.clear();
         self.buffer
-            .extend(std::iter::repeat_n(0, self.delay_samples));
+            .extend(std::iter::repeat_n(0.0, self.delay_samples));
     }
 }
@@ -149,14 +149,14 @@ impl EqualizerFilter {
 }
 
 impl AudioFilter for EqualizerFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         let num_frames = samples.len() / 2;
 
         for frame in 0..num_frames {
             let offset = frame * 2;
 
-            let left_f = samples[offset] as f32 / 32768.0;
-            let right_f = samples[offset + 1] as f32 / 32768.0;
+            let left_f = samples[offset] / 32768.0;
+            let right_f = samples[offset + 1] / 32768.0;
 
             let mut result_left = left_f * 0.25;
             let mut result_right = right_f * 0.25;
@@ -176,11 +176,8 @@ impl AudioFilter for EqualizerFilter {
                 result_right += band_right * gain;
             }
 
-            let out_left = (result_left * self.makeup_gain).clamp(-1.0, 1.0);
-            let out_right = (result_right * self.makeup_gain).clamp(-1.0, 1.0);
-
-            samples[offset] = (out_left * 32767.0) as i16;
-            samples[offset + 1] = (out_right * 32767.0) as i16;
+            samples[offset] = result_left * self.makeup_gain * 32767.0;
+            samples[offset + 1] = result_right * self.makeup_gain * 32767.0;
         }
     }
 
@@ -35,7 +35,7 @@ impl FlangerFilter {
 }
 
 impl AudioFilter for FlangerFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         if self.rate == 0.0 || self.depth == 0.0 {
             return;
         }
@@ -49,12 +49,11 @@ impl AudioFilter for FlangerFilter {
             let delay = center_delay + lfo_value * max_delay_width;
 
             let delayed = self.delay_line.read(delay);
-            let input = (*sample as f32) + delayed * self.feedback;
+            let input = *sample + delayed * self.feedback;
             self.delay_line
                 .write(input.clamp(i16::MIN as f32, i16::MAX as f32));
 
-            let output = (*sample as f32) + delayed;
-            *sample = output.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+            *sample += delayed;
         }
     }
 
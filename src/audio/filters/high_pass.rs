@@ -58,7 +58,7 @@ impl HighPassFilter {
 }
 
 impl AudioFilter for HighPassFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         if self.cutoff_frequency <= 0 {
             return;
         }
@@ -71,16 +71,8 @@ impl AudioFilter for HighPassFilter {
         let boost_factor_f32 = self.boost_factor;
 
         for chunk in samples.chunks_exact_mut(2) {
-            let left_in = chunk[0] as f32;
-            let right_in = chunk[1] as f32;
-
-            let left_out =
-                self.left_state.process(left_in as f64, coeffs) as f32 * boost_factor_f32;
-            let right_out =
-                self.right_state.process(right_in as f64, coeffs) as f32 * boost_factor_f32;
-
-            chunk[0] = left_out.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
-            chunk[1] = right_out.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+            chunk[0] = self.left_state.process(chunk[0] as f64, coeffs) as f32 * boost_factor_f32;
+            chunk[1] = self.right_state.process(chunk[1] as f64, coeffs) as f32 * boost_factor_f32;
         }
     }
 
@@ -66,7 +66,7 @@ impl KaraokeFilter {
 }
 
 impl AudioFilter for KaraokeFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         if self.level <= 0.0 && self.mono_level <= 0.0 {
             return;
         }
@@ -133,20 +133,8 @@ impl AudioFilter for KaraokeFilter {
             let offset = frame * 2;
             current += step;
 
-            let mut out_l = out_left_buf[frame] * current;
-            let mut out_r = out_right_buf[frame] * current;
-
-            let peak = out_l.abs().max(out_r.abs());
-            if peak > 0.9999 {
-                let s = 0.9999 / peak;
-                out_l *= s;
-                out_r *= s;
-            }
-
-            samples[offset] =
-                ((out_l * SCALE_16).round() as i32).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
-            samples[offset + 1] =
-                ((out_r * SCALE_16).round() as i32).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+            samples[offset] = (out_left_buf[frame] * current * SCALE_16) as f32;
+            samples[offset + 1] = (out_right_buf[frame] * current * SCALE_16) as f32;
         }
 
         self.prev_gain = target;
@@ -97,7 +97,9 @@ pub fn validate_filters(filters: &Filters, config: &FiltersConfig) -> Vec<&'stat
 }
 
 pub trait AudioFilter: Send {
-    fn process(&mut self, samples: &mut [i16]);
+    /// Samples are interleaved stereo in i16 scale but unclamped; the output
+    /// stage limits them once before encoding.
+    fn process(&mut self, samples: &mut [f32]);
     fn is_enabled(&self) -> bool;
     fn reset(&mut self);
 }
@@ -126,7 +128,7 @@ pub enum ConcreteFilter {
 
 impl ConcreteFilter {
     #[inline(always)]
-    pub fn process(&mut self, samples: &mut [i16]) {
+    pub fn process(&mut self, samples: &mut [f32]) {
         match self {
             Self::Volume(f) => f.process(samples),
             Self::Equalizer(f) => f.process(samples),
@@ -178,7 +180,7 @@ impl ConcreteFilter {
 pub struct FilterChain {
     filters: Vec<ConcreteFilter>,
     timescale: Option<timescale::TimescaleFilter>,
-    timescale_buffer: Vec<i16>,
+    timescale_buffer: Vec<f32>,
 }
 
 impl FilterChain {
@@ -402,7 +404,7 @@ impl FilterChain {
         !self.filters.is_empty() || self.timescale.is_some()
     }
 
-    pub fn process(&mut self, samples: &mut [i16]) {
+    pub fn process(&mut self, samples: &mut [f32]) {
         for filter in self.filters.iter_mut() {
             filter.process(samples);
         }
@@ -422,7 +424,7 @@ impl FilterChain {
         }
     }
 
-    pub fn fill_frame(&mut self, output: &mut [i16]) -> bool {
+    pub fn fill_frame(&mut self, output: &mut [f32]) -> bool {
         if self.timescale.is_none() {
             return false;
         }
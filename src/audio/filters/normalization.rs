@@ -31,21 +31,20 @@ impl NormalizationFilter {
 }
 
 impl AudioFilter for NormalizationFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         if self.max_amplitude <= 0.0 {
             return;
         }
 
         if !self.adaptive {
             for sample in samples.iter_mut() {
-                let v = (*sample as f32) / 32768.0;
-                let scaled = v.clamp(-self.max_amplitude, self.max_amplitude);
-                *sample = (scaled * 32768.0) as i16;
+                let v = *sample / 32768.0;
+                *sample = v.clamp(-self.max_amplitude, self.max_amplitude) * 32768.0;
             }
         } else {
             for chunk in samples.chunks_exact_mut(2) {
-                let left_in = chunk[0] as f32 / 32768.0;
-                let right_in = chunk[1] as f32 / 32768.0;
+                let left_in = chunk[0] / 32768.0;
+                let right_in = chunk[1] / 32768.0;
 
                 let abs_peak = left_in.abs().max(right_in.abs());
 
@@ -63,10 +62,8 @@ impl AudioFilter for NormalizationFilter {
                     1.0
                 };
 
-                chunk[0] =
-                    (left_in * gain * 32768.0).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
-                chunk[1] =
-                    (right_in * gain * 32768.0).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+                chunk[0] = left_in * gain * 32768.0;
+                chunk[1] = right_in * gain * 32768.0;
             }
         }
     }
@@ -120,7 +120,7 @@ impl PhaserFilter {
 }
 
 impl AudioFilter for PhaserFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         if self.rate == 0.0 || self.depth == 0.0 || self.mix == 0.0 {
             return;
         }
@@ -129,8 +129,8 @@ impl AudioFilter for PhaserFilter {
         let sweep_range = self.max_frequency - self.min_frequency;
 
         for chunk in samples.chunks_exact_mut(2) {
-            let left_sample = chunk[0] as f32;
-            let right_sample = chunk[1] as f32;
+            let left_sample = chunk[0];
+            let right_sample = chunk[1];
 
             let left_lfo_val = (self.left_lfo.get_value() as f32 + 1.0) / 2.0;
             let right_lfo_val = (self.right_lfo.get_value() as f32 + 1.0) / 2.0;
@@ -160,8 +160,8 @@ impl AudioFilter for PhaserFilter {
             self.last_right_feedback = wet_right;
             let final_right = right_sample * (1.0 - self.mix) + wet_right * self.mix;
 
-            chunk[0] = final_left.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
-            chunk[1] = final_right.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+            chunk[0] = final_left;
+            chunk[1] = final_right;
         }
     }
 
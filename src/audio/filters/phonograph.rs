@@ -242,7 +242,7 @@ impl PhonographFilter {
 }
 
 impl AudioFilter for PhonographFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         let fs = 48000.0;
         let wow_max = self.depth * 0.014 * fs;
         let flutter_max = self.flutter * 0.0022 * fs;
@@ -262,10 +262,7 @@ impl AudioFilter for PhonographFilter {
         let rel = 0.0006 + 0.0012 * self.mic_agc;
 
         for chunk in samples.chunks_exact_mut(2) {
-            let left_sample = chunk[0] as f32;
-            let right_sample = chunk[1] as f32;
-
-            let mut x = ((left_sample + right_sample) * 0.5) / 32768.0;
+            let mut x = ((chunk[0] + chunk[1]) * 0.5) / 32768.0;
 
             let d_noise = self.rng.next_noise();
             self.drift += (d_noise * drift_amount - self.drift) * drift_smooth;
@@ -338,9 +335,8 @@ impl AudioFilter for PhonographFilter {
                 x *= g;
             }
 
-            let out = (x * 32768.0).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
-            chunk[0] = out;
-            chunk[1] = out;
+            chunk[0] = x * 32768.0;
+            chunk[1] = x * 32768.0;
         }
     }
 
@@ -150,14 +150,14 @@ impl ReverbFilter {
 }
 
 impl AudioFilter for ReverbFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         if self.wet == 0.0 {
             return;
         }
 
         for chunk in samples.chunks_exact_mut(2) {
-            let left_input = chunk[0] as f32;
-            let right_input = chunk[1] as f32;
+            let left_input = chunk[0];
+            let right_input = chunk[1];
             let mono_input = (left_input + right_input) * 0.5;
 
             let mut left_out = 0.0;
@@ -189,8 +189,8 @@ impl AudioFilter for ReverbFilter {
             let final_left = left_input * self.dry + left_out * wet1 + right_out * wet2;
             let final_right = right_input * self.dry + right_out * wet1 + left_out * wet2;
 
-            chunk[0] = final_left.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
-            chunk[1] = final_right.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+            chunk[0] = final_left;
+            chunk[1] = final_right;
         }
     }
 
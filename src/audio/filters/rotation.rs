@@ -14,7 +14,7 @@ impl RotationFilter {
 }
 
 impl AudioFilter for RotationFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         if self.lfo.frequency == 0.0 {
             return;
         }
@@ -27,14 +27,8 @@ impl AudioFilter for RotationFilter {
             let left_factor = (1.0 - lfo_value) / 2.0;
             let right_factor = (1.0 + lfo_value) / 2.0;
 
-            let left = samples[offset] as f64;
-            let right = samples[offset + 1] as f64;
-
-            let new_left = (left * left_factor) as i32;
-            let new_right = (right * right_factor) as i32;
-
-            samples[offset] = new_left.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
-            samples[offset + 1] = new_right.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+            samples[offset] = (samples[offset] as f64 * left_factor) as f32;
+            samples[offset + 1] = (samples[offset + 1] as f64 * right_factor) as f32;
         }
     }
 
@@ -33,7 +33,7 @@ impl SpatialFilter {
 }
 
 impl AudioFilter for SpatialFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         if self.depth == 0.0 {
             return;
         }
@@ -44,8 +44,8 @@ impl AudioFilter for SpatialFilter {
         let feedback = -0.3;
 
         for chunk in samples.chunks_exact_mut(2) {
-            let left_in = chunk[0] as f32;
-            let right_in = chunk[1] as f32;
+            let left_in = chunk[0];
+            let right_in = chunk[1];
 
             let lfo_value = self.lfo.get_value() as f32;
 
@@ -64,8 +64,8 @@ impl AudioFilter for SpatialFilter {
             let new_left = left_in * dry + delayed_right * wet;
             let new_right = right_in * dry + delayed_left * wet;
 
-            chunk[0] = new_left.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
-            chunk[1] = new_right.clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+            chunk[0] = new_left;
+            chunk[1] = new_right;
         }
     }
 
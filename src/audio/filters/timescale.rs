@@ -15,7 +15,7 @@ pub struct TimescaleFilter {
     _pitch: f64,
     _rate: f64,
     final_rate: f32,
-    input_buffer: Vec<i16>,
+    input_buffer: Vec<f32>,
     position: f32,
 }
 
@@ -36,7 +36,7 @@ impl TimescaleFilter {
         }
     }
 
-    pub fn process_resample(&mut self, samples: &[i16]) -> Vec<i16> {
+    pub fn process_resample(&mut self, samples: &[f32]) -> Vec<f32> {
         if (self.final_rate - 1.0).abs() < f32::EPSILON {
             return samples.to_vec();
         }
@@ -67,20 +67,18 @@ impl TimescaleFilter {
             let p3_idx = i1 + 2;
 
             // Left channel
-            let p0_l = self.input_buffer[p0_idx * 2] as f32 / 32768.0;
-            let p1_l = self.input_buffer[p1_idx * 2] as f32 / 32768.0;
-            let p2_l = self.input_buffer[p2_idx * 2] as f32 / 32768.0;
-            let p3_l = self.input_buffer[p3_idx * 2] as f32 / 32768.0;
-            let out_l = cubic_resample(p0_l, p1_l, p2_l, p3_l, frac);
-            output.push((out_l.clamp(-1.0, 1.0) * 32767.0) as i16);
+            let p0_l = self.input_buffer[p0_idx * 2];
+            let p1_l = self.input_buffer[p1_idx * 2];
+            let p2_l = self.input_buffer[p2_idx * 2];
+            let p3_l = self.input_buffer[p3_idx * 2];
+            output.push(cubic_resample(p0_l, p1_l, p2_l, p3_l, frac));
 
             // Right channel
-            let p0_r = self.input_buffer[p0_idx * 2 + 1] as f32 / 32768.0;
-            let p1_r = self.input_buffer[p1_idx * 2 + 1] as f32 / 32768.0;
-            let p2_r = self.input_buffer[p2_idx * 2 + 1] as f32 / 32768.0;
-            let p3_r = self.input_buffer[p3_idx * 2 + 1] as f32 / 32768.0;
-            let out_r = cubic_resample(p0_r, p1_r, p2_r, p3_r, frac);
-            output.push((out_r.clamp(-1.0, 1.0) * 32767.0) as i16);
+            let p0_r = self.input_buffer[p0_idx * 2 + 1];
+            let p1_r = self.input_buffer[p1_idx * 2 + 1];
+            let p2_r = self.input_buffer[p2_idx * 2 + 1];
+            let p3_r = self.input_buffer[p3_idx * 2 + 1];
+            output.push(cubic_resample(p0_r, p1_r, p2_r, p3_r, frac));
 
             self.position += self.final_rate;
         }
@@ -101,7 +99,7 @@ impl TimescaleFilter {
 }
 
 impl AudioFilter for TimescaleFilter {
-    fn process(&mut self, _samples: &mut [i16]) {
+    fn process(&mut self, _samples: &mut [f32]) {
         // Timescale cannot work in-place because it changes buffer length.
         // Use `process_resample` instead. This is a no-op for the in-place trait.
     }
@@ -15,7 +15,7 @@ impl TremoloFilter {
 }
 
 impl AudioFilter for TremoloFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         if self.lfo.depth == 0.0 || self.lfo.frequency == 0.0 {
             return;
         }
@@ -24,11 +24,8 @@ impl AudioFilter for TremoloFilter {
         for chunk in samples.chunks_exact_mut(2) {
             let multiplier = self.lfo.process();
 
-            let left = (chunk[0] as f64 * multiplier) as i32;
-            chunk[0] = left.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
-
-            let right = (chunk[1] as f64 * multiplier) as i32;
-            chunk[1] = right.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+            chunk[0] = (chunk[0] as f64 * multiplier) as f32;
+            chunk[1] = (chunk[1] as f64 * multiplier) as f32;
         }
     }
 
@@ -26,7 +26,7 @@ impl VibratoFilter {
 }
 
 impl AudioFilter for VibratoFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         if self.lfo.depth == 0.0 || self.lfo.frequency == 0.0 {
             self.left_delay.clear();
             self.right_delay.clear();
@@ -42,16 +42,11 @@ impl AudioFilter for VibratoFilter {
             let lfo_value = self.lfo.get_value();
             let delay = center_delay + lfo_value * max_delay_width;
 
-            let left_sample = samples[offset] as f32;
-            self.left_delay.write(left_sample);
-            let delayed_left = self.left_delay.read(delay as f32);
-            samples[offset] = (delayed_left as i32).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+            self.left_delay.write(samples[offset]);
+            samples[offset] = self.left_delay.read(delay as f32);
 
-            let right_sample = samples[offset + 1] as f32;
-            self.right_delay.write(right_sample);
-            let delayed_right = self.right_delay.read(delay as f32);
-            samples[offset + 1] =
-                (delayed_right as i32).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+            self.right_delay.write(samples[offset + 1]);
+            samples[offset + 1] = self.right_delay.read(delay as f32);
         }
     }
 
@@ -12,11 +12,10 @@ impl VolumeFilter {
 }
 
 impl AudioFilter for VolumeFilter {
-    fn process(&mut self, samples: &mut [i16]) {
+    fn process(&mut self, samples: &mut [f32]) {
         let vol = self.volume;
         for sample in samples.iter_mut() {
-            let s = (*sample as f32 * vol) as i32;
-            *sample = s.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+            *sample *= vol;
         }
     }
 
@@ -2,24 +2,49 @@ use flume::{Receiver, Sender};
 
 use crate::audio::{
     AudioFrame,
-    buffer::{PooledBuffer, acquire_buffer},
-    constants::FRAME_SIZE_SAMPLES,
+    buffer::acquire_buffer,
+    constants::{FRAME_SIZE_SAMPLES, INT16_MAX_F, INT16_MIN_F, MIXER_CHANNELS},
     effects::{
-        crossfade::CrossfadeController, fade::FadeEffect, tape::TapeEffect, volume::VolumeEffect,
+        crossfade::CrossfadeController,
+        fade::{FadeCurve, FadeEffect},
+        tape::TapeEffect,
+        volume::VolumeEffect,
     },
     error::AudioError,
+    flow::silence::SilenceTrimmer,
 };
 
+/// How the decoder answered the last seek.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekOutcome {
+    /// Playback resumed at this position (ms).
+    Landed(u64),
+    Failed,
+}
+
 pub struct FlowController {
     pub tape: TapeEffect,
     pub volume: VolumeEffect,
     pub fade: FadeEffect,
     pub crossfade: CrossfadeController,
     pending_pcm: Vec<i16>,
+    /// Current frame, converted to `f32` and run through the effects.
+    frame: Vec<f32>,
     decoder_done: bool,
     frame_rx: Receiver<AudioFrame>,
     frame_tx: Option<Sender<AudioFrame>>,
-    latest_opus: Option<Vec<u8>>,
+    /// Source packet of the frame at the head of `pending_pcm`.
+    pending_opus: Option<Vec<u8>>,
+    /// Source packet of the last popped frame, if the effects left it as is.
+    frame_opus: Option<Vec<u8>>,
+    sample_rate: u32,
+    fade_in_ms: u64,
+    fade_curve: FadeCurve,
+    seek: Option<SeekOutcome>,
+    silence: Option<SilenceTrimmer>,
+    /// Samples per channel dropped as silence since the last call to
+    /// [`Self::take_skipped`].
+    skipped: u64,
 }
 
 impl FlowController {
@@ -55,28 +80,61 @@ impl FlowController {
             fade: FadeEffect::new(1.0, channels),
             crossfade: CrossfadeController::new(sample_rate, channels),
             pending_pcm: Vec::with_capacity(FRAME_SIZE_SAMPLES * 2),
+            frame: Vec::with_capacity(FRAME_SIZE_SAMPLES),
             decoder_done: false,
             frame_rx,
             frame_tx,
-            latest_opus: None,
+            pending_opus: None,
+            frame_opus: None,
+            sample_rate,
+            fade_in_ms: 0,
+            fade_curve: FadeCurve::default(),
+            seek: None,
+            silence: None,
+            skipped: 0,
         }
     }
 
+    /// Trims leading and trailing silence from the track from now on.
+    pub fn set_silence_trim(&mut self, trimmer: SilenceTrimmer) {
+        self.silence = Some(trimmer);
+    }
+
+    /// Ramp played from silence on [`Self::fade_in`] and after every seek.
+    pub fn set_fade_in(&mut self, duration_ms: u64, curve: FadeCurve) {
+        self.fade_in_ms = duration_ms;
+        self.fade_curve = curve;
+    }
+
+    /// Restarts the gain from silence, or restores it at once when no
+    /// fade-in is configured.
+    pub fn fade_in(&mut self) {
+        if self.fade_in_ms > 0 {
+            self.fade.set_gain(0.0);
+        }
+        self.fade
+            .fade_to(1.0, self.fade_in_ms, self.fade_curve, self.sample_rate);
+    }
+
     pub fn run(&mut self) {
         while let Ok(frame_data) = self.frame_rx.recv() {
             match frame_data {
-                AudioFrame::Pcm(pooled) => {
-                    if pooled.is_empty() {
-                        self.pending_pcm.clear();
-                        continue;
-                    }
-
+                AudioFrame::Seeked(ms) => {
+                    self.pending_pcm.clear();
+                    self.on_seek(SeekOutcome::Landed(ms));
+                }
+                AudioFrame::SeekFailed => self.on_seek(SeekOutcome::Failed),
+                AudioFrame::Pcm(pooled) | AudioFrame::Passthrough { pcm: pooled, .. } => {
                     self.pending_pcm.extend_from_slice(&pooled);
 
                     while self.pending_pcm.len() >= FRAME_SIZE_SAMPLES {
+                        self.next_frame();
                         let mut frame = acquire_buffer(FRAME_SIZE_SAMPLES);
-                        frame.extend(self.pending_pcm.drain(..FRAME_SIZE_SAMPLES));
-                        self.process_frame(&mut frame);
+                        frame.extend(
+                            self.frame
+                                .iter()
+                                .map(|&s| s.round().clamp(INT16_MIN_F, INT16_MAX_F) as i16),
+                        );
 
                         if self
                             .frame_tx
@@ -98,35 +156,26 @@ impl FlowController {
         }
     }
 
-    pub fn try_pop_frame(&mut self) -> Result<Option<PooledBuffer>, AudioError> {
-        if !self.decoder_done {
-            while self.pending_pcm.len() < FRAME_SIZE_SAMPLES {
-                match self.frame_rx.try_recv() {
-                    Ok(AudioFrame::Pcm(chunk)) if chunk.is_empty() => {
-                        self.pending_pcm.clear();
-                        self.decoder_done = false;
-                    }
-                    Ok(AudioFrame::Pcm(chunk)) => {
-                        self.pending_pcm.extend_from_slice(&chunk);
-                        crate::audio::buffer::release_buffer(chunk);
-                    }
-                    Ok(AudioFrame::Opus(packet)) => {
-                        self.latest_opus = Some(packet);
-                    }
-                    Err(flume::TryRecvError::Empty) => break,
-                    Err(flume::TryRecvError::Disconnected) => {
-                        self.decoder_done = true;
-                        break;
-                    }
+    pub fn try_pop_frame(&mut self) -> Result<Option<&[f32]>, AudioError> {
+        self.fill_pending();
+
+        if self.silence.is_some() {
+            // Dropping leading silence makes room to pull more from the decoder.
+            loop {
+                match self.trim_leading() {
+                    None => return Ok(None),
+                    Some(0) => break,
+                    Some(_) => self.fill_pending(),
                 }
             }
+            if self.trim_trailing() {
+                return Err(AudioError::DecoderFinished);
+            }
         }
 
         if self.pending_pcm.len() >= FRAME_SIZE_SAMPLES {
-            let mut frame = acquire_buffer(FRAME_SIZE_SAMPLES);
-            frame.extend(self.pending_pcm.drain(..FRAME_SIZE_SAMPLES));
-            self.process_frame(&mut frame);
-            Ok(Some(frame))
+            self.next_frame();
+            Ok(Some(&self.frame))
         } else if self.decoder_done {
             Err(AudioError::DecoderFinished)
         } else {
@@ -134,7 +183,96 @@ impl FlowController {
         }
     }
 
-    pub fn process_frame(&mut self, frame: &mut [i16]) {
+    fn fill_pending(&mut self) {
+        if self.decoder_done {
+            return;
+        }
+        while self.pending_pcm.len() < self.wanted() {
+            match self.frame_rx.try_recv() {
+                Ok(AudioFrame::Seeked(ms)) => {
+                    self.pending_pcm.clear();
+                    self.pending_opus = None;
+                    self.on_seek(SeekOutcome::Landed(ms));
+                }
+                Ok(AudioFrame::SeekFailed) => self.on_seek(SeekOutcome::Failed),
+                Ok(AudioFrame::Pcm(chunk)) => {
+                    self.pending_pcm.extend_from_slice(&chunk);
+                    crate::audio::buffer::release_buffer(chunk);
+                }
+                Ok(AudioFrame::Passthrough { pcm, packet }) => {
+                    // Only a packet that starts a frame maps onto one.
+                    if self.pending_pcm.is_empty() && pcm.len() == FRAME_SIZE_SAMPLES {
+                        self.pending_opus = Some(packet);
+                    }
+                    self.pending_pcm.extend_from_slice(&pcm);
+                    crate::audio::buffer::release_buffer(pcm);
+                }
+                // A bare packet has no PCM to mix.
+                Ok(AudioFrame::Opus(_)) => {}
+                Err(flume::TryRecvError::Empty) => break,
+                Err(flume::TryRecvError::Disconnected) => {
+                    self.decoder_done = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    fn trim_leading(&mut self) -> Option<usize> {
+        let trimmer = self.silence.as_mut()?;
+        let cut = trimmer.trim_leading(&mut self.pending_pcm, self.decoder_done)?;
+        if cut > 0 {
+            self.pending_opus = None;
+            self.skipped += (cut / MIXER_CHANNELS) as u64;
+        }
+        Some(cut)
+    }
+
+    fn trim_trailing(&mut self) -> bool {
+        let Some(trimmer) = &mut self.silence else {
+            return false;
+        };
+        if !trimmer.trim_trailing(&self.pending_pcm, self.decoder_done) {
+            return false;
+        }
+        self.pending_pcm.clear();
+        self.pending_opus = None;
+        true
+    }
+
+    /// Samples to buffer before popping; more while looking for silence.
+    fn wanted(&mut self) -> usize {
+        match &mut self.silence {
+            Some(trimmer) => trimmer.wanted(&self.pending_pcm, FRAME_SIZE_SAMPLES),
+            None => FRAME_SIZE_SAMPLES,
+        }
+    }
+
+    /// Moves one frame out of `pending_pcm` into `frame` and processes it.
+    fn next_frame(&mut self) {
+        if let Some(trimmer) = &mut self.silence {
+            trimmer.observe(&self.pending_pcm[..FRAME_SIZE_SAMPLES]);
+        }
+        let packet = self.pending_opus.take();
+        let transparent = self.is_transparent();
+
+        let mut frame = std::mem::take(&mut self.frame);
+        frame.clear();
+        frame.extend(self.pending_pcm.drain(..FRAME_SIZE_SAMPLES).map(f32::from));
+        self.process_frame(&mut frame);
+        self.frame = frame;
+        self.frame_opus = packet.filter(|_| transparent);
+    }
+
+    /// Whether the effects would pass a frame through unchanged.
+    pub fn is_transparent(&self) -> bool {
+        !self.tape.is_active()
+            && self.volume.is_unity()
+            && self.fade.is_unity()
+            && !self.crossfade.is_active()
+    }
+
+    pub fn process_frame(&mut self, frame: &mut [f32]) {
         self.tape.process(frame);
         self.volume.process(frame);
         self.fade.process(frame);
@@ -145,7 +283,30 @@ impl FlowController {
         }
     }
 
-    pub fn take_opus(&mut self) -> Option<Vec<u8>> {
-        self.latest_opus.take()
+    fn on_seek(&mut self, outcome: SeekOutcome) {
+        self.seek = Some(outcome);
+        if let Some(trimmer) = &mut self.silence {
+            trimmer.reset();
+        }
+        // The track was held silent for the seek; a failed one fades back in
+        // where it was.
+        self.fade_in();
+    }
+
+    /// Samples per channel trimmed as leading silence since the last call;
+    /// the track's position moves past them.
+    pub fn take_skipped(&mut self) -> u64 {
+        std::mem::take(&mut self.skipped)
+    }
+
+    /// Result of a seek the decoder has finished since the last call.
+    pub fn take_seek(&mut self) -> Option<SeekOutcome> {
+        self.seek.take()
+    }
+
+    /// Source packet of the frame just popped, when it can be sent in place
+    /// of re-encoding that frame.
+    pub fn take_frame_opus(&mut self) -> Option<Vec<u8>> {
+        self.frame_opus.take()
     }
 }
@@ -1,3 +1,5 @@
 pub mod controller;
+pub mod silence;
 
-pub use controller::FlowController;
+pub use controller::{FlowController, SeekOutcome};
+pub use silence::{SilenceTrimmer, TrimStats};
@@ -0,0 +1,255 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+    audio::constants::{MIXER_CHANNELS, TARGET_SAMPLE_RATE},
+    config::player::SilenceConfig,
+};
+
+/// Furthest the decoder is read ahead through a silent run to find out
+/// whether it lasts until the end of the track. Bounds the PCM held per
+/// track to under 1 MB; a longer silence plays until its last stretch fits.
+const LOOKAHEAD_MS: u64 = 5_000;
+
+/// Silence trimmed from the current track, in milliseconds.
+#[derive(Debug, Default)]
+pub struct TrimStats {
+    leading_ms: AtomicU64,
+    trailing_ms: AtomicU64,
+}
+
+impl TrimStats {
+    pub fn leading_ms(&self) -> u64 {
+        self.leading_ms.load(Ordering::Acquire)
+    }
+
+    pub fn trailing_ms(&self) -> u64 {
+        self.trailing_ms.load(Ordering::Acquire)
+    }
+}
+
+/// Finds leading and trailing silence in a track's decoded PCM.
+///
+/// Leading silence is held back until it either lasts `min_duration_ms`,
+/// and is then dropped up to the first audible sample, or turns out to be
+/// shorter and is played. Once `min_duration_ms` of silence has played, the
+/// decoder is read ahead; if it finishes with nothing audible left, the rest
+/// is dropped and the track ends there.
+pub struct SilenceTrimmer {
+    threshold: i16,
+    min_samples: usize,
+    lookahead_samples: usize,
+    stats: Arc<TrimStats>,
+    leading: bool,
+    /// Leading silence has reached `min_samples` and is being dropped.
+    skipping: bool,
+    /// Silent samples played since the last audible one.
+    run: usize,
+    /// Samples at the head of the pending buffer known to be silent.
+    checked: usize,
+}
+
+impl SilenceTrimmer {
+    pub fn new(config: &SilenceConfig, stats: Arc<TrimStats>) -> Self {
+        let threshold = (i16::MAX as f32 * 10f32.powf(config.threshold_db / 20.0))
+            .clamp(0.0, i16::MAX as f32) as i16;
+        Self {
+            threshold,
+            min_samples: Self::samples(config.min_duration_ms()).max(MIXER_CHANNELS),
+            lookahead_samples: Self::samples(LOOKAHEAD_MS.max(config.min_duration_ms())),
+            stats,
+            leading: true,
+            skipping: false,
+            run: 0,
+            checked: 0,
+        }
+    }
+
+    fn samples(ms: u64) -> usize {
+        (ms * TARGET_SAMPLE_RATE as u64 / 1000) as usize * MIXER_CHANNELS
+    }
+
+    fn ms(samples: usize) -> u64 {
+        (samples / MIXER_CHANNELS) as u64 * 1000 / TARGET_SAMPLE_RATE as u64
+    }
+
+    fn is_silent(&self, sample: i16) -> bool {
+        sample.unsigned_abs() <= self.threshold as u16
+    }
+
+    /// Extends the known-silent prefix of `pending` as far as it goes.
+    fn scan(&mut self, pending: &[i16]) {
+        let silent = pending[self.checked..]
+            .iter()
+            .take_while(|&&s| self.is_silent(s))
+            .count();
+        self.checked += silent;
+    }
+
+    /// How many samples `pending` should hold before a frame is taken.
+    pub fn wanted(&mut self, pending: &[i16], frame: usize) -> usize {
+        if !self.leading && self.run < self.min_samples {
+            return frame;
+        }
+        self.scan(pending);
+        if self.checked < pending.len() {
+            // Audible audio is already buffered; nothing to look for.
+            return frame;
+        }
+        if self.leading {
+            self.min_samples.max(frame)
+        } else {
+            self.lookahead_samples
+        }
+    }
+
+    /// Drops leading silence from `pending`. Returns the samples dropped, or
+    /// `None` while it is too early to tell whether there is any to drop.
+    pub fn trim_leading(&mut self, pending: &mut Vec<i16>, decoder_done: bool) -> Option<usize> {
+        if !self.leading {
+            return Some(0);
+        }
+        self.scan(pending);
+
+        let audible = self.checked < pending.len();
+        if !self.skipping && self.checked < self.min_samples {
+            if audible || decoder_done {
+                self.leading = false;
+                return Some(0);
+            }
+            return None;
+        }
+
+        self.skipping = true;
+        let cut = self.checked - self.checked % MIXER_CHANNELS;
+        pending.drain(..cut);
+        self.checked -= cut;
+        self.stats
+            .leading_ms
+            .fetch_add(Self::ms(cut), Ordering::AcqRel);
+        if audible {
+            self.leading = false;
+        }
+        Some(cut)
+    }
+
+    /// Whether everything left in `pending` is trailing silence after at
+    /// least `min_duration_ms` of it has played, in which case the caller
+    /// drops it and ends the track.
+    pub fn trim_trailing(&mut self, pending: &[i16], decoder_done: bool) -> bool {
+        if !decoder_done || self.leading || self.run < self.min_samples {
+            return false;
+        }
+        self.scan(pending);
+        if self.checked < pending.len() {
+            return false;
+        }
+        self.stats
+            .trailing_ms
+            .store(Self::ms(pending.len()), Ordering::Release);
+        true
+    }
+
+    /// Records a frame taken from the head of `pending` for playback.
+    pub fn observe(&mut self, frame: &[i16]) {
+        self.checked = self.checked.saturating_sub(frame.len());
+        match frame.iter().rposition(|&s| !self.is_silent(s)) {
+            Some(last) => self.run = frame.len() - 1 - last,
+            None => self.run += frame.len(),
+        }
+    }
+
+    /// Forgets what was buffered, as after a seek.
+    pub fn reset(&mut self) {
+        self.leading = false;
+        self.run = 0;
+        self.checked = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trimmer(min_duration_ms: u64) -> (SilenceTrimmer, Arc<TrimStats>) {
+        let stats = Arc::new(TrimStats::default());
+        let config = SilenceConfig {
+            enabled: true,
+            threshold_db: -60.0,
+            min_duration_ms,
+        };
+        (SilenceTrimmer::new(&config, stats.clone()), stats)
+    }
+
+    #[test]
+    fn test_leading_silence_is_dropped() {
+        let (mut trimmer, stats) = trimmer(100);
+        let mut pending = vec![0i16; SilenceTrimmer::samples(200)];
+        let len = pending.len();
+        assert_eq!(trimmer.trim_leading(&mut pending, false), Some(len));
+        assert!(pending.is_empty());
+
+        pending.extend_from_slice(&[0, 0, 5000, 5000]);
+        assert_eq!(trimmer.trim_leading(&mut pending, false), Some(2));
+        assert_eq!(pending, vec![5000, 5000]);
+        assert_eq!(stats.leading_ms(), 200);
+    }
+
+    #[test]
+    fn test_short_leading_silence_is_kept() {
+        let (mut trimmer, stats) = trimmer(100);
+        let mut pending = vec![0i16; SilenceTrimmer::samples(50)];
+        assert_eq!(trimmer.trim_leading(&mut pending, false), None);
+
+        pending.push(5000);
+        assert_eq!(trimmer.trim_leading(&mut pending, false), Some(0));
+        assert_eq!(pending.len(), SilenceTrimmer::samples(50) + 1);
+        assert_eq!(stats.leading_ms(), 0);
+    }
+
+    #[test]
+    fn test_trailing_silence_ends_the_track() {
+        let (mut trimmer, stats) = trimmer(100);
+        let mut pending = vec![5000i16; 4];
+        trimmer.trim_leading(&mut pending, false);
+        trimmer.observe(&pending);
+
+        let silence = vec![0i16; SilenceTrimmer::samples(100)];
+        trimmer.observe(&silence);
+        assert!(trimmer.wanted(&[], 2) > silence.len());
+
+        let rest = vec![0i16; SilenceTrimmer::samples(500)];
+        assert!(!trimmer.trim_trailing(&rest, false));
+        assert!(trimmer.trim_trailing(&rest, true));
+        assert_eq!(stats.trailing_ms(), 500);
+    }
+
+    #[test]
+    fn test_held_silence_is_capped() {
+        let (mut trimmer, _) = trimmer(60_000);
+        let longest = SilenceTrimmer::samples(SilenceConfig::MAX_MIN_DURATION_MS);
+        assert_eq!(trimmer.wanted(&[], 2), longest);
+
+        trimmer.reset();
+        trimmer.observe(&vec![0i16; longest]);
+        assert_eq!(
+            trimmer.wanted(&[], 2),
+            SilenceTrimmer::samples(LOOKAHEAD_MS)
+        );
+    }
+
+    #[test]
+    fn test_silence_before_audio_is_not_trailing() {
+        let (mut trimmer, stats) = trimmer(100);
+        trimmer.reset();
+        trimmer.observe(&vec![0i16; SilenceTrimmer::samples(200)]);
+
+        let mut rest = vec![0i16; SilenceTrimmer::samples(100)];
+        rest.push(5000);
+        assert!(!trimmer.trim_trailing(&rest, true));
+        assert_eq!(trimmer.wanted(&rest, 2), 2);
+        assert_eq!(stats.trailing_ms(), 0);
+    }
+}
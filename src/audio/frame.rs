@@ -4,6 +4,16 @@ use crate::audio::buffer::PooledBuffer;
 pub enum AudioFrame {
     Pcm(PooledBuffer),
     Opus(Vec<u8>),
+    /// One 20 ms frame of an Opus source: the decoded PCM and the packet it
+    /// came from, which can be sent as-is while nothing alters the audio.
+    Passthrough {
+        pcm: PooledBuffer,
+        packet: Vec<u8>,
+    },
+    /// A seek landed at this position (ms); anything queued before it is stale.
+    Seeked(u64),
+    /// A seek could not be performed; playback carries on where it was.
+    SeekFailed,
 }
 
 #[cfg(test)]
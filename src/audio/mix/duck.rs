@@ -0,0 +1,213 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicU32, Ordering},
+};
+
+use crate::{
+    audio::constants::{MIXER_CHANNELS, TARGET_SAMPLE_RATE},
+    config::player::DuckingConfig,
+};
+
+/// Layer level, on the 16-bit scale, above which a layer counts as producing
+/// audio; about -60 dBFS.
+pub const AUDIBLE_LEVEL: f32 = 32.0;
+
+/// Current ducking of a mixer, readable without locking it.
+#[derive(Debug)]
+pub struct DuckState {
+    gain: AtomicU32,
+    active: AtomicBool,
+}
+
+impl Default for DuckState {
+    fn default() -> Self {
+        Self {
+            gain: AtomicU32::new(1.0f32.to_bits()),
+            active: AtomicBool::new(false),
+        }
+    }
+}
+
+impl DuckState {
+    /// Gain applied to the main tracks, 1.0 when not ducked.
+    pub fn gain(&self) -> f32 {
+        f32::from_bits(self.gain.load(Ordering::Acquire))
+    }
+
+    /// Whether a layer is currently holding the main tracks down.
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+}
+
+/// Attenuates the main tracks while a layer is audible and restores them
+/// once the layers have been silent for the hold time, ramping linearly over
+/// the attack and release times.
+pub struct Ducker {
+    config: DuckingConfig,
+    gain: f32,
+    /// Frames mixed since a layer was last audible.
+    quiet_frames: u64,
+    /// Current ramp from `from` to `target` over `len` frames.
+    from: f32,
+    target: f32,
+    pos: usize,
+    len: usize,
+    state: Arc<DuckState>,
+}
+
+impl Default for Ducker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Ducker {
+    pub fn new() -> Self {
+        Self {
+            config: DuckingConfig::default(),
+            gain: 1.0,
+            quiet_frames: u64::MAX,
+            from: 1.0,
+            target: 1.0,
+            pos: 0,
+            len: 0,
+            state: Arc::new(DuckState::default()),
+        }
+    }
+
+    pub fn state(&self) -> Arc<DuckState> {
+        self.state.clone()
+    }
+
+    pub fn configure(&mut self, config: &DuckingConfig) {
+        self.config = *config;
+    }
+
+    /// True while the main tracks play at full level.
+    pub fn is_idle(&self) -> bool {
+        self.gain >= 1.0
+    }
+
+    /// Applies the ducking gain to `main`, moving towards the ducked level
+    /// while `layers_audible` or within the hold time after, and back to
+    /// unity otherwise.
+    pub fn process(&mut self, main: &mut [f32], layers_audible: bool) {
+        let held = self.quiet_frames < self.config.hold_ms * TARGET_SAMPLE_RATE as u64 / 1000;
+        self.quiet_frames = if layers_audible {
+            0
+        } else {
+            self.quiet_frames
+                .saturating_add((main.len() / MIXER_CHANNELS) as u64)
+        };
+        let active = self.config.enabled && (layers_audible || held);
+        self.state.active.store(active, Ordering::Release);
+
+        let floor = self.config.floor_gain();
+        let target = if active { floor } else { 1.0 };
+        if target != self.target {
+            // A full ramp spans unity to the floor; shorter ones take their
+            // share of the attack or release time.
+            let ramp_ms = if target < self.gain {
+                self.config.attack_ms
+            } else {
+                self.config.release_ms
+            };
+            let full = (ramp_ms * TARGET_SAMPLE_RATE as u64 / 1000) as f32;
+            let share = (target - self.gain).abs() / (1.0 - floor).max(f32::EPSILON);
+            self.from = self.gain;
+            self.target = target;
+            self.pos = 0;
+            self.len = (full * share.min(1.0)).ceil() as usize;
+        }
+        if self.is_idle() && self.target >= 1.0 {
+            return;
+        }
+
+        for frame in main.chunks_exact_mut(MIXER_CHANNELS) {
+            self.pos = (self.pos + 1).min(self.len);
+            self.gain = if self.pos == self.len {
+                self.target
+            } else {
+                let t = self.pos as f32 / self.len as f32;
+                self.from + (self.target - self.from) * t
+            };
+            for s in frame {
+                *s *= self.gain;
+            }
+        }
+        self.state
+            .gain
+            .store(self.gain.to_bits(), Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ducking(amount_db: f32, attack_ms: u64, release_ms: u64) -> DuckingConfig {
+        DuckingConfig {
+            enabled: true,
+            amount_db,
+            attack_ms,
+            hold_ms: 0,
+            release_ms,
+        }
+    }
+
+    #[test]
+    fn test_ducks_with_attack_and_recovers_with_release() {
+        let mut ducker = Ducker::new();
+        ducker.configure(&ducking(20.0, 20, 40));
+        let state = ducker.state();
+
+        // 20 ms attack: one frame reaches the ducked level.
+        let mut frame = vec![1000.0; 960 * 2];
+        ducker.process(&mut frame, true);
+        assert!(frame[0] < 1000.0 && frame[0] > 900.0);
+        assert!((frame[frame.len() - 1] - 100.0).abs() < 0.5);
+        assert!(state.is_active());
+        assert!((state.gain() - 0.1).abs() < 1e-3);
+
+        // 40 ms release: halfway back after one frame, restored after two.
+        let mut frame = vec![1000.0; 960 * 2];
+        ducker.process(&mut frame, false);
+        assert!(!state.is_active());
+        assert!((frame[frame.len() - 1] - 550.0).abs() < 1.0);
+        ducker.process(&mut frame, false);
+        assert!(ducker.is_idle());
+        assert_eq!(state.gain(), 1.0);
+    }
+
+    #[test]
+    fn test_hold_bridges_short_gaps() {
+        let mut ducker = Ducker::new();
+        ducker.configure(&DuckingConfig {
+            hold_ms: 40,
+            ..ducking(20.0, 20, 40)
+        });
+        let state = ducker.state();
+
+        let mut frame = vec![1000.0; 960 * 2];
+        ducker.process(&mut frame, true);
+        // Two silent 20 ms frames stay within the hold.
+        for _ in 0..2 {
+            ducker.process(&mut frame, false);
+            assert!(state.is_active());
+            assert!((state.gain() - 0.1).abs() < 1e-3);
+        }
+        ducker.process(&mut frame, false);
+        assert!(!state.is_active());
+        assert!(state.gain() > 0.1);
+    }
+
+    #[test]
+    fn test_disabled_ducker_leaves_audio_alone() {
+        let mut ducker = Ducker::new();
+        let mut frame = vec![1000.0; 960 * 2];
+        ducker.process(&mut frame, true);
+        assert!(frame.iter().all(|&s| s == 1000.0));
+        assert!(!ducker.state().is_active());
+    }
+}
@@ -39,7 +39,7 @@ impl MixLayer {
         self.finished && self.ring_buffer.is_empty()
     }
 
-    pub fn accumulate(&mut self, acc: &mut [i32]) {
+    pub fn accumulate(&mut self, acc: &mut [f32]) {
         let byte_count = acc.len() * 2;
         if let Some(bytes) = self.ring_buffer.read(byte_count) {
             // SAFETY: u8 bytes reinterpreted as i16 samples.
@@ -47,7 +47,7 @@ impl MixLayer {
                 std::slice::from_raw_parts(bytes.as_ptr() as *const i16, bytes.len() / 2)
             };
             for (acc_val, &s) in acc.iter_mut().zip(samples.iter()) {
-                *acc_val += (s as f32 * self.volume).round() as i32;
+                *acc_val += s as f32 * self.volume;
             }
         }
     }
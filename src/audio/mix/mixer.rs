@@ -8,23 +8,27 @@ use std::{
 
 use flume::Receiver;
 
-use super::layer::MixLayer;
+use super::{
+    duck::{AUDIBLE_LEVEL, DuckState, Ducker},
+    layer::MixLayer,
+};
 use crate::{
     audio::{
         AudioFrame,
         buffer::PooledBuffer,
         constants::{MAX_LAYERS, MIXER_CHANNELS, TARGET_SAMPLE_RATE},
-        flow::FlowController,
-        playback::handle::PlaybackState,
+        flow::{FlowController, SeekOutcome, SilenceTrimmer, TrimStats},
+        playback::handle::{PlaybackState, TrackSignals},
     },
-    config::player::PlayerConfig,
+    config::player::{DuckingConfig, FadeConfig, PlayerConfig},
 };
 
 pub struct AudioMixer {
     pub layers: HashMap<String, MixLayer>,
     pub max_layers: usize,
     pub enabled: bool,
-    acc_buf: Vec<i32>,
+    /// Sum of the layers for the current frame.
+    frame: Vec<f32>,
 }
 
 impl Default for AudioMixer {
@@ -39,7 +43,7 @@ impl AudioMixer {
             layers: HashMap::new(),
             max_layers: MAX_LAYERS,
             enabled: true,
-            acc_buf: Vec::with_capacity(1920),
+            frame: Vec::new(),
         }
     }
 
@@ -67,18 +71,13 @@ impl AudioMixer {
         }
     }
 
-    pub fn mix(&mut self, main_frame: &mut [i16]) {
+    /// Sums the layers into a frame of `len` samples, kept until the next
+    /// call.
+    pub fn render(&mut self, len: usize) -> &[f32] {
+        self.frame.clear();
+        self.frame.resize(len, 0.0);
         if !self.enabled || self.layers.is_empty() {
-            return;
-        }
-
-        let out_len = main_frame.len();
-        if self.acc_buf.len() != out_len {
-            self.acc_buf.resize(out_len, 0);
-        }
-
-        for (acc, &sample) in self.acc_buf.iter_mut().zip(main_frame.iter()) {
-            *acc = sample as i32;
+            return &self.frame;
         }
 
         self.layers.retain(|_, layer| {
@@ -87,48 +86,82 @@ impl AudioMixer {
         });
 
         for layer in self.layers.values_mut() {
-            layer.accumulate(&mut self.acc_buf);
-        }
-
-        for (out, &sum) in main_frame.iter_mut().zip(self.acc_buf.iter()) {
-            *out = sum.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+            layer.accumulate(&mut self.frame);
         }
+        &self.frame
     }
 }
 
 pub struct Mixer {
     tracks: Vec<MixerTrack>,
-    mix_buf: Vec<i32>,
     pub audio_mixer: AudioMixer,
-    opus_passthrough_track: Option<usize>, // index of track providing opus passthrough
-    final_pcm_buf: Vec<i16>,
+    /// Source Opus packet identical to the last mixed frame, when a single
+    /// untouched Opus track made up all of it.
+    passthrough: Option<Vec<u8>>,
+    ducker: Ducker,
 }
 
-// PassthroughTrack is now implicitly handled by MixerTrack via FlowController's latest_opus
-
 struct MixerTrack {
     flow: FlowController,
-    pending: Vec<i16>,
+    pending: Vec<f32>,
     pending_pos: usize,
     state: Arc<AtomicU8>,
     volume: Arc<AtomicU32>,
     position: Arc<AtomicU64>,
+    /// Samples this track has really played; `position` is set ahead of it
+    /// while a seek is pending and restored from it if the seek fails.
+    played: u64,
     is_buffering: Arc<AtomicBool>,
     config: PlayerConfig,
     finished: bool,
+    signals: Arc<TrackSignals>,
+}
+
+impl MixerTrack {
+    fn advance(&mut self, samples: u64) {
+        self.played += samples;
+        self.position.fetch_add(samples, Ordering::Relaxed);
+    }
+
+    /// Moves the position to where the decoder landed, or back to what was
+    /// actually played when the seek failed.
+    fn apply_seek(&mut self, seek: SeekOutcome) {
+        if let SeekOutcome::Landed(ms) = seek {
+            self.played = ms * TARGET_SAMPLE_RATE as u64 / 1000;
+        }
+        self.position.store(self.played, Ordering::Release);
+        self.signals.seek_finished(seek);
+    }
 }
 
 impl Mixer {
     pub fn new(_sample_rate: u32) -> Self {
         Self {
             tracks: Vec::new(),
-            mix_buf: Vec::with_capacity(1920),
             audio_mixer: AudioMixer::new(),
-            opus_passthrough_track: None,
-            final_pcm_buf: Vec::with_capacity(1920),
+            passthrough: None,
+            ducker: Ducker::new(),
         }
     }
 
+    /// Ducking of this mixer's tracks, for reporting.
+    pub fn duck_state(&self) -> Arc<DuckState> {
+        self.ducker.state()
+    }
+
+    pub fn set_ducking(&mut self, config: &DuckingConfig) {
+        self.ducker.configure(config);
+    }
+
+    /// Fades the playing tracks with `config` from their next ramp.
+    pub fn set_fade(&mut self, config: &FadeConfig) {
+        for track in self.tracks.iter_mut() {
+            track.config.fade = *config;
+            track.flow.set_fade_in(config.fade_in_ms, config.curve);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn add_track(
         &mut self,
         rx: Receiver<AudioFrame>,
@@ -136,11 +169,19 @@ impl Mixer {
         volume: Arc<AtomicU32>,
         position: Arc<AtomicU64>,
         is_buffering: Arc<AtomicBool>,
+        trim: Arc<TrimStats>,
+        signals: Arc<TrackSignals>,
         config: PlayerConfig,
     ) {
         let vol_raw = f32::from_bits(volume.load(Ordering::Acquire));
         let mut flow = FlowController::for_mixer(rx, TARGET_SAMPLE_RATE, MIXER_CHANNELS, vol_raw);
         flow.volume.set_volume_instant(vol_raw);
+        flow.set_fade_in(config.fade.fade_in_ms, config.fade.curve);
+        flow.fade_in();
+        if config.silence.enabled {
+            flow.set_silence_trim(SilenceTrimmer::new(&config.silence, trim));
+        }
+        self.ducker.configure(&config.ducking);
 
         self.tracks.push(MixerTrack {
             flow,
@@ -148,36 +189,19 @@ impl Mixer {
             pending_pos: 0,
             state,
             volume,
+            played: position.load(Ordering::Acquire),
             position,
             is_buffering,
             config,
             finished: false,
+            signals,
         });
     }
 
-    pub fn set_passthrough_track(&mut self, track_index: usize) {
-        self.opus_passthrough_track = Some(track_index);
-    }
-
-    pub fn take_opus_frame(&mut self) -> Option<Vec<u8>> {
-        for track in self.tracks.iter_mut() {
-            let state = PlaybackState::from(track.state.load(Ordering::Acquire));
-            if matches!(
-                state,
-                PlaybackState::Paused
-                    | PlaybackState::Stopped
-                    | PlaybackState::Stopping
-                    | PlaybackState::Starting
-            ) {
-                continue;
-            }
-
-            if let Some(packet) = track.flow.take_opus() {
-                track.position.fetch_add(960, Ordering::Relaxed);
-                return Some(packet);
-            }
-        }
-        None
+    /// Packet that can be sent instead of encoding the frame last returned
+    /// by [`Self::mix`]; only valid for that frame.
+    pub fn take_passthrough(&mut self) -> Option<Vec<u8>> {
+        self.passthrough.take()
     }
 
     pub fn stop_all(&mut self) {
@@ -190,18 +214,18 @@ impl Mixer {
         self.audio_mixer.enabled = false;
     }
 
-    pub fn mix(&mut self, buf: &mut [i16]) -> bool {
+    /// Sums the tracks and layers into `buf` without clamping; the output
+    /// stage limits the result once before encoding.
+    pub fn mix(&mut self, buf: &mut [f32]) -> bool {
         let out_len = buf.len();
-
-        if self.mix_buf.len() != out_len {
-            self.mix_buf.resize(out_len, 0);
-        }
-        self.mix_buf.fill(0);
+        buf.fill(0.0);
 
         self.tracks
             .retain(|t| t.state.load(Ordering::Acquire) != PlaybackState::Stopped as u8);
 
         let mut has_audio = false;
+        let mut contributing = 0usize;
+        let mut passthrough = None;
 
         for track in self.tracks.iter_mut() {
             let state = PlaybackState::from(track.state.load(Ordering::Acquire));
@@ -229,19 +253,32 @@ impl Mixer {
                 );
             }
 
+            if track.signals.take_fade_in() {
+                track.flow.fade_in();
+            }
+            if state == PlaybackState::FadingOut && track.flow.fade.target_gain() > 0.0 {
+                track.flow.fade.fade_to(
+                    0.0,
+                    track.config.fade.fade_out_ms,
+                    track.config.fade.curve,
+                    TARGET_SAMPLE_RATE,
+                );
+            }
+
             let mut filled = 0usize;
 
             // 1. Drain pending buffer
             if track.pending_pos < track.pending.len() {
                 let n = (out_len - filled).min(track.pending.len() - track.pending_pos);
-                for (acc, &s) in self.mix_buf[filled..filled + n]
+                for (acc, &s) in buf[filled..filled + n]
                     .iter_mut()
                     .zip(&track.pending[track.pending_pos..track.pending_pos + n])
                 {
-                    *acc += s as i32;
+                    *acc += s;
                 }
                 track.pending_pos += n;
                 filled += n;
+                track.advance((n / MIXER_CHANNELS) as u64);
 
                 if track.pending_pos >= track.pending.len() {
                     track.pending.clear();
@@ -250,36 +287,50 @@ impl Mixer {
             }
 
             // 2. Pull new frames from flow
-            'pull: while filled < out_len && !track.finished {
-                match track.flow.try_pop_frame() {
+            while filled < out_len && !track.finished {
+                let popped = match track.flow.try_pop_frame() {
                     Ok(Some(frame)) => {
                         let n = frame.len().min(out_len - filled);
-                        for (acc, &s) in
-                            self.mix_buf[filled..filled + n].iter_mut().zip(&frame[..n])
-                        {
-                            *acc += s as i32;
+                        for (acc, &s) in buf[filled..filled + n].iter_mut().zip(&frame[..n]) {
+                            *acc += s;
                         }
 
                         if n < frame.len() {
                             track.pending.extend_from_slice(&frame[n..]);
                             track.pending_pos = 0;
                         }
-                        filled += n;
-                        crate::audio::buffer::release_buffer(frame);
+                        Some((n, filled == 0 && n == frame.len() && n == out_len))
                     }
-                    Ok(None) => break 'pull,
+                    Ok(None) => None,
                     Err(_) => {
                         track.finished = true;
-                        break 'pull;
+                        None
                     }
+                };
+
+                // A seek marker read during this pop precedes the frame it
+                // returned, so the new position is applied first.
+                if let Some(seek) = track.flow.take_seek() {
+                    track.apply_seek(seek);
+                }
+                let skipped = track.flow.take_skipped();
+                if skipped > 0 {
+                    track.advance(skipped);
                 }
+
+                let Some((n, whole)) = popped else {
+                    break;
+                };
+                if whole {
+                    passthrough = track.flow.take_frame_opus();
+                }
+                filled += n;
+                track.advance((n / MIXER_CHANNELS) as u64);
             }
 
             if filled > 0 {
                 has_audio = true;
-                track
-                    .position
-                    .fetch_add(filled as u64 / MIXER_CHANNELS as u64, Ordering::Relaxed);
+                contributing += 1;
                 track.is_buffering.store(false, Ordering::Release);
             } else if !track.finished {
                 track.is_buffering.store(true, Ordering::Release);
@@ -291,6 +342,21 @@ impl Mixer {
                     .store(PlaybackState::Stopped as u8, Ordering::Release);
             }
 
+            if state == PlaybackState::FadingOut
+                && track.flow.fade.is_done()
+                && track
+                    .state
+                    .compare_exchange(
+                        PlaybackState::FadingOut as u8,
+                        PlaybackState::Paused as u8,
+                        Ordering::AcqRel,
+                        Ordering::Acquire,
+                    )
+                    .is_ok()
+            {
+                track.signals.fade_finished();
+            }
+
             if track.flow.tape.check_ramp_completed() {
                 match state {
                     PlaybackState::Stopping => {
@@ -308,20 +374,253 @@ impl Mixer {
             }
         }
 
-        if self.final_pcm_buf.len() != out_len {
-            self.final_pcm_buf.resize(out_len, 0);
-        }
-
-        for (final_pcm, &sum) in self.final_pcm_buf.iter_mut().zip(self.mix_buf.iter()) {
-            *final_pcm = sum.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+        // Layers are filled first so the tracks can be ducked under them.
+        let layers = self.audio_mixer.render(out_len);
+        let audible = layers.iter().any(|s| s.abs() > AUDIBLE_LEVEL);
+        self.ducker.process(buf, audible);
+        for (acc, &s) in buf.iter_mut().zip(layers) {
+            *acc += s;
         }
-
-        self.audio_mixer.mix(&mut self.final_pcm_buf);
         if !self.audio_mixer.layers.is_empty() {
             has_audio = true;
         }
 
-        buf.copy_from_slice(&self.final_pcm_buf);
+        self.passthrough = passthrough.filter(|_| {
+            contributing == 1 && self.audio_mixer.layers.is_empty() && self.ducker.is_idle()
+        });
+
         has_audio
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::constants::FRAME_SIZE_SAMPLES;
+
+    /// Default settings without the start fade-in, so frames mix untouched.
+    fn unfaded_config() -> PlayerConfig {
+        let mut config = PlayerConfig::default();
+        config.fade.fade_in_ms = 0;
+        config
+    }
+
+    #[test]
+    fn test_fade_out_ramps_to_silence_then_pauses() {
+        let (tx, rx) = flume::unbounded();
+        for _ in 0..10 {
+            tx.send(AudioFrame::Pcm(vec![10_000; FRAME_SIZE_SAMPLES]))
+                .unwrap();
+        }
+
+        let mut config = unfaded_config();
+        config.fade.fade_out_ms = 40;
+        let state = Arc::new(AtomicU8::new(PlaybackState::FadingOut as u8));
+        let mut mixer = Mixer::new(TARGET_SAMPLE_RATE);
+        mixer.add_track(
+            rx,
+            state.clone(),
+            Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(TrimStats::default()),
+            Arc::new(TrackSignals::default()),
+            config,
+        );
+
+        let mut buf = vec![0.0f32; FRAME_SIZE_SAMPLES];
+        mixer.mix(&mut buf);
+        assert_eq!(
+            state.load(Ordering::Acquire),
+            PlaybackState::FadingOut as u8
+        );
+        assert!(buf[0] > buf[FRAME_SIZE_SAMPLES - 1]);
+
+        mixer.mix(&mut buf);
+        assert_eq!(state.load(Ordering::Acquire), PlaybackState::Paused as u8);
+        assert!(buf[FRAME_SIZE_SAMPLES - 1].abs() < 1.0);
+    }
+
+    #[test]
+    fn test_faded_track_stays_silent_until_faded_in() {
+        let (tx, rx) = flume::unbounded();
+        for _ in 0..10 {
+            tx.send(AudioFrame::Pcm(vec![10_000; FRAME_SIZE_SAMPLES]))
+                .unwrap();
+        }
+
+        let mut config = unfaded_config();
+        config.fade.fade_out_ms = 10;
+        let fade = config.fade;
+        let state = Arc::new(AtomicU8::new(PlaybackState::FadingOut as u8));
+        let signals = Arc::new(TrackSignals::default());
+        let mut mixer = Mixer::new(TARGET_SAMPLE_RATE);
+        mixer.add_track(
+            rx,
+            state.clone(),
+            Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(TrimStats::default()),
+            signals.clone(),
+            config,
+        );
+
+        let mut buf = vec![0.0f32; FRAME_SIZE_SAMPLES];
+        mixer.mix(&mut buf);
+        assert_eq!(state.load(Ordering::Acquire), PlaybackState::Paused as u8);
+
+        // Resumed for a seek that never reports back.
+        state.store(PlaybackState::Playing as u8, Ordering::Release);
+        for _ in 0..3 {
+            mixer.mix(&mut buf);
+            assert!(buf.iter().all(|s| s.abs() < 1.0));
+        }
+
+        mixer.set_fade(&FadeConfig {
+            fade_in_ms: 10,
+            ..fade
+        });
+        signals.request_fade_in();
+        mixer.mix(&mut buf);
+        assert!((buf[FRAME_SIZE_SAMPLES - 1] - 10_000.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_untouched_opus_frames_pass_through() {
+        let (tx, rx) = flume::unbounded();
+        for i in 0..3u8 {
+            tx.send(AudioFrame::Passthrough {
+                pcm: vec![1000; FRAME_SIZE_SAMPLES],
+                packet: vec![i],
+            })
+            .unwrap();
+        }
+
+        let config = unfaded_config();
+        let volume = Arc::new(AtomicU32::new(1.0f32.to_bits()));
+        let mut mixer = Mixer::new(TARGET_SAMPLE_RATE);
+        mixer.add_track(
+            rx,
+            Arc::new(AtomicU8::new(PlaybackState::Playing as u8)),
+            volume.clone(),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(TrimStats::default()),
+            Arc::new(TrackSignals::default()),
+            config,
+        );
+
+        let mut buf = vec![0.0f32; FRAME_SIZE_SAMPLES];
+        assert!(mixer.mix(&mut buf));
+        assert_eq!(mixer.take_passthrough(), Some(vec![0]));
+
+        // A volume change alters the PCM, so the frame has to be re-encoded.
+        volume.store(0.5f32.to_bits(), Ordering::Release);
+        assert!(mixer.mix(&mut buf));
+        assert_eq!(mixer.take_passthrough(), None);
+    }
+
+    #[test]
+    fn test_silence_is_trimmed_at_both_ends() {
+        let (tx, rx) = flume::unbounded();
+        let frames = [(10, 0), (5, 10_000), (20, 0)];
+        for (count, sample) in frames {
+            for _ in 0..count {
+                tx.send(AudioFrame::Pcm(vec![sample; FRAME_SIZE_SAMPLES]))
+                    .unwrap();
+            }
+        }
+        drop(tx);
+
+        let mut config = unfaded_config();
+        config.silence.enabled = true;
+        config.silence.min_duration_ms = 100;
+        let state = Arc::new(AtomicU8::new(PlaybackState::Playing as u8));
+        let position = Arc::new(AtomicU64::new(0));
+        let trim = Arc::new(TrimStats::default());
+        let mut mixer = Mixer::new(TARGET_SAMPLE_RATE);
+        mixer.add_track(
+            rx,
+            state.clone(),
+            Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            position.clone(),
+            Arc::new(AtomicBool::new(false)),
+            trim.clone(),
+            Arc::new(TrackSignals::default()),
+            config,
+        );
+
+        let mut buf = vec![0.0f32; FRAME_SIZE_SAMPLES];
+        assert!(mixer.mix(&mut buf));
+        assert!(buf[0] > 0.0);
+        assert_eq!(position.load(Ordering::Acquire), 200 * 48 + 960);
+        assert_eq!(trim.leading_ms(), 200);
+
+        let mut mixed = 1;
+        while state.load(Ordering::Acquire) != PlaybackState::Stopped as u8 {
+            mixer.mix(&mut buf);
+            mixed += 1;
+            assert!(
+                mixed < 35,
+                "the track should end before its trailing silence"
+            );
+        }
+        // 5 audible frames, then 100 ms of silence before the rest is dropped.
+        assert_eq!(trim.trailing_ms(), 300);
+    }
+
+    #[test]
+    fn test_audible_layer_ducks_the_track() {
+        let (tx, rx) = flume::unbounded();
+        for _ in 0..10 {
+            tx.send(AudioFrame::Pcm(vec![10_000; FRAME_SIZE_SAMPLES]))
+                .unwrap();
+        }
+
+        let mut config = unfaded_config();
+        config.ducking = DuckingConfig {
+            enabled: true,
+            amount_db: 20.0,
+            attack_ms: 20,
+            hold_ms: 0,
+            release_ms: 20,
+        };
+        let mut mixer = Mixer::new(TARGET_SAMPLE_RATE);
+        mixer.add_track(
+            rx,
+            Arc::new(AtomicU8::new(PlaybackState::Playing as u8)),
+            Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(TrimStats::default()),
+            Arc::new(TrackSignals::default()),
+            config,
+        );
+        let duck = mixer.duck_state();
+
+        let mut buf = vec![0.0f32; FRAME_SIZE_SAMPLES];
+        mixer.mix(&mut buf);
+        assert!((buf[FRAME_SIZE_SAMPLES - 1] - 10_000.0).abs() < 1.0);
+
+        let (layer_tx, layer_rx) = flume::unbounded();
+        layer_tx.send(vec![1000; FRAME_SIZE_SAMPLES]).unwrap();
+        mixer
+            .audio_mixer
+            .add_layer("tts".to_owned(), layer_rx, 1.0)
+            .unwrap();
+
+        // The track reaches -20 dB within the frame; the layer is not ducked.
+        mixer.mix(&mut buf);
+        assert!(duck.is_active());
+        assert!((buf[FRAME_SIZE_SAMPLES - 1] - 2_000.0).abs() < 1.0);
+        assert_eq!(mixer.take_passthrough(), None);
+
+        // Once the layer runs dry the track is released back to full level.
+        mixer.mix(&mut buf);
+        assert!(!duck.is_active());
+        assert!(buf[0] < 2_000.0);
+        assert!((buf[FRAME_SIZE_SAMPLES - 1] - 10_000.0).abs() < 1.0);
+        assert_eq!(duck.gain(), 1.0);
+    }
+}
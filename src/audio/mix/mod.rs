@@ -1,5 +1,7 @@
+pub mod duck;
 pub mod layer;
 pub mod mixer;
 
+pub use duck::{DuckState, Ducker};
 pub use layer::MixLayer;
 pub use mixer::{AudioMixer, Mixer};
@@ -5,14 +5,19 @@ pub mod demux;
 pub mod effects;
 pub mod engine;
 pub mod error;
+pub mod features;
 pub mod filters;
 pub mod flow;
 pub mod frame;
 pub mod mix;
+pub mod mux;
 pub mod playback;
 pub mod processor;
 pub mod resample;
 pub mod source;
+#[cfg(test)]
+pub(crate) mod test_util;
+pub mod worker;
 
 pub use buffer::{BufferPool, PooledBuffer, RingBuffer, get_byte_pool};
 pub use flow::FlowController;
@@ -0,0 +1,5 @@
+pub mod ogg;
+pub mod wav;
+
+pub use ogg::OggOpusWriter;
+pub use wav::{RF64_HEADER_LEN, WAV_HEADER_LEN, rf64_header, wav_header};
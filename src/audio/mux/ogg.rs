@@ -0,0 +1,168 @@
+//! Minimal Ogg/Opus muxer (RFC 7845) for raw 20 ms Opus packets.
+
+use crate::audio::constants::{MIXER_CHANNELS, TARGET_SAMPLE_RATE};
+
+const OPUS_PRE_SKIP: u16 = 312;
+const SAMPLES_PER_PACKET: u64 = 960;
+const VENDOR: &[u8] = b"rustalink";
+
+const HEADER_BOS: u8 = 0x02;
+const HEADER_EOS: u8 = 0x04;
+
+/// Wraps Opus packets into Ogg pages, one packet per page so consumers see
+/// each frame as soon as it is written.
+pub struct OggOpusWriter {
+    serial: u32,
+    sequence: u32,
+    granule: u64,
+}
+
+impl OggOpusWriter {
+    pub fn new(serial: u32) -> Self {
+        Self {
+            serial,
+            sequence: 0,
+            granule: 0,
+        }
+    }
+
+    /// The `OpusHead` and `OpusTags` pages that must start every stream.
+    pub fn headers(&mut self) -> Vec<u8> {
+        let mut head = Vec::with_capacity(19);
+        head.extend_from_slice(b"OpusHead");
+        head.push(1); // version
+        head.push(MIXER_CHANNELS as u8);
+        head.extend_from_slice(&OPUS_PRE_SKIP.to_le_bytes());
+        head.extend_from_slice(&TARGET_SAMPLE_RATE.to_le_bytes());
+        head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+        head.push(0); // channel mapping family
+
+        let mut tags = Vec::with_capacity(16 + VENDOR.len());
+        tags.extend_from_slice(b"OpusTags");
+        tags.extend_from_slice(&(VENDOR.len() as u32).to_le_bytes());
+        tags.extend_from_slice(VENDOR);
+        tags.extend_from_slice(&0u32.to_le_bytes()); // user comments
+
+        let mut out = self.page(&head, 0, HEADER_BOS);
+        out.extend(self.page(&tags, 0, 0));
+        out
+    }
+
+    /// One page carrying a single 20 ms packet.
+    pub fn packet(&mut self, packet: &[u8]) -> Vec<u8> {
+        self.granule += SAMPLES_PER_PACKET;
+        self.page(packet, self.granule, 0)
+    }
+
+    /// Accounts for `count` packets that were dropped so playback time stays
+    /// in step with the source.
+    pub fn skip(&mut self, count: u64) {
+        self.granule += count * SAMPLES_PER_PACKET;
+    }
+
+    /// An end-of-stream page with no packets.
+    pub fn finish(&mut self) -> Vec<u8> {
+        self.page(&[], self.granule, HEADER_EOS)
+    }
+
+    fn page(&mut self, data: &[u8], granule: u64, header_type: u8) -> Vec<u8> {
+        // An empty page carries no packet at all rather than a zero-length one.
+        let mut lacing = vec![255u8; data.len() / 255];
+        if !data.is_empty() {
+            lacing.push((data.len() % 255) as u8);
+        }
+
+        let mut page = Vec::with_capacity(27 + lacing.len() + data.len());
+        page.extend_from_slice(b"OggS");
+        page.push(0); // stream structure version
+        page.push(header_type);
+        page.extend_from_slice(&granule.to_le_bytes());
+        page.extend_from_slice(&self.serial.to_le_bytes());
+        page.extend_from_slice(&self.sequence.to_le_bytes());
+        page.extend_from_slice(&[0; 4]); // checksum, filled below
+        page.push(lacing.len() as u8);
+        page.extend_from_slice(&lacing);
+        page.extend_from_slice(data);
+
+        let crc = crc32(&page);
+        page[22..26].copy_from_slice(&crc.to_le_bytes());
+
+        self.sequence = self.sequence.wrapping_add(1);
+        page
+    }
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut r = (i as u32) << 24;
+        let mut bit = 0;
+        while bit < 8 {
+            r = if r & 0x8000_0000 != 0 {
+                (r << 1) ^ 0x04c1_1db7
+            } else {
+                r << 1
+            };
+            bit += 1;
+        }
+        table[i] = r;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    data.iter().fold(0u32, |crc, &b| {
+        (crc << 8) ^ CRC_TABLE[((crc >> 24) as u8 ^ b) as usize]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use symphonia::core::{
+        codecs::CODEC_TYPE_OPUS,
+        formats::{FormatOptions, FormatReader},
+        io::MediaSourceStream,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_packets_round_trip_through_ogg_reader() {
+        let mut writer = OggOpusWriter::new(0x1234_5678);
+        let mut bytes = writer.headers();
+        let packets: Vec<Vec<u8>> = (0..5u8).map(|i| vec![0xfc, i, i, i]).collect();
+        for p in &packets {
+            bytes.extend(writer.packet(p));
+        }
+        bytes.extend(writer.finish());
+
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(bytes)), Default::default());
+        let mut reader =
+            symphonia::default::formats::OggReader::try_new(mss, &FormatOptions::default())
+                .expect("valid ogg stream");
+
+        let track = reader.default_track().expect("opus track");
+        assert_eq!(track.codec_params.codec, CODEC_TYPE_OPUS);
+        assert_eq!(track.codec_params.channels.map(|c| c.count()), Some(2));
+
+        let mut read = Vec::new();
+        while let Ok(packet) = reader.next_packet() {
+            read.push(packet.data.to_vec());
+        }
+        assert_eq!(read, packets);
+    }
+
+    #[test]
+    fn test_large_packet_lacing() {
+        let mut writer = OggOpusWriter::new(1);
+        let page = writer.packet(&[7u8; 510]);
+        // 510 bytes = two full segments plus a terminating zero-length one.
+        assert_eq!(page[26], 3);
+        assert_eq!(&page[27..30], &[255, 255, 0]);
+        assert_eq!(page.len(), 30 + 510);
+    }
+}
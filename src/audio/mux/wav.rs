@@ -0,0 +1,124 @@
+//! Canonical 16-bit PCM WAV header for the mixer's output format.
+
+use crate::audio::constants::{MIXER_CHANNELS, TARGET_SAMPLE_RATE};
+
+pub const WAV_HEADER_LEN: usize = 44;
+/// [`WAV_HEADER_LEN`] plus room for a `ds64` chunk.
+pub const RF64_HEADER_LEN: usize = WAV_HEADER_LEN + 36;
+
+/// Builds a header for `data_len` bytes of interleaved 48 kHz stereo `i16`.
+///
+/// Writers emit it with a zero length up front and rewrite it once the final
+/// size is known.
+pub fn wav_header(data_len: u32) -> [u8; WAV_HEADER_LEN] {
+    let block_align = (MIXER_CHANNELS * 2) as u16;
+    let byte_rate = TARGET_SAMPLE_RATE * block_align as u32;
+
+    let mut h = [0u8; WAV_HEADER_LEN];
+    h[0..4].copy_from_slice(b"RIFF");
+    h[4..8].copy_from_slice(&data_len.saturating_add(36).to_le_bytes());
+    h[8..16].copy_from_slice(b"WAVEfmt ");
+    h[16..20].copy_from_slice(&16u32.to_le_bytes());
+    h[20..22].copy_from_slice(&1u16.to_le_bytes()); // PCM
+    h[22..24].copy_from_slice(&(MIXER_CHANNELS as u16).to_le_bytes());
+    h[24..28].copy_from_slice(&TARGET_SAMPLE_RATE.to_le_bytes());
+    h[28..32].copy_from_slice(&byte_rate.to_le_bytes());
+    h[32..34].copy_from_slice(&block_align.to_le_bytes());
+    h[34..36].copy_from_slice(&16u16.to_le_bytes());
+    h[36..40].copy_from_slice(b"data");
+    h[40..44].copy_from_slice(&data_len.to_le_bytes());
+    h
+}
+
+/// Like [`wav_header`], for output that may pass 4 GiB.
+///
+/// A 36-byte chunk follows `WAVE`: a `JUNK` placeholder while the data fits a
+/// plain WAV, and the `ds64` sizes of an RF64 file once it does not, so the
+/// header can be rewritten in place either way.
+pub fn rf64_header(data_len: u64) -> [u8; RF64_HEADER_LEN] {
+    let base = wav_header(u32::try_from(data_len).unwrap_or(u32::MAX));
+    let riff_len = data_len + (RF64_HEADER_LEN - 8) as u64;
+
+    let mut h = [0u8; RF64_HEADER_LEN];
+    h[0..12].copy_from_slice(&base[0..12]);
+    h[16..20].copy_from_slice(&28u32.to_le_bytes());
+    h[48..].copy_from_slice(&base[12..]);
+
+    match u32::try_from(riff_len) {
+        Ok(len) => {
+            h[4..8].copy_from_slice(&len.to_le_bytes());
+            h[12..16].copy_from_slice(b"JUNK");
+        }
+        Err(_) => {
+            let block_align = (MIXER_CHANNELS * 2) as u64;
+            h[0..4].copy_from_slice(b"RF64");
+            h[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+            h[12..16].copy_from_slice(b"ds64");
+            h[20..28].copy_from_slice(&riff_len.to_le_bytes());
+            h[28..36].copy_from_slice(&data_len.to_le_bytes());
+            h[36..44].copy_from_slice(&(data_len / block_align).to_le_bytes());
+            h[76..80].copy_from_slice(&u32::MAX.to_le_bytes());
+        }
+    }
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use symphonia::core::{
+        formats::{FormatOptions, FormatReader},
+        io::MediaSourceStream,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_header_is_readable() {
+        let mut bytes = wav_header(960 * 4).to_vec();
+        bytes.extend(std::iter::repeat_n(0u8, 960 * 4));
+
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(bytes)), Default::default());
+        let reader =
+            symphonia::default::formats::WavReader::try_new(mss, &FormatOptions::default())
+                .expect("valid wav");
+        let params = &reader.default_track().expect("track").codec_params;
+
+        assert_eq!(params.sample_rate, Some(48_000));
+        assert_eq!(params.channels.map(|c| c.count()), Some(2));
+        assert_eq!(params.n_frames, Some(960));
+    }
+
+    #[test]
+    fn test_rf64_header_is_plain_wav_while_small() {
+        let mut bytes = rf64_header(960 * 4).to_vec();
+        bytes.extend(std::iter::repeat_n(0u8, 960 * 4));
+        assert_eq!(&bytes[12..16], b"JUNK");
+
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(bytes)), Default::default());
+        let reader =
+            symphonia::default::formats::WavReader::try_new(mss, &FormatOptions::default())
+                .expect("valid wav");
+        let params = &reader.default_track().expect("track").codec_params;
+        assert_eq!(params.n_frames, Some(960));
+    }
+
+    #[test]
+    fn test_rf64_header_switches_past_4_gib() {
+        let data_len = 5u64 << 30;
+        let h = rf64_header(data_len);
+
+        assert_eq!(&h[0..4], b"RF64");
+        assert_eq!(&h[4..8], &u32::MAX.to_le_bytes());
+        assert_eq!(&h[12..16], b"ds64");
+        assert_eq!(u64::from_le_bytes(h[28..36].try_into().unwrap()), data_len);
+        assert_eq!(
+            u64::from_le_bytes(h[36..44].try_into().unwrap()),
+            data_len / 4
+        );
+        assert_eq!(&h[48..52], b"fmt ");
+        assert_eq!(&h[72..76], b"data");
+        assert_eq!(&h[76..80], &u32::MAX.to_le_bytes());
+    }
+}
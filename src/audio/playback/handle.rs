@@ -3,7 +3,84 @@ use std::sync::{
     atomic::{AtomicBool, AtomicU8, AtomicU32, AtomicU64, Ordering},
 };
 
-use crate::audio::{constants::OPUS_SAMPLE_RATE, processor::DecoderCommand};
+use tokio::sync::{Notify, futures::Notified};
+
+use crate::audio::{
+    constants::OPUS_SAMPLE_RATE,
+    flow::{SeekOutcome, TrimStats},
+    processor::DecoderCommand,
+};
+
+/// What the mixer reports back about a track's fades and seeks, and the
+/// fade-ins asked of it.
+#[derive(Default)]
+pub struct TrackSignals {
+    fade_done: Notify,
+    seek_done: Notify,
+    /// Set when a seek fails, until the monitor reports it.
+    seek_failed: AtomicBool,
+    /// Set until the mixer fades the track back in.
+    fade_in: AtomicBool,
+}
+
+impl TrackSignals {
+    /// Resolves once a fade-out has reached silence.
+    pub fn fade_done(&self) -> Notified<'_> {
+        self.fade_done.notified()
+    }
+
+    /// Resolves once the decoder has finished a seek, landed or failed.
+    pub fn seek_done(&self) -> Notified<'_> {
+        self.seek_done.notified()
+    }
+
+    pub fn fade_finished(&self) {
+        self.fade_done.notify_waiters();
+    }
+
+    pub fn seek_finished(&self, outcome: SeekOutcome) {
+        if outcome == SeekOutcome::Failed {
+            self.seek_failed.store(true, Ordering::Release);
+        }
+        self.seek_done.notify_waiters();
+    }
+
+    /// Whether a seek failed since the last call.
+    pub fn take_seek_failure(&self) -> bool {
+        self.seek_failed.swap(false, Ordering::AcqRel)
+    }
+
+    pub fn request_fade_in(&self) {
+        self.fade_in.store(true, Ordering::Release);
+    }
+
+    /// Whether a fade-in was asked for since the last call.
+    pub fn take_fade_in(&self) -> bool {
+        self.fade_in.swap(false, Ordering::AcqRel)
+    }
+}
+
+/// End of the audio fetched so far from a live stream, on the same timeline
+/// as the track position. Set by the source, read through the track handle.
+#[derive(Default)]
+pub struct LiveEdge {
+    live: AtomicBool,
+    edge_ms: AtomicU64,
+}
+
+impl LiveEdge {
+    pub fn set(&self, edge_ms: u64) {
+        self.edge_ms.store(edge_ms, Ordering::Release);
+        self.live.store(true, Ordering::Release);
+    }
+
+    /// `None` until the source has started playing as a live stream.
+    pub fn get(&self) -> Option<u64> {
+        self.live
+            .load(Ordering::Acquire)
+            .then(|| self.edge_ms.load(Ordering::Acquire))
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -13,6 +90,8 @@ pub enum PlaybackState {
     Stopped = 2,
     Stopping = 3,
     Starting = 4,
+    /// Ramping to silence; the mixer moves the track to `Paused` once done.
+    FadingOut = 5,
 }
 
 impl From<u8> for PlaybackState {
@@ -22,6 +101,7 @@ impl From<u8> for PlaybackState {
             1 => Self::Paused,
             3 => Self::Stopping,
             4 => Self::Starting,
+            5 => Self::FadingOut,
             _ => Self::Stopped,
         }
     }
@@ -35,12 +115,16 @@ pub struct TrackHandle {
     command_tx: flume::Sender<DecoderCommand>,
     tape_stop_enabled: Arc<AtomicBool>,
     is_buffering: Arc<AtomicBool>,
+    trim: Arc<TrimStats>,
+    signals: Arc<TrackSignals>,
+    live_edge: Option<Arc<LiveEdge>>,
 }
 
 impl TrackHandle {
     pub fn new(
         command_tx: flume::Sender<DecoderCommand>,
         tape_stop_enabled: Arc<AtomicBool>,
+        live_edge: Option<Arc<LiveEdge>>,
     ) -> (
         Self,
         Arc<AtomicU8>,
@@ -61,6 +145,9 @@ impl TrackHandle {
                 command_tx,
                 tape_stop_enabled,
                 is_buffering: is_buffering.clone(),
+                trim: Arc::new(TrimStats::default()),
+                signals: Arc::new(TrackSignals::default()),
+                live_edge,
             },
             state,
             volume,
@@ -87,6 +174,30 @@ impl TrackHandle {
         self.state.store(next_state as u8, Ordering::Release);
     }
 
+    /// Starts a fade to silence. Only a playing track fades; returns whether
+    /// one was started.
+    pub fn fade_out(&self) -> bool {
+        self.state
+            .compare_exchange(
+                PlaybackState::Playing as u8,
+                PlaybackState::FadingOut as u8,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .is_ok()
+    }
+
+    /// Resumes a faded-out track without a tape ramp.
+    pub fn resume(&self) {
+        self.state
+            .store(PlaybackState::Playing as u8, Ordering::Release);
+    }
+
+    /// Fades a track held silent back in, for when no seek report will.
+    pub fn fade_in(&self) {
+        self.signals.request_fade_in();
+    }
+
     pub fn stop(&self) {
         // SeqCst matches the ordering used by stop_signal in start_playback,
         // ensuring the stopped state is visible to all threads immediately.
@@ -114,6 +225,22 @@ impl TrackHandle {
         (samples * 1000) / OPUS_SAMPLE_RATE
     }
 
+    /// How far playback trails the live edge (ms), for live streams.
+    pub fn live_latency(&self) -> Option<u64> {
+        let edge_ms = self.live_edge.as_ref()?.get()?;
+        Some(edge_ms.saturating_sub(self.get_position()))
+    }
+
+    /// Silence trimmed from this track so far.
+    pub fn trim_stats(&self) -> Arc<TrimStats> {
+        self.trim.clone()
+    }
+
+    /// Fade and seek completions reported by the mixer.
+    pub fn signals(&self) -> Arc<TrackSignals> {
+        self.signals.clone()
+    }
+
     pub fn is_buffering(&self) -> bool {
         self.is_buffering.load(Ordering::Acquire)
     }
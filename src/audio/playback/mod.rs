@@ -1,3 +1,3 @@
 pub mod handle;
 
-pub use handle::{PlaybackState, TrackHandle};
+pub use handle::{LiveEdge, PlaybackState, TrackHandle, TrackSignals};
@@ -3,18 +3,18 @@ use std::io::ErrorKind;
 use flume::Receiver;
 use symphonia::core::{
     audio::SampleBuffer,
-    codecs::Decoder,
+    codecs::{CODEC_TYPE_OPUS, Decoder},
     errors::Error,
-    formats::{FormatReader, SeekMode, SeekTo},
+    formats::{FormatReader, SeekMode, SeekTo, SeekedTo},
     io::MediaSource,
-    units::Time,
+    units::{Time, TimeBase},
 };
 use tracing::{Level, debug, span, warn};
 
 use crate::{
     audio::{
         AudioFrame,
-        constants::{MIXER_CHANNELS, TARGET_SAMPLE_RATE},
+        constants::{FRAME_SIZE_SAMPLES, MIXER_CHANNELS, TARGET_SAMPLE_RATE},
         demux::{DemuxResult, open_format},
         engine::{BoxedEngine, StandardEngine},
         resample::Resampler,
@@ -29,6 +29,23 @@ pub enum DecoderCommand {
     Stop,
 }
 
+/// Result of a single cooperative [`AudioProcessor::step`] call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// At least one packet was decoded.
+    Progress,
+    /// The output buffer is full; nothing was decoded.
+    Blocked,
+    /// The stream ended, the consumer went away or a stop was requested.
+    Finished,
+}
+
+#[derive(Debug, PartialEq)]
+enum PacketOutcome {
+    Continue,
+    Finished,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum CommandOutcome {
     Stop,
@@ -50,7 +67,17 @@ pub struct AudioProcessor {
     channels: usize,
     config: PlayerConfig,
     recoverable_errors: u32,
+    packet_count: u64,
     downmix_buf: Vec<i16>,
+    time_base: Option<TimeBase>,
+    /// Opus at 48 kHz stereo: 20 ms packets are forwarded alongside their
+    /// PCM so the voice link can skip re-encoding them.
+    opus_passthrough: bool,
+    /// After a seek, decoded audio before this timestamp is dropped so
+    /// playback starts on the requested sample rather than the packet.
+    seek_target: Option<u64>,
+    /// Seek report still waiting for room in the output buffer.
+    pending: Option<AudioFrame>,
 }
 
 impl AudioProcessor {
@@ -93,6 +120,12 @@ impl AudioProcessor {
             sample_rate, channels
         );
 
+        let track = format.tracks().iter().find(|t| t.id == track_id);
+        let time_base = track.and_then(|t| t.codec_params.time_base);
+        let opus_passthrough = track.is_some_and(|t| t.codec_params.codec == CODEC_TYPE_OPUS)
+            && sample_rate == TARGET_SAMPLE_RATE
+            && channels == MIXER_CHANNELS;
+
         let resampler = if sample_rate == TARGET_SAMPLE_RATE {
             Resampler::linear(sample_rate, TARGET_SAMPLE_RATE, MIXER_CHANNELS)
         } else {
@@ -122,7 +155,12 @@ impl AudioProcessor {
             channels,
             config,
             recoverable_errors: 0,
+            packet_count: 0,
             downmix_buf: Vec::with_capacity(1920),
+            time_base,
+            opus_passthrough,
+            seek_target: None,
+            pending: None,
         })
     }
 
@@ -134,180 +172,259 @@ impl AudioProcessor {
             self.source_rate, self.channels, TARGET_SAMPLE_RATE
         );
 
-        let mut packet_count = 0u64;
         loop {
-            packet_count += 1;
             if self.check_commands() == CommandOutcome::Stop {
                 break;
             }
 
-            let packet = match self.format.next_packet() {
-                Ok(p) => p,
-                Err(Error::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
-                Err(e) => {
-                    self.send_error(format!("Packet read error: {e}"));
-                    return Err(e);
-                }
-            };
+            if let Some(frame) = self.pending.take()
+                && !self.engine.push(frame)
+            {
+                break;
+            }
+
+            if self.decode_next()? == PacketOutcome::Finished {
+                break;
+            }
+        }
+
+        debug!("Transcode loop finished");
+        Ok(())
+    }
+
+    /// Decodes up to `budget` packets without blocking on a full output buffer.
+    ///
+    /// Used by the shared decoder pool to time-slice many processors on a
+    /// small number of threads. Pending commands are always serviced, even
+    /// when the downstream buffer has no room for another frame; the frame
+    /// reporting a seek waits for room without holding up the worker.
+    pub fn step(&mut self, budget: usize) -> Result<StepOutcome, Error> {
+        let mut decoded = 0usize;
 
-            if packet.track_id() != self.track_id {
-                continue;
+        while decoded < budget {
+            if self.check_commands() == CommandOutcome::Stop || self.engine.is_closed() {
+                return Ok(StepOutcome::Finished);
             }
 
-            match self.decoder.decode(&packet) {
-                Ok(decoded) => {
-                    self.recoverable_errors = 0;
-                    let spec = *decoded.spec();
-                    let mut buf = self.sample_buf.take().unwrap_or_else(|| {
-                        SampleBuffer::<i16>::new(decoded.capacity() as u64, spec)
-                    });
+            if !self.flush_pending() || self.engine.fill_level() >= 1.0 {
+                break;
+            }
 
-                    buf.copy_interleaved_ref(decoded);
-                    let samples = buf.samples();
+            if self.decode_next()? == PacketOutcome::Finished {
+                debug!("Transcode loop finished");
+                return Ok(StepOutcome::Finished);
+            }
+            decoded += 1;
+        }
 
-                    if !samples.is_empty() {
-                        let frame_channels = spec.channels.count();
-                        let frame_rate = spec.rate;
+        Ok(if decoded > 0 {
+            StepOutcome::Progress
+        } else {
+            StepOutcome::Blocked
+        })
+    }
 
-                        if frame_rate != self.source_rate {
-                            debug!(
-                                "AudioProcessor: frame rate mismatch ({}Hz vs {}Hz) — re-initializing resampler",
-                                frame_rate, self.source_rate
-                            );
-                            self.source_rate = frame_rate;
-                            self.resampler = if self.source_rate == TARGET_SAMPLE_RATE {
-                                Resampler::linear(
+    /// Fraction of the downstream frame buffer currently occupied (0.0 – 1.0).
+    pub fn fill_level(&self) -> f32 {
+        self.engine.fill_level()
+    }
+
+    /// Whether this processor belongs on the background decode pool.
+    pub fn is_background(&self) -> bool {
+        self.config.background_decode
+    }
+
+    fn decode_next(&mut self) -> Result<PacketOutcome, Error> {
+        self.packet_count += 1;
+
+        let packet = match self.format.next_packet() {
+            Ok(p) => p,
+            Err(Error::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => {
+                return Ok(PacketOutcome::Finished);
+            }
+            Err(e) => {
+                self.send_error(format!("Packet read error: {e}"));
+                return Err(e);
+            }
+        };
+
+        if packet.track_id() != self.track_id {
+            return Ok(PacketOutcome::Continue);
+        }
+
+        match self.decoder.decode(&packet) {
+            Ok(decoded) => {
+                self.recoverable_errors = 0;
+                let spec = *decoded.spec();
+                let mut buf = self
+                    .sample_buf
+                    .take()
+                    .unwrap_or_else(|| SampleBuffer::<i16>::new(decoded.capacity() as u64, spec));
+
+                buf.copy_interleaved_ref(decoded);
+                let frame_channels = spec.channels.count();
+                let skip = self
+                    .seek_skip(packet.ts(), packet.dur(), spec.rate)
+                    .saturating_mul(frame_channels)
+                    .min(buf.samples().len());
+                let samples = &buf.samples()[skip..];
+
+                if !samples.is_empty() {
+                    let frame_rate = spec.rate;
+
+                    if frame_rate != self.source_rate {
+                        debug!(
+                            "AudioProcessor: frame rate mismatch ({}Hz vs {}Hz) — re-initializing resampler",
+                            frame_rate, self.source_rate
+                        );
+                        self.source_rate = frame_rate;
+                        self.resampler = if self.source_rate == TARGET_SAMPLE_RATE {
+                            Resampler::linear(self.source_rate, TARGET_SAMPLE_RATE, MIXER_CHANNELS)
+                        } else {
+                            match self.config.resampling_quality {
+                                ResamplingQuality::Low => Resampler::linear(
                                     self.source_rate,
                                     TARGET_SAMPLE_RATE,
                                     MIXER_CHANNELS,
-                                )
-                            } else {
-                                match self.config.resampling_quality {
-                                    ResamplingQuality::Low => Resampler::linear(
-                                        self.source_rate,
-                                        TARGET_SAMPLE_RATE,
-                                        MIXER_CHANNELS,
-                                    ),
-                                    ResamplingQuality::Medium => Resampler::hermite(
-                                        self.source_rate,
-                                        TARGET_SAMPLE_RATE,
-                                        MIXER_CHANNELS,
-                                    ),
-                                    ResamplingQuality::High => Resampler::sinc(
-                                        self.source_rate,
-                                        TARGET_SAMPLE_RATE,
-                                        MIXER_CHANNELS,
-                                    ),
-                                }
-                            };
-                        }
-
-                        let pcm_data = if frame_channels == MIXER_CHANNELS {
-                            samples
-                        } else {
-                            if packet_count.is_multiple_of(100) {
-                                debug!(
-                                    "AudioProcessor: Downmixing {}ch -> {}ch (samples: {})",
-                                    frame_channels,
+                                ),
+                                ResamplingQuality::Medium => Resampler::hermite(
+                                    self.source_rate,
+                                    TARGET_SAMPLE_RATE,
+                                    MIXER_CHANNELS,
+                                ),
+                                ResamplingQuality::High => Resampler::sinc(
+                                    self.source_rate,
+                                    TARGET_SAMPLE_RATE,
                                     MIXER_CHANNELS,
-                                    samples.len()
-                                );
+                                ),
                             }
-                            let num_frames = samples.len() / frame_channels;
-                            self.downmix_buf.clear();
-                            self.downmix_buf.reserve(num_frames * MIXER_CHANNELS);
-
-                            for i in 0..num_frames {
-                                let frame = &samples[i * frame_channels..(i + 1) * frame_channels];
-                                let mut l = 0i32;
-                                let mut r = 0i32;
-
-                                for (ch, &sample) in frame.iter().enumerate() {
-                                    if ch % 2 == 0 {
-                                        l += sample as i32;
-                                    } else {
-                                        r += sample as i32;
-                                    }
-                                }
+                        };
+                    }
 
-                                let left_count = frame_channels.div_ceil(2);
-                                let right_count = frame_channels / 2;
+                    let pcm_data = if frame_channels == MIXER_CHANNELS {
+                        samples
+                    } else {
+                        if self.packet_count.is_multiple_of(100) {
+                            debug!(
+                                "AudioProcessor: Downmixing {}ch -> {}ch (samples: {})",
+                                frame_channels,
+                                MIXER_CHANNELS,
+                                samples.len()
+                            );
+                        }
+                        let num_frames = samples.len() / frame_channels;
+                        self.downmix_buf.clear();
+                        self.downmix_buf.reserve(num_frames * MIXER_CHANNELS);
 
-                                self.downmix_buf.push((l / left_count as i32) as i16);
-                                if right_count > 0 {
-                                    self.downmix_buf.push((r / right_count as i32) as i16);
+                        for i in 0..num_frames {
+                            let frame = &samples[i * frame_channels..(i + 1) * frame_channels];
+                            let mut l = 0i32;
+                            let mut r = 0i32;
+
+                            for (ch, &sample) in frame.iter().enumerate() {
+                                if ch % 2 == 0 {
+                                    l += sample as i32;
                                 } else {
-                                    // Upmix mono to stereo
-                                    self.downmix_buf.push((l / left_count as i32) as i16);
+                                    r += sample as i32;
                                 }
                             }
-                            &self.downmix_buf[..]
-                        };
 
-                        let capacity = (pcm_data.len() as f64 * TARGET_SAMPLE_RATE as f64
-                            / self.source_rate as f64)
-                            .ceil() as usize
-                            + 32;
-                        let mut resampled = crate::audio::buffer::acquire_buffer(capacity);
-                        if self.resampler.is_passthrough() {
-                            resampled.extend_from_slice(pcm_data);
-                        } else {
-                            self.resampler.process(pcm_data, &mut resampled);
-                        }
+                            let left_count = frame_channels.div_ceil(2);
+                            let right_count = frame_channels / 2;
 
-                        if !resampled.is_empty() && !self.engine.push(AudioFrame::Pcm(resampled)) {
-                            return Ok(());
+                            self.downmix_buf.push((l / left_count as i32) as i16);
+                            if right_count > 0 {
+                                self.downmix_buf.push((r / right_count as i32) as i16);
+                            } else {
+                                // Upmix mono to stereo
+                                self.downmix_buf.push((l / left_count as i32) as i16);
+                            }
                         }
+                        &self.downmix_buf[..]
+                    };
+
+                    let capacity = (pcm_data.len() as f64 * TARGET_SAMPLE_RATE as f64
+                        / self.source_rate as f64)
+                        .ceil() as usize
+                        + 32;
+                    let mut resampled = crate::audio::buffer::acquire_buffer(capacity);
+                    if self.resampler.is_passthrough() {
+                        resampled.extend_from_slice(pcm_data);
+                    } else {
+                        self.resampler.process(pcm_data, &mut resampled);
                     }
 
-                    self.sample_buf = Some(buf);
-                }
-                Err(Error::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
-                Err(Error::DecodeError(e)) => {
-                    self.recoverable_errors += 1;
-                    if self.recoverable_errors == 1 {
-                        warn!("Decode error (recoverable): {e}");
-                    } else if self.recoverable_errors.is_multiple_of(100) {
-                        warn!(
-                            "Decode error (recoverable, x{}): {e}",
-                            self.recoverable_errors
-                        );
+                    if !resampled.is_empty() {
+                        let frame = if self.opus_passthrough
+                            && skip == 0
+                            && self.resampler.is_passthrough()
+                            && resampled.len() == FRAME_SIZE_SAMPLES
+                        {
+                            AudioFrame::Passthrough {
+                                pcm: resampled,
+                                packet: packet.data.to_vec(),
+                            }
+                        } else {
+                            AudioFrame::Pcm(resampled)
+                        };
+
+                        if !self.engine.push(frame) {
+                            return Ok(PacketOutcome::Finished);
+                        }
                     }
                 }
-                Err(e) => {
-                    self.send_error(format!("Decode error: {e}"));
-                    return Err(e);
+
+                self.sample_buf = Some(buf);
+            }
+            Err(Error::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => {
+                return Ok(PacketOutcome::Finished);
+            }
+            Err(Error::DecodeError(e)) => {
+                self.recoverable_errors += 1;
+                if self.recoverable_errors == 1 {
+                    warn!("Decode error (recoverable): {e}");
+                } else if self.recoverable_errors.is_multiple_of(100) {
+                    warn!(
+                        "Decode error (recoverable, x{}): {e}",
+                        self.recoverable_errors
+                    );
                 }
             }
+            Err(e) => {
+                self.send_error(format!("Decode error: {e}"));
+                return Err(e);
+            }
         }
 
-        debug!("Transcode loop finished");
-        Ok(())
+        Ok(PacketOutcome::Continue)
     }
 
     fn check_commands(&mut self) -> CommandOutcome {
         match self.cmd_rx.try_recv() {
             Ok(DecoderCommand::Seek(ms)) => {
                 let time = Time::from(ms as f64 / 1000.0);
-                if self
-                    .format
-                    .seek(
-                        SeekMode::Coarse,
-                        SeekTo::Time {
-                            time,
-                            track_id: Some(self.track_id),
-                        },
-                    )
-                    .is_ok()
-                {
-                    self.resampler.reset();
-                    self.decoder.reset();
-                    self.sample_buf = None;
-                    let _ = self.engine.push(AudioFrame::Pcm(Vec::new()));
-                    CommandOutcome::Seeked
-                } else {
-                    warn!("AudioProcessor: seek to {}ms failed", ms);
-                    CommandOutcome::SeekFailed
+                match self.format.seek(
+                    SeekMode::Accurate,
+                    SeekTo::Time {
+                        time,
+                        track_id: Some(self.track_id),
+                    },
+                ) {
+                    Ok(seeked) => {
+                        self.resampler.reset();
+                        self.decoder.reset();
+                        self.sample_buf = None;
+                        let landed = self.landed_ms(&seeked, ms);
+                        self.seek_target =
+                            (seeked.actual_ts < seeked.required_ts).then_some(seeked.required_ts);
+                        self.pending = Some(AudioFrame::Seeked(landed));
+                        CommandOutcome::Seeked
+                    }
+                    Err(e) => {
+                        warn!("AudioProcessor: seek to {}ms failed: {}", ms, e);
+                        self.pending = Some(AudioFrame::SeekFailed);
+                        CommandOutcome::SeekFailed
+                    }
                 }
             }
             Ok(DecoderCommand::Stop) | Err(flume::TryRecvError::Disconnected) => {
@@ -317,9 +434,97 @@ impl AudioProcessor {
         }
     }
 
+    /// Hands the pending seek report to the engine if there is room. Audio
+    /// decoded after a seek must follow its report, so nothing else is pushed
+    /// until this succeeds.
+    fn flush_pending(&mut self) -> bool {
+        let Some(frame) = self.pending.take() else {
+            return true;
+        };
+        match self.engine.try_push(frame) {
+            Ok(()) => true,
+            Err(frame) => {
+                self.pending = Some(frame);
+                false
+            }
+        }
+    }
+
+    /// Position a seek really landed on; the requested one when the track has
+    /// no time base to convert with.
+    fn landed_ms(&self, seeked: &SeekedTo, requested_ms: u64) -> u64 {
+        match self.time_base {
+            Some(tb) => {
+                let time = tb.calc_time(seeked.required_ts);
+                time.seconds * 1000 + (time.frac * 1000.0).round() as u64
+            }
+            None => requested_ms,
+        }
+    }
+
+    /// Frames to drop from the start of a packet at `ts` lasting `dur`,
+    /// while a seek target is pending.
+    fn seek_skip(&mut self, ts: u64, dur: u64, rate: u32) -> usize {
+        let Some(target) = self.seek_target else {
+            return 0;
+        };
+        if ts.saturating_add(dur) <= target {
+            return usize::MAX;
+        }
+        self.seek_target = None;
+
+        let ahead = target.saturating_sub(ts);
+        match self.time_base {
+            Some(tb) => {
+                let time = tb.calc_time(ahead);
+                ((time.seconds as f64 + time.frac) * rate as f64).round() as usize
+            }
+            None => ahead as usize,
+        }
+    }
+
     fn send_error(&self, msg: String) {
         if let Some(tx) = &self.error_tx {
             let _ = tx.send(msg);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::audio::test_util::wav_bytes;
+
+    #[test]
+    fn test_seek_lands_on_the_requested_sample() {
+        let (tx, rx) = flume::bounded(256);
+        let (cmd_tx, cmd_rx) = flume::unbounded();
+        let mut processor = AudioProcessor::new(
+            Box::new(Cursor::new(wav_bytes(48_000))),
+            Some(AudioFormat::Wav),
+            tx,
+            cmd_rx,
+            None,
+            PlayerConfig::default(),
+        )
+        .expect("wav should open");
+
+        cmd_tx.send(DecoderCommand::Seek(505)).unwrap();
+        processor.run().unwrap();
+
+        let mut frames = rx.try_iter();
+        assert!(matches!(frames.next(), Some(AudioFrame::Seeked(505))));
+
+        let pcm: Vec<i16> = frames
+            .flat_map(|frame| match frame {
+                AudioFrame::Pcm(pcm) => pcm,
+                other => panic!("unexpected frame {other:?}"),
+            })
+            .collect();
+        // Frame 24_240 of the ramp.
+        assert_eq!(&pcm[..2], &[-1000, -1000]);
+        assert_eq!(pcm.len(), (48_000 - 24_240) * 2);
+    }
+}
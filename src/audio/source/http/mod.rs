@@ -2,6 +2,7 @@ use std::{
     io::{Read, Seek, SeekFrom},
     sync::Arc,
     thread,
+    time::{Duration, Instant},
 };
 
 use parking_lot::{Condvar, Mutex};
@@ -9,7 +10,7 @@ use symphonia::core::io::MediaSource;
 use tracing::debug;
 
 use super::AudioSource;
-use crate::common::types::AnyResult;
+use crate::{audio::constants::READ_STALL_TIMEOUT_SECS, common::types::AnyResult};
 
 pub mod prefetcher;
 use prefetcher::{PrefetchCommand, SharedState, prefetch_loop};
@@ -108,11 +109,14 @@ impl Read for HttpSource {
         let (lock, cvar) = &*self.shared;
         let mut state = lock.lock();
 
-        loop {
-            if !state.chunks.is_empty() || state.done || state.error.is_some() {
-                break;
+        let deadline = Instant::now() + Duration::from_secs(READ_STALL_TIMEOUT_SECS);
+        while !state.is_readable() {
+            if cvar.wait_until(&mut state, deadline).timed_out() && !state.is_readable() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    "HttpSource: no data received before the read timeout",
+                ));
             }
-            cvar.wait(&mut state);
         }
 
         if let Some(err) = state.error.take() {
@@ -39,6 +39,11 @@ impl SharedState {
         }
     }
 
+    /// Whether a read can return without waiting for the prefetcher.
+    pub fn is_readable(&self) -> bool {
+        !self.chunks.is_empty() || self.done || self.error.is_some()
+    }
+
     pub fn drain_into(&mut self, dst: &mut [u8]) -> usize {
         let mut written = 0;
         while written < dst.len() {
@@ -2,7 +2,7 @@ use std::{
     collections::HashMap,
     io::{Read, Seek, SeekFrom},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use bytes::Bytes;
@@ -14,7 +14,7 @@ use super::AudioSource;
 use crate::{
     audio::constants::{
         CHUNK_SIZE, FETCH_WAIT_MS, MAX_CONCURRENT_FETCHES, MAX_FETCH_RETRIES, PREFETCH_CHUNKS,
-        PROBE_TIMEOUT_SECS, WORKER_IDLE_MS,
+        PROBE_TIMEOUT_SECS, READ_STALL_TIMEOUT_SECS, WORKER_IDLE_MS,
     },
     common::types::AnyResult,
 };
@@ -126,6 +126,7 @@ impl Read for SegmentedSource {
         let (lock, cvar) = &*self.shared;
         let mut state = lock.lock();
         state.current_pos = self.pos;
+        let deadline = Instant::now() + Duration::from_secs(READ_STALL_TIMEOUT_SECS);
 
         loop {
             if let Some(ref err) = state.fatal_error {
@@ -159,6 +160,15 @@ impl Read for SegmentedSource {
                 }
 
                 Some(ChunkState::Downloading) | Some(ChunkState::Empty(_)) => {
+                    if Instant::now() >= deadline {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::TimedOut,
+                            format!(
+                                "SegmentedSource: chunk {} did not arrive in time",
+                                chunk_idx
+                            ),
+                        ));
+                    }
                     cvar.notify_all();
                     trace!("SegmentedSource: waiting for chunk {}", chunk_idx);
                     cvar.wait_for(&mut state, Duration::from_millis(FETCH_WAIT_MS));
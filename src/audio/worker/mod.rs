@@ -0,0 +1,6 @@
+pub mod pool;
+
+pub use pool::{
+    DecodePool, WorkerLoad, get_background_pool, get_decode_pool, init_background_pool,
+    init_decode_pool, submit,
+};
@@ -0,0 +1,507 @@
+use std::{
+    io,
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use parking_lot::{Condvar, Mutex};
+use symphonia::core::errors::Error;
+use tracing::{debug, info, warn};
+
+use crate::audio::{
+    constants::{
+        DECODE_IDLE_WAIT_MS, DECODE_LOAD_WINDOW_MS, DECODE_SLICE_PACKETS, DECODE_STALL_MS,
+    },
+    processor::{AudioProcessor, StepOutcome},
+};
+
+type DoneCallback = Box<dyn FnOnce(Result<(), Error>) + Send>;
+
+struct DecodeJob {
+    id: u64,
+    label: String,
+    processor: AudioProcessor,
+    on_done: DoneCallback,
+}
+
+/// State of one worker that its peers and `submit` can see.
+///
+/// Jobs wait in `jobs` between steps, and the job being stepped is taken out
+/// of it, so peers can adopt the rest when a read blocks inside a step.
+struct Worker {
+    jobs: Mutex<Vec<DecodeJob>>,
+    wake: Condvar,
+    tasks: AtomicUsize,
+    load: AtomicU32, // f32 bits, busy fraction over the last window
+    /// Microseconds since the pool epoch at which the current step began,
+    /// 0 between steps.
+    stepping_since: AtomicU64,
+}
+
+impl Worker {
+    fn new() -> Self {
+        Self {
+            jobs: Mutex::new(Vec::new()),
+            wake: Condvar::new(),
+            tasks: AtomicUsize::new(0),
+            load: AtomicU32::new(0.0f32.to_bits()),
+            stepping_since: AtomicU64::new(0),
+        }
+    }
+
+    fn load(&self) -> f32 {
+        f32::from_bits(self.load.load(Ordering::Relaxed))
+    }
+
+    fn is_stalled(&self, epoch: Instant) -> bool {
+        let since = self.stepping_since.load(Ordering::Acquire);
+        since != 0 && micros_since(epoch).saturating_sub(since) >= DECODE_STALL_MS * 1_000
+    }
+
+    /// Takes job `id` out of the queue, unless a peer adopted it meanwhile.
+    fn take(&self, id: u64) -> Option<DecodeJob> {
+        let mut jobs = self.jobs.lock();
+        let idx = jobs.iter().position(|job| job.id == id)?;
+        Some(jobs.swap_remove(idx))
+    }
+
+    fn finish(&self, job: DecodeJob, result: Result<(), Error>) {
+        self.tasks.fetch_sub(1, Ordering::AcqRel);
+        debug!("Decoder pool: '{}' finished", job.label);
+        (job.on_done)(result);
+    }
+}
+
+struct PoolShared {
+    workers: Vec<Worker>,
+    epoch: Instant,
+    next_id: AtomicU64,
+    closed: AtomicBool,
+}
+
+fn micros_since(epoch: Instant) -> u64 {
+    (epoch.elapsed().as_micros() as u64).max(1)
+}
+
+/// Snapshot of a single decode worker.
+#[derive(Debug, Clone, Copy)]
+pub struct WorkerLoad {
+    pub tasks: usize,
+    pub load: f32,
+}
+
+/// A bounded set of threads that cooperatively drive every `AudioProcessor`.
+///
+/// Each worker owns a list of processors and repeatedly steps the ones whose
+/// output buffer is emptiest, so a node with thousands of players runs a fixed
+/// number of decode threads instead of one per track. A worker whose step has
+/// been blocked on a slow source for [`DECODE_STALL_MS`] loses its other jobs
+/// to its peers, so one stalled stream does not hold up the rest.
+pub struct DecodePool {
+    shared: Arc<PoolShared>,
+}
+
+impl DecodePool {
+    pub fn new(size: usize) -> Self {
+        Self::named(size, "decode-worker")
+    }
+
+    /// A pool whose threads are called `{name}-{id}`.
+    pub fn named(size: usize, name: &str) -> Self {
+        let size = if size == 0 {
+            thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        } else {
+            size
+        };
+
+        let shared = Arc::new(PoolShared {
+            workers: (0..size).map(|_| Worker::new()).collect(),
+            epoch: Instant::now(),
+            next_id: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+        });
+
+        for id in 0..size {
+            let pool = shared.clone();
+            thread::Builder::new()
+                .name(format!("{name}-{id}"))
+                .spawn(move || run_worker(pool, id))
+                .expect("failed to spawn decode worker thread");
+        }
+
+        info!("Decoder pool '{}' started with {} workers", name, size);
+        Self { shared }
+    }
+
+    pub fn size(&self) -> usize {
+        self.shared.workers.len()
+    }
+
+    /// Hands `processor` to the least busy worker that is not stalled. `on_done`
+    /// runs on a worker thread once the processor finishes, fails or is stopped.
+    pub fn submit<F>(&self, label: impl Into<String>, processor: AudioProcessor, on_done: F)
+    where
+        F: FnOnce(Result<(), Error>) + Send + 'static,
+    {
+        let label = label.into();
+        let epoch = self.shared.epoch;
+        let Some(worker) = self.shared.workers.iter().min_by(|a, b| {
+            a.is_stalled(epoch)
+                .cmp(&b.is_stalled(epoch))
+                .then_with(|| {
+                    a.tasks
+                        .load(Ordering::Acquire)
+                        .cmp(&b.tasks.load(Ordering::Acquire))
+                })
+                .then_with(|| a.load().total_cmp(&b.load()))
+        }) else {
+            on_done(Err(Error::IoError(io::Error::other(
+                "decoder pool has no workers",
+            ))));
+            return;
+        };
+
+        if self.shared.closed.load(Ordering::Acquire) {
+            on_done(Err(Error::IoError(io::Error::other(
+                "decode worker is not running",
+            ))));
+            return;
+        }
+
+        debug!("Decoder pool: scheduling '{}'", label);
+        worker.tasks.fetch_add(1, Ordering::AcqRel);
+        worker.jobs.lock().push(DecodeJob {
+            id: self.shared.next_id.fetch_add(1, Ordering::Relaxed),
+            label,
+            processor,
+            on_done: Box::new(on_done),
+        });
+        worker.wake.notify_one();
+    }
+
+    pub fn loads(&self) -> Vec<WorkerLoad> {
+        self.shared
+            .workers
+            .iter()
+            .map(|w| WorkerLoad {
+                tasks: w.tasks.load(Ordering::Acquire),
+                load: w.load(),
+            })
+            .collect()
+    }
+}
+
+impl Drop for DecodePool {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+        for worker in &self.shared.workers {
+            worker.wake.notify_all();
+        }
+    }
+}
+
+/// Moves the queued jobs of every stalled peer onto worker `index`.
+fn adopt_stalled(pool: &PoolShared, index: usize) {
+    let me = &pool.workers[index];
+    for (peer_index, peer) in pool.workers.iter().enumerate() {
+        if peer_index == index || !peer.is_stalled(pool.epoch) {
+            continue;
+        }
+        let adopted = std::mem::take(&mut *peer.jobs.lock());
+        if adopted.is_empty() {
+            continue;
+        }
+        warn!(
+            "Decoder pool: worker {} is stalled on a slow source, worker {} takes over {} job(s)",
+            peer_index,
+            index,
+            adopted.len()
+        );
+        peer.tasks.fetch_sub(adopted.len(), Ordering::AcqRel);
+        me.tasks.fetch_add(adopted.len(), Ordering::AcqRel);
+        me.jobs.lock().extend(adopted);
+    }
+}
+
+fn run_worker(pool: Arc<PoolShared>, index: usize) {
+    let me = &pool.workers[index];
+    let mut order: Vec<(f32, u64)> = Vec::new();
+    let mut window_start = Instant::now();
+    let mut busy = Duration::ZERO;
+
+    loop {
+        adopt_stalled(&pool, index);
+
+        {
+            let mut jobs = me.jobs.lock();
+            if jobs.is_empty() {
+                if pool.closed.load(Ordering::Acquire) {
+                    return;
+                }
+                me.load.store(0.0f32.to_bits(), Ordering::Relaxed);
+                // Wake up now and then to look for stalled peers.
+                me.wake
+                    .wait_for(&mut jobs, Duration::from_millis(DECODE_STALL_MS));
+                window_start = Instant::now();
+                busy = Duration::ZERO;
+                continue;
+            }
+
+            // Emptiest buffers first so starving players catch up before full ones top off.
+            order.clear();
+            order.extend(jobs.iter().map(|job| (job.processor.fill_level(), job.id)));
+        }
+        order.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+        let slice_start = Instant::now();
+        let mut progressed = false;
+
+        for &(_, id) in &order {
+            let Some(mut job) = me.take(id) else {
+                continue;
+            };
+
+            me.stepping_since
+                .store(micros_since(pool.epoch), Ordering::Release);
+            let outcome = catch_unwind(AssertUnwindSafe(|| {
+                job.processor.step(DECODE_SLICE_PACKETS)
+            }))
+            .unwrap_or_else(|_| Err(Error::IoError(io::Error::other("decoder panicked"))));
+            me.stepping_since.store(0, Ordering::Release);
+
+            match outcome {
+                Ok(StepOutcome::Progress) => {
+                    progressed = true;
+                    me.jobs.lock().push(job);
+                }
+                Ok(StepOutcome::Blocked) => me.jobs.lock().push(job),
+                Ok(StepOutcome::Finished) => me.finish(job, Ok(())),
+                Err(e) => me.finish(job, Err(e)),
+            }
+        }
+        busy += slice_start.elapsed();
+
+        let elapsed = window_start.elapsed();
+        if elapsed >= Duration::from_millis(DECODE_LOAD_WINDOW_MS) {
+            let load = (busy.as_secs_f32() / elapsed.as_secs_f32()).min(1.0);
+            me.load.store(load.to_bits(), Ordering::Relaxed);
+            window_start = Instant::now();
+            busy = Duration::ZERO;
+        }
+
+        if !progressed {
+            // Every buffer is full: wait for the mixer to drain or for new work.
+            let mut jobs = me.jobs.lock();
+            me.wake
+                .wait_for(&mut jobs, Duration::from_millis(DECODE_IDLE_WAIT_MS));
+        }
+    }
+}
+
+static GLOBAL_DECODE_POOL: OnceLock<Arc<DecodePool>> = OnceLock::new();
+
+/// Starts the global decoder pool with `size` workers (0 = one per CPU core).
+/// Has no effect if the pool is already running.
+pub fn init_decode_pool(size: usize) -> Arc<DecodePool> {
+    GLOBAL_DECODE_POOL
+        .get_or_init(|| Arc::new(DecodePool::new(size)))
+        .clone()
+}
+
+pub fn get_decode_pool() -> Arc<DecodePool> {
+    init_decode_pool(0)
+}
+
+static BACKGROUND_DECODE_POOL: OnceLock<Arc<DecodePool>> = OnceLock::new();
+
+/// Starts the pool for tracks decoded without being played, such as those
+/// being analysed, so they never take a playback worker's time. Has no
+/// effect if the pool is already running.
+pub fn init_background_pool(size: usize) -> Arc<DecodePool> {
+    BACKGROUND_DECODE_POOL
+        .get_or_init(|| Arc::new(DecodePool::named(size.max(1), "background-decode")))
+        .clone()
+}
+
+pub fn get_background_pool() -> Arc<DecodePool> {
+    init_background_pool(1)
+}
+
+/// Schedules `processor` on the global decoder pool, or on the background
+/// pool when its config asks for it.
+#[inline]
+pub fn submit<F>(label: impl Into<String>, processor: AudioProcessor, on_done: F)
+where
+    F: FnOnce(Result<(), Error>) + Send + 'static,
+{
+    let pool = if processor.is_background() {
+        get_background_pool()
+    } else {
+        get_decode_pool()
+    };
+    pool.submit(label, processor, on_done);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Seek, SeekFrom};
+
+    use super::*;
+    use crate::{
+        audio::{AudioFrame, processor::DecoderCommand, test_util::wav_bytes},
+        common::types::AudioFormat,
+        config::player::PlayerConfig,
+    };
+
+    fn processor(
+        frames: usize,
+        buffer: usize,
+    ) -> (
+        AudioProcessor,
+        flume::Receiver<AudioFrame>,
+        flume::Sender<DecoderCommand>,
+    ) {
+        let (tx, rx) = flume::bounded(buffer);
+        let (cmd_tx, cmd_rx) = flume::unbounded();
+        let source = Box::new(Cursor::new(wav_bytes(frames)));
+        let processor = AudioProcessor::new(
+            source,
+            Some(AudioFormat::Wav),
+            tx,
+            cmd_rx,
+            None,
+            PlayerConfig::default(),
+        )
+        .expect("wav should open");
+        (processor, rx, cmd_tx)
+    }
+
+    #[test]
+    fn test_pool_decodes_to_completion() {
+        let pool = DecodePool::new(1);
+        let (processor, rx, _cmd_tx) = processor(48_000, 4);
+        let (done_tx, done_rx) = flume::bounded(1);
+
+        pool.submit("test", processor, move |res| {
+            let _ = done_tx.send(res.is_ok());
+        });
+
+        let mut samples = 0usize;
+        while let Ok(frame) = rx.recv_timeout(Duration::from_secs(5)) {
+            if let AudioFrame::Pcm(pcm) = frame {
+                samples += pcm.len();
+            }
+        }
+
+        assert_eq!(samples, 48_000 * 2);
+        assert_eq!(done_rx.recv_timeout(Duration::from_secs(5)), Ok(true));
+        assert_eq!(pool.loads()[0].tasks, 0);
+    }
+
+    #[test]
+    fn test_pool_parks_full_buffers_and_honours_stop() {
+        let pool = DecodePool::new(2);
+        let (first, _first_rx, first_cmd) = processor(480_000, 2);
+        let (second, _second_rx, _second_cmd) = processor(480_000, 2);
+        let (done_tx, done_rx) = flume::unbounded();
+
+        let tx = done_tx.clone();
+        pool.submit("first", first, move |res| {
+            let _ = tx.send(res.is_ok());
+        });
+        pool.submit("second", second, move |res| {
+            let _ = done_tx.send(res.is_ok());
+        });
+
+        assert_eq!(pool.loads().iter().map(|w| w.tasks).sum::<usize>(), 2);
+        assert!(pool.loads().iter().all(|w| w.tasks == 1));
+
+        // Nobody drains the buffers, so neither job can finish on its own.
+        assert!(done_rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+        first_cmd.send(DecoderCommand::Stop).unwrap();
+        assert_eq!(done_rx.recv_timeout(Duration::from_secs(5)), Ok(true));
+    }
+
+    /// A WAV stream whose reads block once past `stall_at` bytes, until
+    /// `release` is dropped.
+    struct StallingSource {
+        inner: Cursor<Vec<u8>>,
+        stall_at: u64,
+        release: flume::Receiver<()>,
+    }
+
+    impl Read for StallingSource {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.inner.position() >= self.stall_at {
+                let _ = self.release.recv();
+            }
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for StallingSource {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    impl symphonia::core::io::MediaSource for StallingSource {
+        fn is_seekable(&self) -> bool {
+            true
+        }
+
+        fn byte_len(&self) -> Option<u64> {
+            Some(self.inner.get_ref().len() as u64)
+        }
+    }
+
+    #[test]
+    fn test_stalled_source_does_not_hold_up_its_worker() {
+        let pool = DecodePool::new(2);
+        let (release_tx, release_rx) = flume::bounded::<()>(0);
+        let (stalled_tx, _stalled_rx) = flume::bounded(256);
+        let (_stalled_cmd, stalled_cmd_rx) = flume::unbounded();
+        let stalled = AudioProcessor::new(
+            Box::new(StallingSource {
+                inner: Cursor::new(wav_bytes(480_000)),
+                stall_at: 64 * 1024,
+                release: release_rx,
+            }),
+            Some(AudioFormat::Wav),
+            stalled_tx,
+            stalled_cmd_rx,
+            None,
+            PlayerConfig::default(),
+        )
+        .expect("wav should open");
+        let (busy, _busy_rx, _busy_cmd) = processor(480_000, 2);
+        let (queued, queued_rx, _queued_cmd) = processor(48_000, 256);
+        let (done_tx, done_rx) = flume::unbounded();
+
+        // The stalled and the queued job share the first worker.
+        pool.submit("stalled", stalled, |_| {});
+        pool.submit("busy", busy, |_| {});
+        pool.submit("queued", queued, move |res| {
+            let _ = done_tx.send(res.is_ok());
+        });
+
+        assert_eq!(done_rx.recv_timeout(Duration::from_secs(5)), Ok(true));
+        let samples: usize = queued_rx
+            .try_iter()
+            .map(|frame| match frame {
+                AudioFrame::Pcm(pcm) => pcm.len(),
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(samples, 48_000 * 2);
+        drop(release_tx);
+    }
+}
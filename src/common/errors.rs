@@ -41,6 +41,11 @@ impl RustalinkError {
         Self::new(404, "Not Found", message, path)
     }
 
+    /// Creates a 409 Conflict error.
+    pub fn conflict(message: impl Into<String>, path: impl Into<String>) -> Self {
+        Self::new(409, "Conflict", message, path)
+    }
+
     /// Creates a generic error response.
     pub fn new(
         status: u16,
@@ -66,6 +66,7 @@ macro_rules! define_id {
 
 define_id!(GuildId, String);
 define_id!(SessionId, String);
+define_id!(BroadcastId, String);
 define_id!(UserId, u64, copy);
 define_id!(ChannelId, u64, copy);
 
@@ -83,17 +84,34 @@ impl Deref for SessionId {
     }
 }
 
+impl Deref for BroadcastId {
+    type Target = str;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Random 16-character alphanumeric identifier (a-z, 0-9).
+fn random_id() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        .take(16)
+        .map(char::from)
+        .collect()
+}
+
 impl SessionId {
     /// Generates a random 16-character alphanumeric session ID (a-z, 0-9).
     pub fn generate() -> Self {
-        let rng = rand::thread_rng();
-        let s: String = rng
-            .sample_iter(&Alphanumeric)
-            .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
-            .take(16)
-            .map(char::from)
-            .collect();
-        Self(s)
+        Self(random_id())
+    }
+}
+
+impl BroadcastId {
+    /// Generates a random 16-character alphanumeric broadcast ID (a-z, 0-9).
+    pub fn generate() -> Self {
+        Self(random_id())
     }
 }
 
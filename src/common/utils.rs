@@ -31,6 +31,30 @@ pub fn now_ms() -> u64 {
         .as_millis() as u64
 }
 
+/// Returns the first value of `key` in a URL's query string.
+pub fn query_param(url: &str, key: &str) -> Option<String> {
+    reqwest::Url::parse(url)
+        .ok()?
+        .query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.into_owned())
+        .filter(|v| !v.is_empty())
+}
+
+/// Uppercases an ISRC and drops the hyphens some catalogs insert.
+pub fn normalize_isrc(isrc: &str) -> String {
+    isrc.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Compares two ISRCs, ignoring case and the hyphens some catalogs insert.
+pub fn same_isrc(a: &str, b: &str) -> bool {
+    let a = normalize_isrc(a);
+    !a.is_empty() && a == normalize_isrc(b)
+}
+
 /// Simple ANSI stripper to prevent the log file from being polluted with escape sequences.
 pub fn strip_ansi_escapes(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
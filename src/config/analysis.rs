@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AnalysisConfig {
+    /// Longest stretch of a track that is decoded for analysis.
+    #[serde(default = "default_max_duration_secs")]
+    pub max_duration_secs: u64,
+    /// Analyses running at once; further requests wait.
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// Results kept in memory, keyed by source and identifier.
+    #[serde(default = "default_cache_size")]
+    pub cache_size: usize,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Adds cached results to `pluginInfo.analysis` in `TrackStartEvent`.
+    #[serde(default)]
+    pub attach_to_track_start: bool,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            max_duration_secs: default_max_duration_secs(),
+            concurrency: default_concurrency(),
+            cache_size: default_cache_size(),
+            timeout_secs: default_timeout_secs(),
+            attach_to_track_start: false,
+        }
+    }
+}
+
+fn default_max_duration_secs() -> u64 {
+    600
+}
+
+fn default_concurrency() -> usize {
+    2
+}
+
+fn default_cache_size() -> usize {
+    1000
+}
+
+fn default_timeout_secs() -> u64 {
+    120
+}
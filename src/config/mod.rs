@@ -1,16 +1,22 @@
+pub mod analysis;
 pub mod filters;
 pub mod lyrics;
 pub mod metrics;
 pub mod player;
+pub mod recording;
+pub mod segments;
 pub mod server;
 pub mod sources;
 
 use std::{fs, path::Path};
 
+pub use analysis::*;
 pub use filters::*;
 pub use lyrics::*;
 pub use metrics::*;
 pub use player::*;
+pub use recording::*;
+pub use segments::*;
 use serde::Deserialize;
 pub use server::*;
 pub use sources::*;
@@ -32,6 +38,12 @@ pub struct AppConfig {
     #[serde(default)]
     pub player: PlayerConfig,
     #[serde(default)]
+    pub recording: RecordingConfig,
+    #[serde(default)]
+    pub segments: SegmentsConfig,
+    #[serde(default)]
+    pub analysis: AnalysisConfig,
+    #[serde(default)]
     pub metrics: MetricsConfig,
     #[serde(default)]
     pub config_server: Option<ConfigServerConfig>,
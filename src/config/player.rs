@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::audio::effects::fade::FadeCurve;
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PlayerConfig {
     #[serde(default = "default_stuck_threshold_ms")]
@@ -15,6 +17,26 @@ pub struct PlayerConfig {
     #[serde(default)]
     pub tape: TapeConfig,
     #[serde(default)]
+    pub fade: FadeConfig,
+    #[serde(default)]
+    pub encoder: EncoderConfig,
+    #[serde(default)]
+    pub silence: SilenceConfig,
+    #[serde(default)]
+    pub meter: MeterConfig,
+    #[serde(default)]
+    pub ducking: DuckingConfig,
+    /// Number of shared decode worker threads (0 = one per CPU core).
+    #[serde(default)]
+    pub decoder_workers: usize,
+    /// Seconds of a live HLS stream kept for rewinding (0 = live edge only).
+    #[serde(default = "default_live_window_secs")]
+    pub live_window_secs: u64,
+    /// Decode on the background pool instead of the one feeding players.
+    /// Set for track analysis, never read from the config file.
+    #[serde(skip)]
+    pub background_decode: bool,
+    #[serde(default)]
     pub mirrors: Option<crate::config::server::MirrorsConfig>,
 }
 
@@ -37,6 +59,195 @@ pub struct TapeConfig {
     pub curve: TapeCurve,
 }
 
+/// Gain ramps around track start, seek, stop and replacement. A duration of
+/// 0 disables that ramp.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct FadeConfig {
+    #[serde(default = "default_fade_in_ms")]
+    pub fade_in_ms: u64,
+    #[serde(default = "default_fade_out_ms")]
+    pub fade_out_ms: u64,
+    #[serde(default)]
+    pub curve: FadeCurve,
+}
+
+impl FadeConfig {
+    /// Longest fade a player may be given.
+    pub const MAX_FADE_MS: u64 = 10_000;
+}
+
+impl Default for FadeConfig {
+    fn default() -> Self {
+        Self {
+            fade_in_ms: default_fade_in_ms(),
+            fade_out_ms: default_fade_out_ms(),
+            curve: FadeCurve::default(),
+        }
+    }
+}
+
+/// Opus encoder settings. Without a `bitrate` or `channel_bitrate` the
+/// encoder picks its own rate.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq)]
+pub struct EncoderConfig {
+    /// Target bitrate in bits per second.
+    #[serde(default)]
+    pub bitrate: Option<u32>,
+    /// Bitrate of the voice channel; caps `bitrate` and is used when it is unset.
+    #[serde(default)]
+    pub channel_bitrate: Option<u32>,
+    /// In-band forward error correction.
+    #[serde(default)]
+    pub fec: bool,
+    /// Expected packet loss (0-100), which tunes how much FEC data is spent.
+    #[serde(default)]
+    pub packet_loss_percent: u8,
+    /// Send bare silence packets instead of encoding silent frames.
+    #[serde(default)]
+    pub dtx: bool,
+    #[serde(default)]
+    pub signal: EncoderSignal,
+}
+
+impl EncoderConfig {
+    pub const MIN_BITRATE: u32 = 6_000;
+    pub const MAX_BITRATE: u32 = 510_000;
+
+    /// The bitrate the encoder is asked for, or `None` to let it decide.
+    pub fn effective_bitrate(&self) -> Option<u32> {
+        let target = match (self.bitrate, self.channel_bitrate) {
+            (Some(bitrate), Some(channel)) => bitrate.min(channel),
+            (bitrate, channel) => bitrate.or(channel)?,
+        };
+        Some(target.clamp(Self::MIN_BITRATE, Self::MAX_BITRATE))
+    }
+
+    /// Whether a source Opus packet of `len` bytes per 20 ms fits the bitrate.
+    pub fn allows_packet(&self, len: usize) -> bool {
+        self.effective_bitrate()
+            .is_none_or(|bitrate| len as u64 * 8 * 50 <= bitrate as u64)
+    }
+}
+
+/// Trimming of silence at the start and end of tracks.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct SilenceConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Level below which audio counts as silence, in dBFS.
+    #[serde(default = "default_silence_threshold_db")]
+    pub threshold_db: f32,
+    /// Shortest stretch of silence that is trimmed.
+    #[serde(default = "default_silence_min_duration_ms")]
+    pub min_duration_ms: u64,
+}
+
+impl SilenceConfig {
+    /// Longest silence held back before deciding to trim it.
+    pub const MAX_MIN_DURATION_MS: u64 = 5_000;
+
+    /// `min_duration_ms`, no longer than [`Self::MAX_MIN_DURATION_MS`].
+    pub fn min_duration_ms(&self) -> u64 {
+        self.min_duration_ms.min(Self::MAX_MIN_DURATION_MS)
+    }
+}
+
+impl Default for SilenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_db: default_silence_threshold_db(),
+            min_duration_ms: default_silence_min_duration_ms(),
+        }
+    }
+}
+
+/// Live level and spectrum events for visualizers.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct MeterConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Time between two level events.
+    #[serde(default = "default_meter_interval_ms")]
+    pub interval_ms: u64,
+    /// Number of spectrum bands.
+    #[serde(default = "default_meter_bands")]
+    pub bands: usize,
+}
+
+impl MeterConfig {
+    pub const MIN_INTERVAL_MS: u64 = 20;
+    pub const MAX_BANDS: usize = 64;
+
+    /// `interval_ms`, no shorter than one 20 ms frame.
+    pub fn interval_ms(&self) -> u64 {
+        self.interval_ms.max(Self::MIN_INTERVAL_MS)
+    }
+
+    pub fn bands(&self) -> usize {
+        self.bands.clamp(1, Self::MAX_BANDS)
+    }
+}
+
+impl Default for MeterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_ms: default_meter_interval_ms(),
+            bands: default_meter_bands(),
+        }
+    }
+}
+
+/// Lowering of the playing track while a mix layer, such as a TTS
+/// announcement, is producing audio.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct DuckingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How far the track is lowered, in dB.
+    #[serde(default = "default_ducking_amount_db")]
+    pub amount_db: f32,
+    /// Time to reach the lowered level once a layer starts.
+    #[serde(default = "default_ducking_attack_ms")]
+    pub attack_ms: u64,
+    /// Time the layers must stay silent before the release starts, so pauses
+    /// between words don't bring the track back up.
+    #[serde(default = "default_ducking_hold_ms")]
+    pub hold_ms: u64,
+    /// Time to return to full level once the layers stop.
+    #[serde(default = "default_ducking_release_ms")]
+    pub release_ms: u64,
+}
+
+impl DuckingConfig {
+    /// Gain applied to the track while fully ducked.
+    pub fn floor_gain(&self) -> f32 {
+        10f32.powf(-self.amount_db.max(0.0) / 20.0)
+    }
+}
+
+impl Default for DuckingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            amount_db: default_ducking_amount_db(),
+            attack_ms: default_ducking_attack_ms(),
+            hold_ms: default_ducking_hold_ms(),
+            release_ms: default_ducking_release_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EncoderSignal {
+    #[default]
+    Auto,
+    Music,
+    Voice,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Default, Copy, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum TapeCurve {
@@ -65,6 +276,14 @@ impl Default for PlayerConfig {
             resampling_quality: ResamplingQuality::default(),
             opus_encoding_quality: default_opus_encoding_quality(),
             tape: TapeConfig::default(),
+            fade: FadeConfig::default(),
+            encoder: EncoderConfig::default(),
+            silence: SilenceConfig::default(),
+            meter: MeterConfig::default(),
+            ducking: DuckingConfig::default(),
+            decoder_workers: 0,
+            live_window_secs: default_live_window_secs(),
+            background_decode: false,
             mirrors: None,
         }
     }
@@ -95,3 +314,36 @@ fn default_opus_encoding_quality() -> u8 {
 fn default_tape_stop_duration_ms() -> u64 {
     500
 }
+fn default_fade_in_ms() -> u64 {
+    20
+}
+fn default_fade_out_ms() -> u64 {
+    40
+}
+fn default_live_window_secs() -> u64 {
+    600
+}
+fn default_silence_threshold_db() -> f32 {
+    -60.0
+}
+fn default_silence_min_duration_ms() -> u64 {
+    1000
+}
+fn default_meter_interval_ms() -> u64 {
+    100
+}
+fn default_meter_bands() -> usize {
+    16
+}
+fn default_ducking_amount_db() -> f32 {
+    12.0
+}
+fn default_ducking_attack_ms() -> u64 {
+    50
+}
+fn default_ducking_hold_ms() -> u64 {
+    400
+}
+fn default_ducking_release_ms() -> u64 {
+    500
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RecordingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_recording_directory")]
+    pub directory: String,
+    /// Longest single recording in seconds (0 = unlimited).
+    #[serde(default = "default_max_duration_secs")]
+    pub max_duration_secs: u64,
+    /// Largest single recording in megabytes (0 = unlimited).
+    #[serde(default = "default_max_size_mb")]
+    pub max_size_mb: u64,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: default_recording_directory(),
+            max_duration_secs: default_max_duration_secs(),
+            max_size_mb: default_max_size_mb(),
+        }
+    }
+}
+
+fn default_recording_directory() -> String {
+    "./recordings".to_string()
+}
+
+fn default_max_duration_secs() -> u64 {
+    4 * 60 * 60
+}
+
+fn default_max_size_mb() -> u64 {
+    1024
+}
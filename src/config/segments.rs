@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SegmentsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Base URL of a SponsorBlock-compatible API.
+    #[serde(default = "default_provider_url")]
+    pub provider_url: String,
+    /// Categories skipped for players that haven't picked their own.
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl Default for SegmentsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider_url: default_provider_url(),
+            categories: Vec::new(),
+            timeout_ms: default_timeout_ms(),
+        }
+    }
+}
+
+fn default_provider_url() -> String {
+    "https://sponsor.ajay.app".to_string()
+}
+
+fn default_timeout_ms() -> u64 {
+    5000
+}
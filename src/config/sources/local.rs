@@ -1,15 +1,33 @@
 use serde::{Deserialize, Serialize};
 
-use crate::config::sources::default_true;
+use crate::config::sources::{default_limit_20, default_true};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct LocalSourceConfig {
     #[serde(default = "default_true")]
     pub enabled: bool,
+    /// Library folders indexed for `localsearch:`. When set, playback is
+    /// limited to files under these folders.
+    #[serde(default)]
+    pub roots: Vec<String>,
+    /// Seconds between rescans of the roots; 0 scans once at startup.
+    #[serde(default = "default_rescan_interval")]
+    pub rescan_interval_secs: u64,
+    #[serde(default = "default_limit_20")]
+    pub search_limit: usize,
 }
 
 impl Default for LocalSourceConfig {
     fn default() -> Self {
-        Self { enabled: true }
+        Self {
+            enabled: true,
+            roots: Vec::new(),
+            rescan_interval_secs: default_rescan_interval(),
+            search_limit: 20,
+        }
     }
 }
+
+fn default_rescan_interval() -> u64 {
+    300
+}
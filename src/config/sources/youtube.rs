@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::config::sources::{default_false, default_true};
+use crate::config::sources::{default_false, default_limit_100, default_true};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct YouTubeConfig {
@@ -14,6 +14,9 @@ pub struct YouTubeConfig {
     pub refresh_tokens: Vec<String>,
     #[serde(default = "default_false")]
     pub get_oauth_token: bool,
+    /// Most recent uploads loaded for a channel URL.
+    #[serde(default = "default_limit_100")]
+    pub channel_load_limit: usize,
 }
 
 impl Default for YouTubeConfig {
@@ -24,6 +27,7 @@ impl Default for YouTubeConfig {
             cipher: YouTubeCipherConfig::default(),
             refresh_tokens: Vec::new(),
             get_oauth_token: false,
+            channel_load_limit: 100,
         }
     }
 }
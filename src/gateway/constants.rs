@@ -33,3 +33,10 @@ pub const IP_DISCOVERY_RETRY_INTERVAL_MS: u64 = 1000;
 // --- Protocol Specifics ---
 pub const OP_HEARTBEAT: u8 = 3;
 pub const MAX_PENDING_PROPOSALS: usize = 64;
+
+// --- Broadcast ---
+pub const BROADCAST_CHANNEL_FRAMES: usize = 50;
+pub const BROADCAST_MAX_BACKLOG: usize = 5;
+
+// --- Listen-along ---
+pub const MONITOR_CHANNEL_FRAMES: usize = 100;
@@ -1,17 +1,94 @@
-use tokio::sync::Mutex;
+use std::sync::Arc;
 
-use crate::{audio::Mixer, common::types::Shared, gateway::constants::DEFAULT_SAMPLE_RATE};
+use tokio::sync::{Mutex, broadcast};
+
+use crate::{
+    audio::{Mixer, mix::DuckState},
+    common::types::Shared,
+    gateway::constants::{BROADCAST_MAX_BACKLOG, DEFAULT_SAMPLE_RATE, MONITOR_CHANNEL_FRAMES},
+};
+
+/// A pre-encoded Opus frame shared between every connection of a broadcast.
+pub type OpusPacket = Arc<[u8]>;
+
+/// Result of polling an [`OpusFeed`] once per voice tick.
+pub enum FeedFrame {
+    /// No broadcast is attached; the connection plays its own mixer.
+    Detached,
+    /// A broadcast is attached but has not produced a frame this tick.
+    Empty,
+    Frame(OpusPacket),
+}
+
+/// Slot through which a broadcast hands Opus frames to a voice connection.
+///
+/// While a feed is attached the speak loop sends these frames as-is and leaves
+/// the player's own mixer and filter chain untouched.
+#[derive(Clone, Default)]
+pub struct OpusFeed {
+    rx: Arc<parking_lot::Mutex<Option<broadcast::Receiver<OpusPacket>>>>,
+}
+
+impl OpusFeed {
+    pub fn attach(&self, rx: broadcast::Receiver<OpusPacket>) {
+        *self.rx.lock() = Some(rx);
+    }
+
+    pub fn detach(&self) {
+        self.rx.lock().take();
+    }
+
+    pub fn is_attached(&self) -> bool {
+        self.rx.lock().is_some()
+    }
+
+    pub fn poll(&self) -> FeedFrame {
+        let mut slot = self.rx.lock();
+        let Some(rx) = slot.as_mut() else {
+            return FeedFrame::Detached;
+        };
+
+        // Drop stale frames so clock drift between loops can't build up latency.
+        while rx.len() > BROADCAST_MAX_BACKLOG {
+            if rx.try_recv().is_err() {
+                break;
+            }
+        }
+
+        loop {
+            match rx.try_recv() {
+                Ok(packet) => return FeedFrame::Frame(packet),
+                Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+                Err(broadcast::error::TryRecvError::Empty) => return FeedFrame::Empty,
+                Err(broadcast::error::TryRecvError::Closed) => {
+                    *slot = None;
+                    return FeedFrame::Detached;
+                }
+            }
+        }
+    }
+}
 
 pub struct VoiceEngine {
     pub mixer: Shared<Mixer>,
     pub dave: Option<Shared<crate::gateway::DaveHandler>>,
+    pub feed: OpusFeed,
+    /// Copies of every Opus frame sent to Discord, for listen-along streams.
+    pub monitor: broadcast::Sender<OpusPacket>,
+    /// How far the mixer currently ducks its tracks under mix layers.
+    pub duck: Arc<DuckState>,
 }
 
 impl VoiceEngine {
     pub fn new() -> Self {
+        let mixer = Mixer::new(DEFAULT_SAMPLE_RATE);
+        let duck = mixer.duck_state();
         Self {
-            mixer: Shared::new(Mutex::new(Mixer::new(DEFAULT_SAMPLE_RATE))),
+            mixer: Shared::new(Mutex::new(mixer)),
             dave: None,
+            feed: OpusFeed::default(),
+            monitor: broadcast::channel(MONITOR_CHANNEL_FRAMES).0,
+            duck,
         }
     }
 }
@@ -21,3 +98,35 @@ impl Default for VoiceEngine {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_detached_by_default() {
+        let feed = OpusFeed::default();
+        assert!(matches!(feed.poll(), FeedFrame::Detached));
+    }
+
+    #[test]
+    fn test_feed_delivers_and_trims_backlog() {
+        let (tx, rx) = broadcast::channel::<OpusPacket>(64);
+        let feed = OpusFeed::default();
+        feed.attach(rx);
+        assert!(matches!(feed.poll(), FeedFrame::Empty));
+
+        for i in 0..20u8 {
+            tx.send(Arc::from(vec![i])).unwrap();
+        }
+
+        let FeedFrame::Frame(first) = feed.poll() else {
+            panic!("expected a frame");
+        };
+        assert_eq!(first[0] as usize, 20 - BROADCAST_MAX_BACKLOG);
+
+        drop(tx);
+        while matches!(feed.poll(), FeedFrame::Frame(_)) {}
+        assert!(!feed.is_attached());
+    }
+}
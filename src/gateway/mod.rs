@@ -1,10 +1,12 @@
 pub mod constants;
 pub mod encryption;
 pub mod engine;
+pub mod pipeline;
 pub mod session;
 pub mod udp_link;
 
 pub use encryption::DaveHandler;
-pub use engine::VoiceEngine;
+pub use engine::{FeedFrame, OpusFeed, OpusPacket, VoiceEngine};
+pub use pipeline::{FramePipeline, LevelSink, PipelineFrame};
 pub use session::VoiceGateway;
 pub use udp_link::UDPVoiceTransport;
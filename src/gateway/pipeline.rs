@@ -0,0 +1,195 @@
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{error, warn};
+
+use crate::{
+    audio::{Mixer, engine::Encoder, features::LevelMeter, filters::FilterChain},
+    common::types::{AnyResult, GuildId, Shared},
+    config::player::{EncoderConfig, MeterConfig},
+    gateway::constants::{MAX_OPUS_FRAME_SIZE, PCM_FRAME_SAMPLES},
+    protocol::RustalinkEvent,
+};
+
+/// Attempts to reach the timescale filter's next frame within one tick.
+const MAX_FILL_ATTEMPTS: usize = 10;
+
+macro_rules! try_lock_yield {
+    ($mutex:expr) => {{
+        let mut guard = None;
+        for _ in 0..10 {
+            if let Ok(g) = $mutex.try_lock() {
+                guard = Some(g);
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        guard
+    }};
+}
+
+/// Where a pipeline reports the levels of the audio it encodes.
+pub struct LevelSink {
+    pub guild_id: GuildId,
+    pub event_tx: UnboundedSender<RustalinkEvent>,
+}
+
+/// Result of one [`FramePipeline::next_frame`] call.
+pub enum PipelineFrame<'a> {
+    /// The mixer has nothing to play and no silence is being padded.
+    Idle,
+    /// The timescale filter is still collecting input for its next frame.
+    Pending,
+    /// Opus for this 20 ms frame, encoded or passed through from the source.
+    /// Empty when the encoder produced nothing; the caller sends silence.
+    Opus { packet: &'a [u8], padded: bool },
+}
+
+/// Mixes, filters, meters and encodes a player's audio one frame at a time.
+///
+/// Both the voice speak loop and broadcasts drive their player through this,
+/// so every stage applies the same way whichever loop owns the mixer.
+pub struct FramePipeline {
+    mixer: Shared<Mixer>,
+    filter_chain: Shared<FilterChain>,
+    encoder_config: Shared<EncoderConfig>,
+    meter_config: Shared<MeterConfig>,
+    levels: Option<LevelSink>,
+    encoder: Encoder,
+    meter: LevelMeter,
+    pcm: Vec<f32>,
+    ts_pcm: Vec<f32>,
+    opus: Vec<u8>,
+    packet: Vec<u8>,
+    /// Set while source Opus packets are sent instead of encoded frames.
+    passthrough: bool,
+}
+
+impl FramePipeline {
+    pub fn new(
+        mixer: Shared<Mixer>,
+        filter_chain: Shared<FilterChain>,
+        encoder_config: Shared<EncoderConfig>,
+        meter_config: Shared<MeterConfig>,
+        levels: Option<LevelSink>,
+    ) -> AnyResult<Self> {
+        Ok(Self {
+            mixer,
+            filter_chain,
+            encoder_config,
+            meter_config,
+            levels,
+            encoder: Encoder::new()?,
+            meter: LevelMeter::new(),
+            pcm: vec![0.0; PCM_FRAME_SAMPLES * 2],
+            ts_pcm: vec![0.0; PCM_FRAME_SAMPLES * 2],
+            opus: vec![0; MAX_OPUS_FRAME_SIZE],
+            packet: Vec::new(),
+            passthrough: false,
+        })
+    }
+
+    /// Produces the next frame. With `pad`, a mixer that has run dry feeds
+    /// silence through the filters instead, so their tails can ring out.
+    pub async fn next_frame(&mut self, pad: bool) -> PipelineFrame<'_> {
+        if let Ok(settings) = self.encoder_config.try_lock()
+            && let Err(e) = self.encoder.configure(&settings)
+        {
+            warn!("Opus encoder rejected settings: {e}");
+        }
+        if let Ok(settings) = self.meter_config.try_lock() {
+            self.meter.configure(&settings);
+        }
+
+        for _ in 0..MAX_FILL_ATTEMPTS {
+            let ready_from_ts = match try_lock_yield!(self.filter_chain) {
+                Some(mut filters) => {
+                    filters.has_timescale() && filters.fill_frame(&mut self.ts_pcm)
+                }
+                None => false,
+            };
+            if ready_from_ts {
+                return self.encode(true, false);
+            }
+
+            let (has_input, passthrough) = match try_lock_yield!(self.mixer) {
+                Some(mut mixer) => (mixer.mix(&mut self.pcm), mixer.take_passthrough()),
+                None => (false, None),
+            };
+            if !has_input {
+                if !pad {
+                    return PipelineFrame::Idle;
+                }
+                self.pcm.fill(0.0);
+            }
+
+            let (has_ts, filtered) = match try_lock_yield!(self.filter_chain) {
+                Some(mut filters) => {
+                    let active = filters.is_active();
+                    filters.process(&mut self.pcm);
+                    (filters.has_timescale(), active)
+                }
+                None => (false, true),
+            };
+
+            if !has_ts {
+                if let Some(packet) =
+                    passthrough.filter(|p| !filtered && self.encoder.accepts_passthrough(p))
+                {
+                    self.measure(false);
+                    self.passthrough = true;
+                    self.packet = packet;
+                    return PipelineFrame::Opus {
+                        packet: &self.packet,
+                        padded: !has_input,
+                    };
+                }
+                return self.encode(false, !has_input);
+            }
+
+            if !has_input {
+                let filled = match try_lock_yield!(self.filter_chain) {
+                    Some(mut filters) => filters.fill_frame(&mut self.ts_pcm),
+                    None => false,
+                };
+                if filled {
+                    return self.encode(true, true);
+                }
+                break;
+            }
+        }
+
+        PipelineFrame::Pending
+    }
+
+    /// Feeds the outgoing frame to the level meter and forwards its report.
+    fn measure(&mut self, timescale: bool) {
+        let pcm = if timescale { &self.ts_pcm } else { &self.pcm };
+        if let Some(sink) = &self.levels
+            && let Some(levels) = self.meter.push(pcm)
+        {
+            let _ = sink.event_tx.send(RustalinkEvent::Levels {
+                guild_id: sink.guild_id.clone(),
+                levels,
+            });
+        }
+    }
+
+    fn encode(&mut self, timescale: bool, padded: bool) -> PipelineFrame<'_> {
+        self.measure(timescale);
+        if std::mem::take(&mut self.passthrough) {
+            self.encoder.reset();
+        }
+
+        let pcm = if timescale { &self.ts_pcm } else { &self.pcm };
+        let size = match self.encoder.encode_float(pcm, &mut self.opus) {
+            Ok(size) => size,
+            Err(e) => {
+                error!("Opus encode failed: {e}");
+                0
+            }
+        };
+        PipelineFrame::Opus {
+            packet: &self.opus[..size],
+            padded,
+        }
+    }
+}
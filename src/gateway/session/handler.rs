@@ -589,6 +589,8 @@ impl<'a> SessionState<'a> {
 
         let config = SpeakConfig {
             mixer: self.gateway.mixer.clone(),
+            feed: self.gateway.feed.clone(),
+            monitor: self.gateway.monitor.clone(),
             socket: self.udp_socket.clone(),
             addr,
             ssrc: self.ssrc,
@@ -596,6 +598,10 @@ impl<'a> SessionState<'a> {
             mode: self.selected_mode.clone(),
             dave: self.dave.clone(),
             filter_chain: self.gateway.filter_chain.clone(),
+            encoder_config: self.gateway.encoder_config.clone(),
+            meter_config: self.gateway.meter_config.clone(),
+            guild_id: self.gateway.guild_id.clone(),
+            event_tx: self.gateway.event_tx.clone(),
             frames_sent: self.gateway.frames_sent.clone(),
             frames_nulled: self.gateway.frames_nulled.clone(),
             cancel_token: self.conn_token.clone(),
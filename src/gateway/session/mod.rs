@@ -12,7 +12,7 @@ use tracing::{debug, error, warn};
 use crate::{
     audio::{Mixer, filters::FilterChain},
     common::types::{ChannelId, GuildId, SessionId, Shared, UserId},
-    gateway::constants::VOICE_GATEWAY_VERSION,
+    gateway::{OpusFeed, OpusPacket, constants::VOICE_GATEWAY_VERSION},
     protocol::RustalinkEvent,
 };
 
@@ -38,7 +38,11 @@ pub struct VoiceGateway {
     token: String,
     endpoint: String,
     pub mixer: Shared<Mixer>,
+    pub feed: OpusFeed,
+    pub monitor: tokio::sync::broadcast::Sender<OpusPacket>,
     pub filter_chain: Shared<FilterChain>,
+    pub encoder_config: Shared<crate::config::player::EncoderConfig>,
+    pub meter_config: Shared<crate::config::player::MeterConfig>,
     pub ping: Arc<AtomicI64>,
     event_tx: Option<UnboundedSender<RustalinkEvent>>,
     pub frames_sent: Arc<std::sync::atomic::AtomicU64>,
@@ -57,7 +61,11 @@ pub struct VoiceGatewayConfig {
     pub token: String,
     pub endpoint: String,
     pub mixer: Shared<Mixer>,
+    pub feed: OpusFeed,
+    pub monitor: tokio::sync::broadcast::Sender<OpusPacket>,
     pub filter_chain: Shared<FilterChain>,
+    pub encoder_config: Shared<crate::config::player::EncoderConfig>,
+    pub meter_config: Shared<crate::config::player::MeterConfig>,
     pub ping: Arc<AtomicI64>,
     pub event_tx: Option<UnboundedSender<RustalinkEvent>>,
     pub frames_sent: Arc<std::sync::atomic::AtomicU64>,
@@ -74,7 +82,11 @@ impl VoiceGateway {
             token: config.token,
             endpoint: config.endpoint,
             mixer: config.mixer,
+            feed: config.feed,
+            monitor: config.monitor,
             filter_chain: config.filter_chain,
+            encoder_config: config.encoder_config,
+            meter_config: config.meter_config,
             ping: config.ping,
             event_tx: config.event_tx,
             frames_sent: config.frames_sent,
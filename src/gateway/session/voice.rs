@@ -6,21 +6,22 @@ use std::{
 
 use tokio::sync::mpsc::UnboundedSender;
 use tokio_util::sync::CancellationToken;
-use tracing::error;
 
 use super::types::GatewayError;
 use crate::{
-    audio::{Mixer, engine::Encoder, filters::FilterChain},
-    common::types::Shared,
+    audio::{Mixer, filters::FilterChain},
+    common::types::{GuildId, Shared},
     gateway::{
-        DaveHandler,
+        DaveHandler, FeedFrame, OpusFeed, OpusPacket,
         constants::{
             DISCOVERY_PACKET_SIZE, FRAME_DURATION_MS, IP_DISCOVERY_RETRIES,
-            IP_DISCOVERY_RETRY_INTERVAL_MS, IP_DISCOVERY_TIMEOUT_SECS, MAX_OPUS_FRAME_SIZE,
-            MAX_SILENCE_FRAMES, PCM_FRAME_SAMPLES, SILENCE_FRAME, UDP_KEEPALIVE_GAP_MS,
+            IP_DISCOVERY_RETRY_INTERVAL_MS, IP_DISCOVERY_TIMEOUT_SECS, MAX_SILENCE_FRAMES,
+            SILENCE_FRAME, UDP_KEEPALIVE_GAP_MS,
         },
+        pipeline::{FramePipeline, LevelSink, PipelineFrame},
         udp_link::UDPVoiceTransport,
     },
+    protocol::RustalinkEvent,
 };
 
 pub async fn discover_ip(
@@ -75,6 +76,8 @@ pub async fn discover_ip(
 
 pub struct SpeakConfig {
     pub mixer: Shared<Mixer>,
+    pub feed: OpusFeed,
+    pub monitor: tokio::sync::broadcast::Sender<OpusPacket>,
     pub socket: Arc<tokio::net::UdpSocket>,
     pub addr: SocketAddr,
     pub ssrc: u32,
@@ -82,6 +85,11 @@ pub struct SpeakConfig {
     pub mode: String,
     pub dave: Shared<DaveHandler>,
     pub filter_chain: Shared<FilterChain>,
+    pub encoder_config: Shared<crate::config::player::EncoderConfig>,
+    pub meter_config: Shared<crate::config::player::MeterConfig>,
+    pub guild_id: GuildId,
+    /// Receives level events while metering is enabled.
+    pub event_tx: Option<UnboundedSender<RustalinkEvent>>,
     pub frames_sent: Arc<std::sync::atomic::AtomicU64>,
     pub frames_nulled: Arc<std::sync::atomic::AtomicU64>,
     pub cancel_token: CancellationToken,
@@ -99,9 +107,20 @@ pub async fn speak_loop(config: SpeakConfig) -> Result<(), GatewayError> {
         &config.mode,
         rtp_state,
     )?;
-    let mut encoder = Encoder::new().map_err(|e| GatewayError::Encoding(e.to_string()))?;
+    let levels = config.event_tx.clone().map(|event_tx| LevelSink {
+        guild_id: config.guild_id.clone(),
+        event_tx,
+    });
+    let mut pipeline = FramePipeline::new(
+        config.mixer.clone(),
+        config.filter_chain.clone(),
+        config.encoder_config.clone(),
+        config.meter_config.clone(),
+        levels,
+    )
+    .map_err(|e| GatewayError::Encoding(e.to_string()))?;
     let mut session = VoiceSession::new(config, transport);
-    session.run(&mut encoder).await
+    session.run(&mut pipeline).await
 }
 
 struct VoiceSession {
@@ -125,17 +144,13 @@ impl VoiceSession {
         }
     }
 
-    async fn run(&mut self, encoder: &mut Encoder) -> Result<(), GatewayError> {
+    async fn run(&mut self, pipeline: &mut FramePipeline) -> Result<(), GatewayError> {
         let mut interval = tokio::time::interval(Duration::from_millis(FRAME_DURATION_MS));
         interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
-        let mut pcm = vec![0i16; PCM_FRAME_SAMPLES * 2];
-        let mut opus = vec![0u8; MAX_OPUS_FRAME_SIZE];
-        let mut ts_pcm = vec![0i16; PCM_FRAME_SAMPLES * 2];
-
         while !self.config.cancel_token.is_cancelled() {
             interval.tick().await;
-            self.tick(encoder, &mut pcm, &mut opus, &mut ts_pcm).await?;
+            self.tick(pipeline).await?;
 
             if self
                 .config
@@ -151,62 +166,10 @@ impl VoiceSession {
         Ok(())
     }
 
-    async fn tick(
-        &mut self,
-        encoder: &mut Encoder,
-        pcm: &mut [i16],
-        opus: &mut [u8],
-        ts_pcm: &mut [i16],
-    ) -> Result<(), GatewayError> {
-        macro_rules! try_lock_yield {
-            ($mutex:expr) => {{
-                let mut guard = None;
-                for _ in 0..10 {
-                    if let Ok(g) = $mutex.try_lock() {
-                        guard = Some(g);
-                        break;
-                    }
-                    tokio::task::yield_now().await;
-                }
-                guard
-            }};
-        }
-
-        let mut loop_count = 0;
-
-        while loop_count < 10 {
-            loop_count += 1;
-
-            let ready_from_ts = {
-                if let Some(mut filters) = try_lock_yield!(self.config.filter_chain) {
-                    filters.has_timescale() && filters.fill_frame(ts_pcm)
-                } else {
-                    false
-                }
-            };
-
-            if ready_from_ts {
-                self.set_speaking(true);
-                self.config.frames_sent.fetch_add(1, Ordering::Relaxed);
-                if self.speaking_holdoff {
-                    self.speaking_holdoff = false;
-                    self.send_silence().await?;
-                }
-                return self.send_pcm(encoder, ts_pcm, opus).await;
-            }
-
-            let mut has_input = false;
-            let mut opus_data = None;
-
-            if let Some(mut mixer) = try_lock_yield!(self.config.mixer) {
-                if let Some(data) = mixer.take_opus_frame() {
-                    opus_data = Some(data);
-                } else {
-                    has_input = mixer.mix(pcm);
-                }
-            }
-
-            if let Some(data) = opus_data {
+    async fn tick(&mut self, pipeline: &mut FramePipeline) -> Result<(), GatewayError> {
+        match self.config.feed.poll() {
+            FeedFrame::Detached => {}
+            FeedFrame::Frame(data) => {
                 self.reset_timers();
                 self.set_speaking(true);
                 self.config.frames_sent.fetch_add(1, Ordering::Relaxed);
@@ -216,59 +179,51 @@ impl VoiceSession {
                 }
                 return self.send_raw(&data).await;
             }
-
-            if has_input {
-                self.reset_timers();
-                self.set_speaking(true);
-            } else if self.active_silence > 0 {
-                self.active_silence -= 1;
-                pcm.fill(0);
-                self.set_speaking(true);
-            } else {
+            FeedFrame::Empty => {
+                // The broadcast owns the mixer; only pad with silence here.
+                if self.active_silence > 0 {
+                    self.active_silence -= 1;
+                    self.set_speaking(true);
+                    return self.send_silence().await;
+                }
                 self.set_speaking(false);
                 if self.last_tx_time.elapsed() >= Duration::from_millis(UDP_KEEPALIVE_GAP_MS) {
                     return self.send_silence().await;
                 }
                 return Ok(());
             }
+        }
 
-            let has_ts = {
-                if let Some(mut filters) = try_lock_yield!(self.config.filter_chain) {
-                    filters.process(pcm);
-                    filters.has_timescale()
-                } else {
-                    false
+        match pipeline.next_frame(self.active_silence > 0).await {
+            PipelineFrame::Idle => {
+                self.set_speaking(false);
+                if self.last_tx_time.elapsed() >= Duration::from_millis(UDP_KEEPALIVE_GAP_MS) {
+                    return self.send_silence().await;
                 }
-            };
-
-            if !has_ts {
-                if has_input {
-                    self.config.frames_sent.fetch_add(1, Ordering::Relaxed);
-                } else {
+                Ok(())
+            }
+            PipelineFrame::Pending => Ok(()),
+            PipelineFrame::Opus { packet, padded } => {
+                if padded {
+                    self.active_silence = self.active_silence.saturating_sub(1);
                     self.config.frames_nulled.fetch_add(1, Ordering::Relaxed);
+                } else {
+                    self.reset_timers();
+                    self.config.frames_sent.fetch_add(1, Ordering::Relaxed);
                 }
-
+                self.set_speaking(true);
                 if self.speaking_holdoff {
                     self.speaking_holdoff = false;
                     self.send_silence().await?;
                 }
-                return self.send_pcm(encoder, pcm, opus).await;
-            }
 
-            let filled_on_silence = {
-                if let Some(mut filters) = try_lock_yield!(self.config.filter_chain) {
-                    !has_input && filters.fill_frame(ts_pcm)
+                if packet.is_empty() {
+                    self.send_silence().await
                 } else {
-                    false
+                    self.send_raw(packet).await
                 }
-            };
-
-            if !has_input && !filled_on_silence {
-                break;
             }
         }
-
-        Ok(())
     }
 
     fn set_speaking(&mut self, speaking: bool) {
@@ -281,35 +236,16 @@ impl VoiceSession {
         }
     }
 
-    async fn send_pcm(
-        &mut self,
-        encoder: &mut Encoder,
-        pcm: &[i16],
-        opus: &mut [u8],
-    ) -> Result<(), GatewayError> {
-        let size = match encoder.encode(pcm, opus) {
-            Ok(s) => s,
-            Err(e) => {
-                error!("Opus encode failed: {e}");
-                0
-            }
-        };
-
-        if size > 0 {
-            self.send_raw(&opus[..size]).await?;
-        } else {
-            self.send_silence().await?;
-        }
-
-        Ok(())
-    }
-
     async fn send_silence(&mut self) -> Result<(), GatewayError> {
         self.config.frames_nulled.fetch_add(1, Ordering::Relaxed);
         self.send_raw(&SILENCE_FRAME).await
     }
 
     async fn send_raw(&mut self, data: &[u8]) -> Result<(), GatewayError> {
+        if self.config.monitor.receiver_count() > 0 {
+            let _ = self.config.monitor.send(OpusPacket::from(data));
+        }
+
         let mut dave = self.config.dave.lock().await;
         let encrypted = dave
             .encrypt_opus(data)
@@ -1,3 +1,4 @@
+pub mod analysis;
 pub mod audio;
 pub mod common;
 pub mod config;
@@ -8,6 +9,7 @@ pub mod player;
 pub mod protocol;
 pub mod rest;
 pub mod routeplanner;
+pub mod segments;
 pub mod server;
 pub mod sources;
 pub mod ws;
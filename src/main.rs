@@ -43,8 +43,16 @@ async fn main() -> AnyResult<()> {
         None
     };
 
+    rustalink::audio::worker::init_decode_pool(config.player.decoder_workers);
+    rustalink::audio::worker::init_background_pool(config.analysis.concurrency);
+
     let source_manager = Arc::new(rustalink::sources::SourceManager::new(&config));
     let lyrics_manager = Arc::new(rustalink::lyrics::LyricsManager::new(&config));
+    let segment_manager = Arc::new(rustalink::segments::SegmentManager::new(&config));
+    let analysis_manager = Arc::new(rustalink::analysis::AnalysisManager::new(
+        &config,
+        source_manager.clone(),
+    ));
     let youtube_ctx = source_manager.youtube_stream_ctx.clone();
 
     let process_stat = perf_monitor::cpu::ProcessStat::cur().map_err(|e| {
@@ -60,6 +68,8 @@ async fn main() -> AnyResult<()> {
         routeplanner,
         source_manager,
         lyrics_manager,
+        segment_manager,
+        analysis_manager,
         config: config.clone(),
         youtube: youtube_ctx,
         system_state: parking_lot::Mutex::new(sysinfo::System::new_all()),
@@ -87,7 +97,40 @@ async fn main() -> AnyResult<()> {
     info!("Rustalink Server listening on {}", address);
 
     let listener = tokio::net::TcpListener::bind(address).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    // Open recordings are only playable once their headers are finalized.
+    let sessions: Vec<_> = shared_state
+        .sessions
+        .iter()
+        .chain(shared_state.resumable_sessions.iter())
+        .map(|kv| kv.value().clone())
+        .collect();
+    futures::future::join_all(sessions.iter().map(|session| session.stop_recordings())).await;
 
     Ok(())
 }
+
+/// Resolves on Ctrl+C or SIGTERM.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut term =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()).ok();
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = async {
+                match term.as_mut() {
+                    Some(term) => { term.recv().await; }
+                    None => std::future::pending::<()>().await,
+                }
+            } => {}
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
+
+    info!("Shutdown signal received");
+}
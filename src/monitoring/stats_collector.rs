@@ -77,6 +77,25 @@ pub fn collect_stats(app_state: &AppState, session: Option<&Session>) -> protoco
         },
         frame_stats: session
             .and_then(|sess| FrameMetrics::calculate(sess, app_state.config.server.stats_interval)),
+        decoder_pool: decoder_pool_stats(),
+    }
+}
+
+fn decoder_pool_stats() -> protocol::DecoderPoolStats {
+    let pool = crate::audio::worker::get_decode_pool();
+    let workers: Vec<protocol::DecoderWorkerStats> = pool
+        .loads()
+        .into_iter()
+        .map(|w| protocol::DecoderWorkerStats {
+            tracks: w.tasks,
+            load: w.load as f64,
+        })
+        .collect();
+
+    protocol::DecoderPoolStats {
+        size: pool.size(),
+        active_tracks: workers.iter().map(|w| w.tracks).sum(),
+        workers,
     }
 }
 
@@ -0,0 +1,147 @@
+use std::{sync::Arc, time::Duration};
+
+use dashmap::DashMap;
+use tokio::sync::{broadcast, mpsc::UnboundedSender};
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+use crate::{
+    common::types::{AnyResult, BroadcastId, GuildId},
+    gateway::{
+        FramePipeline, LevelSink, OpusFeed, OpusPacket, PipelineFrame,
+        constants::{BROADCAST_CHANNEL_FRAMES, FRAME_DURATION_MS},
+    },
+    player::PlayerContext,
+    protocol::{self, RustalinkEvent},
+};
+
+/// One player's audio, encoded once and fanned out to many voice connections.
+///
+/// The source player keeps its track, volume and filters; the broadcast drives
+/// its mixer and filter chain and publishes the resulting Opus frames. Every
+/// attached connection (the source's own included) sends those frames with its
+/// own RTP, encryption and DAVE state.
+pub struct Broadcast {
+    pub id: BroadcastId,
+    pub source_guild_id: GuildId,
+    source_feed: OpusFeed,
+    tx: broadcast::Sender<OpusPacket>,
+    listeners: DashMap<GuildId, OpusFeed>,
+    cancel: CancellationToken,
+}
+
+impl Broadcast {
+    /// Starts broadcasting `source`. Its `LevelsEvent`s, measured on the
+    /// broadcast audio, go to `event_tx`. The source's voice feed is only
+    /// taken over once the pipeline is up.
+    pub async fn start(
+        id: BroadcastId,
+        source: &PlayerContext,
+        event_tx: UnboundedSender<RustalinkEvent>,
+    ) -> AnyResult<Arc<Self>> {
+        let (mixer, source_feed) = {
+            let engine = source.engine.lock().await;
+            (engine.mixer.clone(), engine.feed.clone())
+        };
+        let pipeline = FramePipeline::new(
+            mixer,
+            source.filter_chain.clone(),
+            source.encoder_config.clone(),
+            source.meter_config.clone(),
+            Some(LevelSink {
+                guild_id: source.guild_id.clone(),
+                event_tx,
+            }),
+        )?;
+
+        let (tx, _) = broadcast::channel(BROADCAST_CHANNEL_FRAMES);
+        let cancel = CancellationToken::new();
+        source_feed.attach(tx.subscribe());
+        tokio::spawn(drive(pipeline, tx.clone(), cancel.clone()));
+
+        debug!("Broadcast {} started from guild {}", id, source.guild_id);
+        Ok(Arc::new(Self {
+            id,
+            source_guild_id: source.guild_id.clone(),
+            source_feed,
+            tx,
+            listeners: DashMap::new(),
+            cancel,
+        }))
+    }
+
+    pub fn attach(&self, guild_id: GuildId, feed: OpusFeed) {
+        feed.attach(self.tx.subscribe());
+        self.listeners.insert(guild_id, feed);
+    }
+
+    pub fn detach(&self, guild_id: &GuildId) -> bool {
+        match self.listeners.remove(guild_id) {
+            Some((_, feed)) => {
+                feed.detach();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_listening(&self, guild_id: &GuildId) -> bool {
+        self.listeners.contains_key(guild_id)
+    }
+
+    pub fn listeners(&self) -> Vec<GuildId> {
+        let mut guilds: Vec<GuildId> = self.listeners.iter().map(|kv| kv.key().clone()).collect();
+        guilds.sort_by(|a, b| a.0.cmp(&b.0));
+        guilds
+    }
+
+    /// Stops the pipeline and hands every connection back to its own mixer.
+    pub fn stop(&self) {
+        self.cancel.cancel();
+        self.source_feed.detach();
+        for kv in self.listeners.iter() {
+            kv.value().detach();
+        }
+        self.listeners.clear();
+        debug!("Broadcast {} stopped", self.id);
+    }
+
+    pub fn to_info(&self) -> protocol::BroadcastInfo {
+        protocol::BroadcastInfo {
+            id: self.id.clone(),
+            guild_id: self.source_guild_id.clone(),
+            players: self.listeners(),
+        }
+    }
+}
+
+impl Drop for Broadcast {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Runs the source player's pipeline every frame and publishes the result,
+/// the voice speak loop minus the transport.
+async fn drive(
+    mut pipeline: FramePipeline,
+    tx: broadcast::Sender<OpusPacket>,
+    cancel: CancellationToken,
+) {
+    let mut interval = tokio::time::interval(Duration::from_millis(FRAME_DURATION_MS));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+
+        // Send errors only mean nobody is listening right now.
+        if let PipelineFrame::Opus { packet, .. } = pipeline.next_frame(false).await
+            && !packet.is_empty()
+        {
+            let _ = tx.send(Arc::from(packet));
+        }
+    }
+}
@@ -1,15 +1,24 @@
-use std::sync::{
-    Arc,
-    atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+    },
+    time::Duration,
 };
 
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 
 use crate::{
-    audio::{filters::FilterChain, playback::TrackHandle},
+    audio::{
+        constants::SEEK_REPLY_TIMEOUT_MS,
+        filters::FilterChain,
+        playback::{PlaybackState, TrackHandle},
+    },
     common::types::Shared,
-    config::player::PlayerConfig,
-    player::state::{Filters, Player, PlayerState, VoiceConnectionState, VoiceState},
+    config::player::{EncoderConfig, MeterConfig, PlayerConfig},
+    player::state::{
+        Filters, Player, PlayerConfigState, PlayerState, VoiceConnectionState, VoiceState,
+    },
     server::AppState,
 };
 
@@ -25,6 +34,10 @@ pub struct PlayerContext {
     pub engine: Shared<crate::gateway::VoiceEngine>,
     pub filters: Filters,
     pub filter_chain: Shared<FilterChain>,
+    /// Encoder settings read by whichever loop encodes this player's audio.
+    pub encoder_config: Shared<EncoderConfig>,
+    /// Level metering settings read by the voice loop.
+    pub meter_config: Shared<MeterConfig>,
     pub end_time: Option<u64>,
     pub stop_signal: Arc<AtomicBool>,
     pub ping: Arc<AtomicI64>,
@@ -37,7 +50,15 @@ pub struct PlayerContext {
     pub lyrics_subscribed: Arc<AtomicBool>,
     pub lyrics_data: Arc<Mutex<Option<crate::protocol::models::LyricsData>>>,
     pub last_lyric_index: Arc<AtomicI64>,
+    /// Chapters of the current track, filled in once the source has been asked.
+    pub chapters: Arc<Mutex<Option<Vec<crate::protocol::tracks::Chapter>>>>,
+    /// Segment categories to skip; `None` falls back to the config defaults.
+    pub segment_categories: Option<Vec<String>>,
+    pub segments: Arc<Mutex<Option<Vec<crate::protocol::segments::Segment>>>>,
     pub tape_stop: Arc<AtomicBool>,
+    /// Broadcast this player is the source of or listening to.
+    pub broadcast: Option<crate::common::types::BroadcastId>,
+    pub recording: Option<crate::player::Recording>,
     pub state: Arc<AppState>,
 }
 
@@ -59,6 +80,8 @@ impl PlayerContext {
             engine: Arc::new(Mutex::new(crate::gateway::VoiceEngine::new())),
             filters: Filters::default(),
             filter_chain: Arc::new(Mutex::new(FilterChain::from_config(&Filters::default()))),
+            encoder_config: Arc::new(Mutex::new(config.encoder)),
+            meter_config: Arc::new(Mutex::new(config.meter)),
             end_time: None,
             stop_signal: Arc::new(AtomicBool::new(false)),
             ping: Arc::new(AtomicI64::new(-1)),
@@ -71,7 +94,12 @@ impl PlayerContext {
             lyrics_subscribed: Arc::new(AtomicBool::new(false)),
             lyrics_data: Arc::new(Mutex::new(None)),
             last_lyric_index: Arc::new(AtomicI64::new(-1)),
+            chapters: Arc::new(Mutex::new(None)),
+            segment_categories: None,
+            segments: Arc::new(Mutex::new(None)),
             tape_stop: Arc::new(AtomicBool::new(config.tape.tape_stop)),
+            broadcast: None,
+            recording: None,
             state,
         }
     }
@@ -120,6 +148,114 @@ impl PlayerContext {
         }
     }
 
+    /// Applies an encoder override; the encoding loop picks it up next frame.
+    pub async fn set_encoder(&mut self, update: crate::player::state::EncoderUpdate) {
+        update.apply(&mut self.config.encoder);
+        *self.encoder_config.lock().await = self.config.encoder;
+    }
+
+    /// Applies a ducking override to the mixer, effective from its next frame.
+    pub async fn set_ducking(&mut self, update: crate::player::state::DuckingUpdate) {
+        update.apply(&mut self.config.ducking);
+        let engine = self.engine.lock().await;
+        engine.mixer.lock().await.set_ducking(&self.config.ducking);
+    }
+
+    /// Applies a metering override; the voice loop picks it up next frame.
+    pub async fn set_meter(&mut self, update: crate::player::state::MeterUpdate) {
+        update.apply(&mut self.config.meter);
+        *self.meter_config.lock().await = self.config.meter;
+    }
+
+    /// Applies a fade override to the mixer, so the playing track ramps with
+    /// the same durations the fade waits below expect.
+    pub async fn set_fade(&mut self, update: crate::player::state::FadeUpdate) {
+        update.apply(&mut self.config.fade);
+        let engine = self.engine.lock().await;
+        engine.mixer.lock().await.set_fade(&self.config.fade);
+    }
+
+    /// Fades the playing track to silence and waits for the ramp, so a stop,
+    /// seek or replacement that follows doesn't click. The player lock is
+    /// only held to start the fade. Returns the track now held silent.
+    pub async fn fade_out(player: &RwLock<Self>) -> Option<TrackHandle> {
+        let (handle, fade_out_ms, guild_id) = {
+            let player = player.read().await;
+            (
+                player.track_handle.clone()?,
+                player.config.fade.fade_out_ms,
+                player.guild_id.clone(),
+            )
+        };
+        if fade_out_ms == 0 {
+            return None;
+        }
+
+        // Registered before the fade starts so the mixer's notification
+        // can't be missed.
+        let signals = handle.signals();
+        let done = signals.fade_done();
+        tokio::pin!(done);
+        done.as_mut().enable();
+        if !handle.fade_out() {
+            return None;
+        }
+
+        let limit = Duration::from_millis(fade_out_ms.saturating_add(250));
+        if tokio::time::timeout(limit, done).await.is_err() {
+            tracing::debug!("Fade-out for player {} timed out", guild_id);
+        }
+        Some(handle)
+    }
+
+    /// Seeks behind a fade-out; the track fades back in once the decoder
+    /// has flushed. A playing track is given [`SEEK_REPLY_TIMEOUT_MS`] to
+    /// finish the seek, so the position reported afterwards is where it
+    /// landed; a failed seek is also reported by the track's monitor. The
+    /// player lock is released while waiting.
+    pub async fn seek_faded(player: &RwLock<Self>, pos: u64) {
+        let faded = Self::fade_out(player).await;
+
+        let mut guard = player.write().await;
+        let Some(handle) = guard.track_handle.clone() else {
+            guard.seek(pos);
+            return;
+        };
+        let seekable = guard.track_info.as_ref().is_none_or(|t| t.info.is_seekable);
+        let faded = faded.is_some_and(|f| f.is_same(&handle));
+
+        let signals = handle.signals();
+        let done = signals.seek_done();
+        tokio::pin!(done);
+        done.as_mut().enable();
+
+        guard.seek(pos);
+        drop(guard);
+        if faded {
+            handle.resume();
+        }
+
+        let mut reported = false;
+        if seekable && handle.get_state() == PlaybackState::Playing {
+            let limit = Duration::from_millis(SEEK_REPLY_TIMEOUT_MS);
+            reported = tokio::time::timeout(limit, done).await.is_ok();
+        }
+        // Without a seek report nothing would end the silence.
+        if faded && !reported {
+            handle.fade_in();
+        }
+
+        let mut guard = player.write().await;
+        if reported
+            && guard
+                .track_handle
+                .as_ref()
+                .is_some_and(|h| h.is_same(&handle))
+        {
+            guard.position = handle.get_position();
+        }
+    }
+
     pub fn stop_track(&mut self) {
         self.track = None;
         self.track_info = None;
@@ -140,6 +276,12 @@ impl PlayerContext {
     pub async fn destroy(&mut self) {
         self.stop_track();
 
+        if let Some(recording) = self.recording.take() {
+            recording
+                .stop(crate::protocol::RecordingEndReason::Cleanup)
+                .await;
+        }
+
         if let Some(task) = self.gateway_task.take() {
             task.abort();
         }
@@ -150,9 +292,9 @@ impl PlayerContext {
     }
 
     pub async fn to_player_response(&self) -> Player {
-        let dave = {
+        let (dave, duck) = {
             let engine = self.engine.lock().await;
-            if let Some(dave_shared) = &engine.dave {
+            let dave = if let Some(dave_shared) = &engine.dave {
                 let dave = dave_shared.lock().await;
                 Some(crate::player::state::DaveState {
                     protocol_version: dave.protocol_version(),
@@ -160,7 +302,8 @@ impl PlayerContext {
                 })
             } else {
                 None
-            }
+            };
+            (dave, engine.duck.clone())
         };
 
         Player {
@@ -177,6 +320,7 @@ impl PlayerContext {
                     .unwrap_or(self.position),
                 connected: !self.voice.token.is_empty(),
                 ping: self.ping.load(Ordering::Acquire),
+                live_latency: self.track_handle.as_ref().and_then(|h| h.live_latency()),
             },
             voice: VoiceState {
                 token: self.voice.token.clone(),
@@ -186,11 +330,26 @@ impl PlayerContext {
             },
             filters: self.filters.clone(),
             dave,
+            broadcast: self.broadcast.clone(),
+            config: PlayerConfigState::from(&self.config).with_duck(&duck),
         }
     }
 
     pub async fn to_response(arc: Arc<tokio::sync::RwLock<Self>>) -> Player {
-        let (guild_id, track_info, volume, paused, position, voice, ping, filters, engine_shared) = {
+        let (
+            guild_id,
+            track_info,
+            volume,
+            paused,
+            position,
+            live_latency,
+            voice,
+            ping,
+            filters,
+            engine_shared,
+            broadcast,
+            config,
+        ) = {
             let this = arc.read().await;
             (
                 this.guild_id.clone(),
@@ -201,16 +360,19 @@ impl PlayerContext {
                     .as_ref()
                     .map(|h| h.get_position())
                     .unwrap_or(this.position),
+                this.track_handle.as_ref().and_then(|h| h.live_latency()),
                 this.voice.clone(),
                 this.ping.load(Ordering::Acquire),
                 this.filters.clone(),
                 this.engine.clone(),
+                this.broadcast.clone(),
+                this.config.clone(),
             )
         };
 
-        let dave = {
+        let (dave, duck) = {
             let engine = engine_shared.lock().await;
-            if let Some(dave_shared) = &engine.dave {
+            let dave = if let Some(dave_shared) = &engine.dave {
                 let dave = dave_shared.lock().await;
                 Some(crate::player::state::DaveState {
                     protocol_version: dave.protocol_version(),
@@ -218,7 +380,8 @@ impl PlayerContext {
                 })
             } else {
                 None
-            }
+            };
+            (dave, engine.duck.clone())
         };
 
         Player {
@@ -231,6 +394,7 @@ impl PlayerContext {
                 position,
                 connected: !voice.token.is_empty(),
                 ping,
+                live_latency,
             },
             voice: VoiceState {
                 token: voice.token,
@@ -240,6 +404,8 @@ impl PlayerContext {
             },
             filters,
             dave,
+            broadcast,
+            config: PlayerConfigState::from(&config).with_duck(&duck),
         }
     }
 }
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::{
+    common::types::GuildId,
+    protocol::{self, events::RustalinkEvent, tracks::Chapter},
+    server::Session,
+};
+
+pub type ChapterList = Arc<Mutex<Option<Vec<Chapter>>>>;
+
+/// Emit `ChapterStartEvent` when playback moves into a different chapter,
+/// whether by playing through a boundary or by seeking.
+pub fn sync_chapter(
+    guild_id: &GuildId,
+    pos_ms: u64,
+    last_idx: &mut Option<usize>,
+    chapters: &ChapterList,
+    session: &Session,
+) {
+    let Ok(lock) = chapters.try_lock() else {
+        return;
+    };
+    let Some(chapters) = &*lock else { return };
+
+    let current = chapters.iter().rposition(|c| pos_ms >= c.start);
+    if current == *last_idx {
+        return;
+    }
+    *last_idx = current;
+
+    if let Some(index) = current {
+        session.send_message(&protocol::OutgoingMessage::Event {
+            event: Box::new(RustalinkEvent::ChapterStart {
+                guild_id: guild_id.clone(),
+                index,
+                chapter: chapters[index].clone(),
+            }),
+        });
+    }
+}
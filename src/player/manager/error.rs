@@ -32,6 +32,7 @@ pub async fn send_load_failed(player: &PlayerContext, session: &Session, message
             guild_id,
             track,
             reason: TrackEndReason::LoadFailed,
+            trimmed: None,
         }),
     });
 }
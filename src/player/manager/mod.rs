@@ -1,6 +1,8 @@
+pub mod chapters;
 pub mod error;
 pub mod lyrics;
 pub mod monitor;
+pub mod segments;
 pub mod start;
 
 pub use start::start_playback;
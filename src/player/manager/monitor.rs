@@ -2,14 +2,18 @@ use std::sync::{Arc, atomic::Ordering};
 
 use tracing::warn;
 
-use super::lyrics::sync_lyrics;
+use super::{
+    chapters::{ChapterList, sync_chapter},
+    lyrics::sync_lyrics,
+    segments::{SegmentList, skip_segment},
+};
 use crate::{
     audio::playback::{PlaybackState, TrackHandle},
     common::types::GuildId,
     player::state::PlayerState,
     protocol::{
         self,
-        events::{RustalinkEvent, TrackEndReason, TrackException},
+        events::{RustalinkEvent, TrackEndReason, TrackException, TrimmedSilence},
         models::LyricsData,
         tracks::Track,
     },
@@ -29,7 +33,23 @@ pub struct MonitorCtx {
     pub lyrics_subscribed: Arc<std::sync::atomic::AtomicBool>,
     pub lyrics_data: Arc<tokio::sync::Mutex<Option<LyricsData>>>,
     pub last_lyric_index: Arc<std::sync::atomic::AtomicI64>,
+    pub chapters: ChapterList,
+    pub segments: SegmentList,
     pub end_time_ms: Option<u64>,
+    /// Whether silence trimming was enabled when the track started.
+    pub trim_silence: bool,
+}
+
+impl MonitorCtx {
+    fn trimmed(&self) -> Option<TrimmedSilence> {
+        self.trim_silence.then(|| {
+            let stats = self.handle.trim_stats();
+            TrimmedSilence {
+                leading_ms: stats.leading_ms(),
+                trailing_ms: stats.trailing_ms(),
+            }
+        })
+    }
 }
 
 pub async fn monitor_loop(ctx: MonitorCtx) {
@@ -39,6 +59,7 @@ pub async fn monitor_loop(ctx: MonitorCtx) {
     let mut last_pos_changed_at = std::time::Instant::now();
     let mut stuck_fired = false;
     let mut buffering_started_at: Option<std::time::Instant> = None;
+    let mut last_chapter: Option<usize> = None;
 
     loop {
         interval.tick().await;
@@ -57,6 +78,16 @@ pub async fn monitor_loop(ctx: MonitorCtx) {
 
         let cur_pos = ctx.handle.get_position();
 
+        if ctx.handle.signals().take_seek_failure() {
+            ctx.session.send_message(&protocol::OutgoingMessage::Event {
+                event: Box::new(RustalinkEvent::SeekFailed {
+                    guild_id: ctx.guild_id.clone(),
+                    track: ctx.track.clone(),
+                    position: cur_pos,
+                }),
+            });
+        }
+
         if let Some(end_ms) = ctx.end_time_ms
             && cur_pos >= end_ms
             && state == PlaybackState::Playing
@@ -82,6 +113,14 @@ pub async fn monitor_loop(ctx: MonitorCtx) {
                 stuck_fired =
                     check_stuck(&ctx, cur_pos, last_pos_changed_at, buffering_started_at).await;
             }
+
+            skip_segment(
+                &ctx.guild_id,
+                cur_pos,
+                &ctx.handle,
+                &ctx.segments,
+                &ctx.session,
+            );
         } else {
             last_pos_changed_at = std::time::Instant::now();
             buffering_started_at = None;
@@ -103,6 +142,14 @@ pub async fn monitor_loop(ctx: MonitorCtx) {
             )
             .await;
         }
+
+        sync_chapter(
+            &ctx.guild_id,
+            cur_pos,
+            &mut last_chapter,
+            &ctx.chapters,
+            &ctx.session,
+        );
     }
 }
 
@@ -138,6 +185,7 @@ async fn handle_playback_stopped(ctx: &MonitorCtx) {
             guild_id: ctx.guild_id.clone(),
             track: ctx.track.clone(),
             reason,
+            trimmed: ctx.trimmed(),
         }),
     });
 }
@@ -153,6 +201,7 @@ async fn handle_track_end_marker(ctx: &MonitorCtx) {
             guild_id: ctx.guild_id.clone(),
             track: ctx.track.clone(),
             reason: TrackEndReason::Finished,
+            trimmed: ctx.trimmed(),
         }),
     });
 }
@@ -206,6 +255,7 @@ fn send_player_update(ctx: &MonitorCtx, cur_pos: u64) {
                 position: cur_pos,
                 connected: true,
                 ping: ctx.ping.load(Ordering::Acquire),
+                live_latency: ctx.handle.live_latency(),
             },
         });
 }
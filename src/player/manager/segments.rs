@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::{
+    audio::playback::TrackHandle,
+    common::types::GuildId,
+    protocol::{self, events::RustalinkEvent, segments::Segment, tracks::TrackInfo},
+    segments::SegmentManager,
+    server::Session,
+};
+
+pub type SegmentList = Arc<Mutex<Option<Vec<Segment>>>>;
+
+/// Spawn a non-blocking task that fetches skippable segments and announces them.
+pub fn spawn_segments_fetch(
+    segments: SegmentList,
+    categories: Vec<String>,
+    track_info: TrackInfo,
+    segment_manager: Arc<SegmentManager>,
+    session: Arc<Session>,
+    guild_id: GuildId,
+) {
+    tokio::spawn(async move {
+        let list = segment_manager
+            .load_segments(&track_info, &categories)
+            .await;
+        if list.is_empty() {
+            return;
+        }
+
+        *segments.lock().await = Some(list.clone());
+        session.send_message(&protocol::OutgoingMessage::Event {
+            event: Box::new(RustalinkEvent::SegmentsLoaded {
+                guild_id,
+                segments: list,
+            }),
+        });
+    });
+}
+
+/// Seek past the segment playback is currently inside, if any.
+pub fn skip_segment(
+    guild_id: &GuildId,
+    pos_ms: u64,
+    handle: &TrackHandle,
+    segments: &SegmentList,
+    session: &Session,
+) {
+    let Ok(lock) = segments.try_lock() else {
+        return;
+    };
+    let Some(segments) = &*lock else { return };
+    let Some(segment) = segments
+        .iter()
+        .find(|s| pos_ms >= s.start && pos_ms < s.end)
+    else {
+        return;
+    };
+
+    handle.seek(segment.end);
+    session.send_message(&protocol::OutgoingMessage::Event {
+        event: Box::new(RustalinkEvent::SegmentSkipped {
+            guild_id: guild_id.clone(),
+            segment: segment.clone(),
+        }),
+    });
+}
@@ -8,6 +8,7 @@ use super::{
     error::send_load_failed,
     lyrics::spawn_lyrics_fetch,
     monitor::{MonitorCtx, monitor_loop},
+    segments::spawn_segments_fetch,
 };
 use crate::{
     audio::playback::{PlaybackState, TrackHandle},
@@ -40,6 +41,8 @@ pub async fn start_playback(player: &mut PlayerContext, config: PlaybackStartCon
     player.end_time = config.end_time;
     player.user_data = config.user_data.unwrap_or_else(|| serde_json::json!({}));
     player.stop_signal = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    player.chapters = Arc::new(tokio::sync::Mutex::new(None));
+    player.segments = Arc::new(tokio::sync::Mutex::new(None));
 
     let track_info = player
         .track_info
@@ -68,7 +71,7 @@ pub async fn start_playback(player: &mut PlayerContext, config: PlaybackStartCon
         Duration::from_secs(30),
         config
             .source_manager
-            .resolve_track(&track_info, config.routeplanner),
+            .resolve_track(&track_info, config.routeplanner.clone()),
     )
     .await
     {
@@ -95,9 +98,14 @@ pub async fn start_playback(player: &mut PlayerContext, config: PlaybackStartCon
         identifier, track_info.source_name
     );
 
+    let chapters = playable.chapters();
+    if !chapters.is_empty() {
+        player.chapters = Arc::new(tokio::sync::Mutex::new(Some(chapters)));
+    }
+
     let (frame_rx, cmd_tx, err_rx) = playable.start_decoding(player.config.clone());
     let (handle, audio_state, vol, pos, is_buffering) =
-        TrackHandle::new(cmd_tx, player.tape_stop.clone());
+        TrackHandle::new(cmd_tx, player.tape_stop.clone(), playable.live_edge());
 
     handle.set_volume(player.volume as f32 / 100.0);
 
@@ -110,6 +118,8 @@ pub async fn start_playback(player: &mut PlayerContext, config: PlaybackStartCon
             vol,
             pos.clone(),
             is_buffering,
+            handle.trim_stats(),
+            handle.signals(),
             player.config.clone(),
         );
     }
@@ -126,7 +136,7 @@ pub async fn start_playback(player: &mut PlayerContext, config: PlaybackStartCon
         handle.pause();
     }
 
-    let Some(track_response) = player.to_player_response().await.track else {
+    let Some(mut track_response) = player.to_player_response().await.track else {
         error!(
             "Failed to build track response for guild {}",
             player.guild_id
@@ -134,6 +144,16 @@ pub async fn start_playback(player: &mut PlayerContext, config: PlaybackStartCon
         return;
     };
 
+    if player.state.config.analysis.attach_to_track_start
+        && let Some(analysis) = player.state.analysis_manager.cached(&track_info)
+        && let Ok(value) = serde_json::to_value(analysis)
+    {
+        if !track_response.plugin_info.is_object() {
+            track_response.plugin_info = serde_json::json!({});
+        }
+        track_response.plugin_info["analysis"] = value;
+    }
+
     config
         .session
         .send_message(&protocol::OutgoingMessage::Event {
@@ -152,6 +172,18 @@ pub async fn start_playback(player: &mut PlayerContext, config: PlaybackStartCon
         player.guild_id.clone(),
     );
 
+    spawn_segments_fetch(
+        player.segments.clone(),
+        player
+            .segment_categories
+            .clone()
+            .unwrap_or_else(|| player.state.segment_manager.default_categories.clone()),
+        track_info.clone(),
+        player.state.segment_manager.clone(),
+        config.session.clone(),
+        player.guild_id.clone(),
+    );
+
     let ctx = MonitorCtx {
         guild_id: player.guild_id.clone(),
         handle: handle.clone(),
@@ -165,7 +197,10 @@ pub async fn start_playback(player: &mut PlayerContext, config: PlaybackStartCon
         lyrics_subscribed: player.lyrics_subscribed.clone(),
         lyrics_data: player.lyrics_data.clone(),
         last_lyric_index: player.last_lyric_index.clone(),
+        chapters: player.chapters.clone(),
+        segments: player.segments.clone(),
         end_time_ms: player.end_time,
+        trim_silence: player.config.silence.enabled,
     };
 
     let track_task = tokio::spawn(monitor_loop(ctx));
@@ -174,6 +209,7 @@ pub async fn start_playback(player: &mut PlayerContext, config: PlaybackStartCon
 }
 
 /// Stop the currently playing track and emit `TrackEnd: Replaced` if needed.
+/// Callers fade it out first with [`PlayerContext::fade_out`].
 async fn stop_current_track(player: &mut PlayerContext, session: &Session) {
     if let Some(handle) = &player.track_handle
         && handle.get_state() != PlaybackState::Stopped
@@ -184,6 +220,7 @@ async fn stop_current_track(player: &mut PlayerContext, session: &Session) {
                 guild_id: player.guild_id.clone(),
                 track,
                 reason: TrackEndReason::Replaced,
+                trimmed: None,
             }),
         });
     }
@@ -1,7 +1,11 @@
+pub mod broadcast;
 pub mod context;
 pub mod manager;
+pub mod recording;
 pub mod state;
 
+pub use broadcast::Broadcast;
 pub use context::PlayerContext;
 pub use manager::start_playback;
+pub use recording::Recording;
 pub use state::*;
@@ -0,0 +1,303 @@
+use std::{
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use audiopus::{Channels, MutSignals, SampleRate, coder::Decoder as OpusDecoder, packet::Packet};
+use tokio::{
+    fs::File,
+    io::{AsyncSeekExt, AsyncWriteExt, BufWriter},
+    sync::{broadcast, oneshot},
+    task::JoinHandle,
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    audio::{
+        constants::{MAX_OPUS_FRAME_SIZE, MIXER_CHANNELS},
+        mux::{OggOpusWriter, rf64_header},
+    },
+    common::types::{AnyResult, GuildId},
+    config::RecordingConfig,
+    gateway::{
+        OpusPacket,
+        constants::{FRAME_DURATION_MS, PCM_FRAME_SAMPLES},
+    },
+    protocol::{self, RecordingEndReason, RecordingFormat, RecordingInfo},
+    server::Session,
+};
+
+const FINALIZE_TIMEOUT_SECS: u64 = 5;
+
+/// Writes everything a player sends to Discord into a file.
+///
+/// Frames come from the voice connection's monitor tap, so the file holds the
+/// mixed and filtered output. Dropping a `Recording` still finalizes the file.
+pub struct Recording {
+    pub info: RecordingInfo,
+    stop_tx: Option<oneshot::Sender<RecordingEndReason>>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl Recording {
+    pub async fn start(
+        config: &RecordingConfig,
+        guild_id: GuildId,
+        monitor: &broadcast::Sender<OpusPacket>,
+        request: protocol::RecordingStart,
+        session: Arc<Session>,
+    ) -> AnyResult<Self> {
+        let started_at = crate::common::utils::now_ms();
+        let name = request
+            .name
+            .as_deref()
+            .map(sanitize_name)
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| format!("{}-{}", guild_id, started_at));
+
+        let dir = Path::new(&config.directory);
+        tokio::fs::create_dir_all(dir).await?;
+        let path = dir.join(format!("{}.{}", name, request.format.extension()));
+
+        let file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .await?;
+
+        let sink = Sink::new(request.format)?;
+        let info = RecordingInfo {
+            path: path.to_string_lossy().into_owned(),
+            format: request.format,
+            started_at,
+        };
+
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let task = tokio::spawn(record(RecordTask {
+            guild_id,
+            path,
+            format: request.format,
+            file: BufWriter::new(file),
+            sink,
+            rx: monitor.subscribe(),
+            stop_rx,
+            session,
+            max_frames: config.max_duration_secs * 1000 / FRAME_DURATION_MS,
+            max_bytes: config.max_size_mb * 1024 * 1024,
+        }));
+
+        info!("Recording started: {}", info.path);
+        Ok(Self {
+            info,
+            stop_tx: Some(stop_tx),
+            task: Some(task),
+        })
+    }
+
+    /// Finalizes the file and waits for the `RecordingFinished` event to go out.
+    pub async fn stop(mut self, reason: RecordingEndReason) {
+        if let Some(tx) = self.stop_tx.take() {
+            let _ = tx.send(reason);
+        }
+        if let Some(task) = self.task.take()
+            && tokio::time::timeout(Duration::from_secs(FINALIZE_TIMEOUT_SECS), task)
+                .await
+                .is_err()
+        {
+            warn!("Recording {} took too long to finalize", self.info.path);
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.as_ref().is_none_or(|t| t.is_finished())
+    }
+}
+
+enum Sink {
+    Ogg(OggOpusWriter),
+    Wav {
+        decoder: OpusDecoder,
+        pcm: Vec<i16>,
+        data_len: u64,
+    },
+}
+
+impl Sink {
+    fn new(format: RecordingFormat) -> AnyResult<Self> {
+        Ok(match format {
+            RecordingFormat::Ogg => Self::Ogg(OggOpusWriter::new(rand::random())),
+            RecordingFormat::Wav => Self::Wav {
+                decoder: OpusDecoder::new(SampleRate::Hz48000, Channels::Stereo)?,
+                pcm: vec![0i16; MAX_OPUS_FRAME_SIZE * MIXER_CHANNELS],
+                data_len: 0,
+            },
+        })
+    }
+
+    fn header(&mut self) -> Vec<u8> {
+        match self {
+            Self::Ogg(writer) => writer.headers(),
+            Self::Wav { .. } => rf64_header(0).to_vec(),
+        }
+    }
+
+    fn frame(&mut self, packet: &[u8]) -> AnyResult<Vec<u8>> {
+        match self {
+            Self::Ogg(writer) => Ok(writer.packet(packet)),
+            Self::Wav {
+                decoder,
+                pcm,
+                data_len,
+            } => {
+                let samples = decoder.decode(
+                    Packet::try_from(packet).ok(),
+                    MutSignals::try_from(pcm.as_mut_slice())?,
+                    false,
+                )?;
+                let bytes: Vec<u8> = pcm[..samples * MIXER_CHANNELS]
+                    .iter()
+                    .flat_map(|s| s.to_le_bytes())
+                    .collect();
+                *data_len += bytes.len() as u64;
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Keeps the timeline intact when the recorder fell behind the voice loop.
+    fn gap(&mut self, frames: u64) -> Vec<u8> {
+        match self {
+            Self::Ogg(writer) => {
+                writer.skip(frames);
+                Vec::new()
+            }
+            Self::Wav { data_len, .. } => {
+                let len = frames as usize * PCM_FRAME_SAMPLES * MIXER_CHANNELS * 2;
+                *data_len += len as u64;
+                vec![0u8; len]
+            }
+        }
+    }
+
+    async fn finish(&mut self, file: &mut BufWriter<File>) -> std::io::Result<()> {
+        match self {
+            Self::Ogg(writer) => file.write_all(&writer.finish()).await?,
+            Self::Wav { data_len, .. } => {
+                file.flush().await?;
+                file.seek(SeekFrom::Start(0)).await?;
+                file.write_all(&rf64_header(*data_len)).await?;
+            }
+        }
+        file.flush().await
+    }
+}
+
+struct RecordTask {
+    guild_id: GuildId,
+    path: PathBuf,
+    format: RecordingFormat,
+    file: BufWriter<File>,
+    sink: Sink,
+    rx: broadcast::Receiver<OpusPacket>,
+    stop_rx: oneshot::Receiver<RecordingEndReason>,
+    session: Arc<Session>,
+    max_frames: u64,
+    max_bytes: u64,
+}
+
+async fn record(mut task: RecordTask) {
+    let mut frames = 0u64;
+    let mut written = 0u64;
+
+    let mut reason = match task.file.write_all(&task.sink.header()).await {
+        Ok(()) => None,
+        Err(e) => {
+            error!("Recording {} failed: {}", task.path.display(), e);
+            Some(RecordingEndReason::Error)
+        }
+    };
+
+    while reason.is_none() {
+        let chunk = tokio::select! {
+            stop = &mut task.stop_rx => {
+                // A dropped handle means the player went away.
+                reason = Some(stop.unwrap_or(RecordingEndReason::Cleanup));
+                break;
+            }
+            packet = task.rx.recv() => match packet {
+                Ok(packet) => {
+                    frames += 1;
+                    task.sink.frame(&packet)
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    frames += missed;
+                    Ok(task.sink.gap(missed))
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    reason = Some(RecordingEndReason::Cleanup);
+                    break;
+                }
+            }
+        };
+
+        let write = match chunk {
+            Ok(bytes) => {
+                written += bytes.len() as u64;
+                task.file.write_all(&bytes).await.map_err(Into::into)
+            }
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = write {
+            error!("Recording {} failed: {}", task.path.display(), e);
+            reason = Some(RecordingEndReason::Error);
+        } else if task.max_frames > 0 && frames >= task.max_frames {
+            reason = Some(RecordingEndReason::MaxDuration);
+        } else if task.max_bytes > 0 && written >= task.max_bytes {
+            reason = Some(RecordingEndReason::MaxSize);
+        }
+    }
+
+    let reason = reason.unwrap_or(RecordingEndReason::Cleanup);
+    if let Err(e) = task.sink.finish(&mut task.file).await {
+        error!(
+            "Failed to finalize recording {}: {}",
+            task.path.display(),
+            e
+        );
+    }
+
+    info!("Recording finished ({:?}): {}", reason, task.path.display());
+    task.session
+        .send_message(&protocol::OutgoingMessage::Event {
+            event: Box::new(protocol::RustalinkEvent::RecordingFinished {
+                guild_id: task.guild_id,
+                path: task.path.to_string_lossy().into_owned(),
+                format: task.format,
+                duration: frames * FRAME_DURATION_MS,
+                reason,
+            }),
+        });
+}
+
+/// Keeps user-supplied names inside the recording directory.
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        .take(128)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_name_strips_paths() {
+        assert_eq!(sanitize_name("../../etc/passwd"), "etcpasswd");
+        assert_eq!(sanitize_name("game-night_01"), "game-night_01");
+        assert_eq!(sanitize_name("/"), "");
+    }
+}
@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{config::player::PlayerConfig, protocol::tracks::Track};
+use crate::{
+    audio::effects::fade::FadeCurve,
+    config::player::{
+        DuckingConfig, EncoderConfig, EncoderSignal, FadeConfig, MeterConfig, PlayerConfig,
+        SilenceConfig,
+    },
+    protocol::tracks::Track,
+};
 
 /// Deserializer for track encoded field which can be null or string.
 pub fn deserialize_track_encoded<'de, D>(deserializer: D) -> Result<Option<TrackEncoded>, D::Error>
@@ -26,6 +33,124 @@ pub struct Player {
     pub voice: VoiceState,
     pub filters: Filters,
     pub dave: Option<DaveState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broadcast: Option<crate::common::types::BroadcastId>,
+    pub config: PlayerConfigState,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerConfigState {
+    pub encoder: EncoderState,
+    pub silence: SilenceState,
+    pub meter: MeterState,
+    pub ducking: DuckingState,
+    pub fade: FadeState,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncoderState {
+    pub bitrate: Option<u32>,
+    pub channel_bitrate: Option<u32>,
+    /// Bitrate actually requested from the encoder; `None` means automatic.
+    pub effective_bitrate: Option<u32>,
+    pub fec: bool,
+    pub packet_loss_percent: u8,
+    pub dtx: bool,
+    pub signal: EncoderSignal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceState {
+    pub enabled: bool,
+    pub threshold_db: f32,
+    pub min_duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeterState {
+    pub enabled: bool,
+    pub interval_ms: u64,
+    pub bands: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuckingState {
+    pub enabled: bool,
+    pub amount_db: f32,
+    pub attack_ms: u64,
+    pub hold_ms: u64,
+    pub release_ms: u64,
+    /// Whether a mix layer is holding the track down right now.
+    pub active: bool,
+    /// Gain currently applied to the track, 0 when not ducked.
+    pub gain_db: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FadeState {
+    pub fade_in_ms: u64,
+    pub fade_out_ms: u64,
+    pub curve: FadeCurve,
+}
+
+impl PlayerConfigState {
+    /// Fills in the live ducking state of the player's mixer.
+    pub fn with_duck(mut self, duck: &crate::audio::mix::DuckState) -> Self {
+        self.ducking.active = duck.is_active();
+        self.ducking.gain_db = (20.0 * duck.gain().log10()).max(-96.0);
+        self
+    }
+}
+
+impl From<&PlayerConfig> for PlayerConfigState {
+    fn from(config: &PlayerConfig) -> Self {
+        let encoder = &config.encoder;
+        let silence = &config.silence;
+        let meter = &config.meter;
+        let ducking = &config.ducking;
+        let fade = &config.fade;
+        Self {
+            encoder: EncoderState {
+                bitrate: encoder.bitrate,
+                channel_bitrate: encoder.channel_bitrate,
+                effective_bitrate: encoder.effective_bitrate(),
+                fec: encoder.fec,
+                packet_loss_percent: encoder.packet_loss_percent,
+                dtx: encoder.dtx,
+                signal: encoder.signal,
+            },
+            silence: SilenceState {
+                enabled: silence.enabled,
+                threshold_db: silence.threshold_db,
+                min_duration_ms: silence.min_duration_ms,
+            },
+            meter: MeterState {
+                enabled: meter.enabled,
+                interval_ms: meter.interval_ms(),
+                bands: meter.bands(),
+            },
+            ducking: DuckingState {
+                enabled: ducking.enabled,
+                amount_db: ducking.amount_db,
+                attack_ms: ducking.attack_ms,
+                hold_ms: ducking.hold_ms,
+                release_ms: ducking.release_ms,
+                active: false,
+                gain_db: 0.0,
+            },
+            fade: FadeState {
+                fade_in_ms: fade.fade_in_ms,
+                fade_out_ms: fade.fade_out_ms,
+                curve: fade.curve,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -47,6 +172,9 @@ pub struct PlayerState {
     pub position: u64,
     pub connected: bool,
     pub ping: i64,
+    /// How far playback trails the live edge (ms), for live streams.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub live_latency: Option<u64>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -97,6 +225,9 @@ pub struct PlayerUpdate {
     pub track: Option<PlayerUpdateTrack>,
     #[serde(default)]
     pub position: Option<u64>,
+    /// Seek to the start of this chapter of the current track.
+    #[serde(default)]
+    pub chapter: Option<usize>,
     #[serde(default)]
     pub end_time: Option<EndTime>,
     #[serde(default)]
@@ -108,7 +239,188 @@ pub struct PlayerUpdate {
     #[serde(default)]
     pub voice: Option<VoiceState>,
     #[serde(default)]
-    pub config: Option<PlayerConfig>,
+    pub config: Option<PlayerConfigUpdate>,
+}
+
+/// Per-player fade override, applied to the following fades, the playing
+/// track's included.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FadeUpdate {
+    #[serde(default)]
+    pub fade_in_ms: Option<u64>,
+    #[serde(default)]
+    pub fade_out_ms: Option<u64>,
+    #[serde(default)]
+    pub curve: Option<FadeCurve>,
+}
+
+impl FadeUpdate {
+    pub fn apply(self, config: &mut FadeConfig) {
+        if let Some(ms) = self.fade_in_ms {
+            config.fade_in_ms = ms.min(FadeConfig::MAX_FADE_MS);
+        }
+        if let Some(ms) = self.fade_out_ms {
+            config.fade_out_ms = ms.min(FadeConfig::MAX_FADE_MS);
+        }
+        if let Some(curve) = self.curve {
+            config.curve = curve;
+        }
+    }
+}
+
+/// Per-player settings that can change while the player is running.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerConfigUpdate {
+    #[serde(default)]
+    pub encoder: Option<EncoderUpdate>,
+    #[serde(default)]
+    pub silence: Option<SilenceUpdate>,
+    #[serde(default)]
+    pub meter: Option<MeterUpdate>,
+    #[serde(default)]
+    pub ducking: Option<DuckingUpdate>,
+    #[serde(default)]
+    pub fade: Option<FadeUpdate>,
+}
+
+/// Per-player silence trimming override, applied from the next track.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceUpdate {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub threshold_db: Option<f32>,
+    #[serde(default)]
+    pub min_duration_ms: Option<u64>,
+}
+
+impl SilenceUpdate {
+    pub fn apply(self, config: &mut SilenceConfig) {
+        if let Some(enabled) = self.enabled {
+            config.enabled = enabled;
+        }
+        if let Some(db) = self.threshold_db {
+            config.threshold_db = db.min(0.0);
+        }
+        if let Some(ms) = self.min_duration_ms {
+            config.min_duration_ms = ms.min(SilenceConfig::MAX_MIN_DURATION_MS);
+        }
+    }
+}
+
+/// Per-player level metering override, applied on the next frame.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeterUpdate {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+    #[serde(default)]
+    pub bands: Option<usize>,
+}
+
+impl MeterUpdate {
+    pub fn apply(self, config: &mut MeterConfig) {
+        if let Some(enabled) = self.enabled {
+            config.enabled = enabled;
+        }
+        if let Some(ms) = self.interval_ms {
+            config.interval_ms = ms.max(MeterConfig::MIN_INTERVAL_MS);
+        }
+        if let Some(bands) = self.bands {
+            config.bands = bands.clamp(1, MeterConfig::MAX_BANDS);
+        }
+    }
+}
+
+/// Per-player ducking override, applied on the next frame.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuckingUpdate {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub amount_db: Option<f32>,
+    #[serde(default)]
+    pub attack_ms: Option<u64>,
+    #[serde(default)]
+    pub hold_ms: Option<u64>,
+    #[serde(default)]
+    pub release_ms: Option<u64>,
+}
+
+impl DuckingUpdate {
+    pub fn apply(self, config: &mut DuckingConfig) {
+        if let Some(enabled) = self.enabled {
+            config.enabled = enabled;
+        }
+        if let Some(db) = self.amount_db {
+            config.amount_db = db.max(0.0);
+        }
+        if let Some(ms) = self.attack_ms {
+            config.attack_ms = ms;
+        }
+        if let Some(ms) = self.hold_ms {
+            config.hold_ms = ms;
+        }
+        if let Some(ms) = self.release_ms {
+            config.release_ms = ms;
+        }
+    }
+}
+
+/// Per-player encoder override; fields left out keep their current value and
+/// a `null` bitrate returns to automatic.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncoderUpdate {
+    #[serde(default, deserialize_with = "deserialize_optional_bitrate")]
+    pub bitrate: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "deserialize_optional_bitrate")]
+    pub channel_bitrate: Option<Option<u32>>,
+    #[serde(default)]
+    pub fec: Option<bool>,
+    #[serde(default)]
+    pub packet_loss_percent: Option<u8>,
+    #[serde(default)]
+    pub dtx: Option<bool>,
+    #[serde(default)]
+    pub signal: Option<EncoderSignal>,
+}
+
+/// Distinguishes an explicit `null` (back to automatic) from a missing field.
+fn deserialize_optional_bitrate<'de, D>(deserializer: D) -> Result<Option<Option<u32>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<u32>::deserialize(deserializer).map(Some)
+}
+
+impl EncoderUpdate {
+    pub fn apply(self, config: &mut EncoderConfig) {
+        if let Some(bitrate) = self.bitrate {
+            config.bitrate = bitrate;
+        }
+        if let Some(bitrate) = self.channel_bitrate {
+            config.channel_bitrate = bitrate;
+        }
+        if let Some(fec) = self.fec {
+            config.fec = fec;
+        }
+        if let Some(percent) = self.packet_loss_percent {
+            config.packet_loss_percent = percent.min(100);
+        }
+        if let Some(dtx) = self.dtx {
+            config.dtx = dtx;
+        }
+        if let Some(signal) = self.signal {
+            config.signal = signal;
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Deserialize)]
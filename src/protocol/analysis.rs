@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// Tempo, key and loudness of a track.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioAnalysis {
+    /// Beats per minute.
+    pub bpm: Option<f64>,
+    /// Tonic and mode, e.g. `A minor`.
+    pub key: Option<String>,
+    /// Camelot wheel position of `key`, e.g. `8A`.
+    pub camelot: Option<String>,
+    /// Integrated loudness in LUFS.
+    pub loudness: Option<f64>,
+    /// Sample peak in dBFS.
+    pub peak: Option<f64>,
+    /// Length of audio the results are based on, in milliseconds.
+    pub analyzed_ms: u64,
+}
+
+/// One entry of the `analyze` response, in request order.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackAnalysis {
+    pub encoded: String,
+    pub analysis: Option<AudioAnalysis>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Output levels of a player over one metering interval, in dBFS.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioLevels {
+    /// RMS level of the left and right channel.
+    pub rms: [f32; 2],
+    /// Sample peak of the left and right channel.
+    pub peak: [f32; 2],
+    /// Peak level of each log-spaced band from 40 Hz to 16 kHz, lowest first.
+    pub spectrum: Vec<f32>,
+}
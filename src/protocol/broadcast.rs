@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use crate::common::types::{BroadcastId, GuildId};
+
+/// Request body for POST /v4/sessions/{sessionId}/broadcasts.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastCreate {
+    /// Player whose output is fanned out.
+    pub guild_id: GuildId,
+}
+
+/// A broadcast and the players currently listening to it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastInfo {
+    pub id: BroadcastId,
+    pub guild_id: GuildId,
+    pub players: Vec<GuildId>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Broadcasts {
+    pub broadcasts: Vec<BroadcastInfo>,
+}
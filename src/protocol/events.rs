@@ -46,6 +46,9 @@ pub enum RustalinkEvent {
         guild_id: crate::common::types::GuildId,
         track: Track,
         reason: TrackEndReason,
+        /// Silence trimmed from the track, when trimming was enabled for it.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        trimmed: Option<TrimmedSilence>,
     },
 
     #[serde(rename = "TrackExceptionEvent")]
@@ -65,6 +68,15 @@ pub enum RustalinkEvent {
         threshold_ms: u64,
     },
 
+    #[serde(rename = "SeekFailedEvent")]
+    SeekFailed {
+        #[serde(rename = "guildId")]
+        guild_id: crate::common::types::GuildId,
+        track: Track,
+        /// Where playback continues, in milliseconds.
+        position: u64,
+    },
+
     #[serde(rename = "LyricsFoundEvent")]
     LyricsFound {
         #[serde(rename = "guildId")]
@@ -97,6 +109,48 @@ pub enum RustalinkEvent {
         #[serde(rename = "byRemote")]
         by_remote: bool,
     },
+
+    #[serde(rename = "ChapterStartEvent")]
+    ChapterStart {
+        #[serde(rename = "guildId")]
+        guild_id: crate::common::types::GuildId,
+        /// Position of the chapter in the track's chapter list.
+        index: usize,
+        chapter: super::tracks::Chapter,
+    },
+
+    #[serde(rename = "SegmentsLoaded")]
+    SegmentsLoaded {
+        #[serde(rename = "guildId")]
+        guild_id: crate::common::types::GuildId,
+        segments: Vec<super::segments::Segment>,
+    },
+
+    #[serde(rename = "SegmentSkipped")]
+    SegmentSkipped {
+        #[serde(rename = "guildId")]
+        guild_id: crate::common::types::GuildId,
+        segment: super::segments::Segment,
+    },
+
+    #[serde(rename = "RecordingFinishedEvent")]
+    RecordingFinished {
+        #[serde(rename = "guildId")]
+        guild_id: crate::common::types::GuildId,
+        path: String,
+        format: super::recording::RecordingFormat,
+        /// Recorded audio length in milliseconds.
+        duration: u64,
+        reason: super::recording::RecordingEndReason,
+    },
+
+    #[serde(rename = "LevelsEvent")]
+    Levels {
+        #[serde(rename = "guildId")]
+        guild_id: crate::common::types::GuildId,
+        #[serde(flatten)]
+        levels: super::analysis::AudioLevels,
+    },
 }
 
 /// Why a track stopped playing.
@@ -131,6 +185,14 @@ pub enum TrackEndReason {
     Cleanup,
 }
 
+/// Leading and trailing silence cut from a track, in milliseconds.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrimmedSilence {
+    pub leading_ms: u64,
+    pub trailing_ms: u64,
+}
+
 /// Exception details for `TrackExceptionEvent`.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
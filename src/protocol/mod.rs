@@ -18,21 +18,29 @@ pub enum CodecError {
     Utf8(#[from] std::string::FromUtf8Error),
 }
 
+pub mod analysis;
+pub mod broadcast;
 pub mod codec;
 pub mod events;
 pub mod info;
 pub mod models;
 pub mod opcodes;
+pub mod recording;
 pub mod routeplanner;
+pub mod segments;
 pub mod session;
 pub mod stats;
 pub mod tracks;
 
+pub use analysis::*;
+pub use broadcast::*;
 pub use codec::*;
 pub use events::*;
 pub use info::*;
 pub use models::*;
+pub use recording::*;
 pub use routeplanner::*;
+pub use segments::*;
 pub use session::*;
 pub use stats::*;
 pub use tracks::*;
@@ -23,6 +23,39 @@ pub struct DecodeTrackQuery {
     pub track: Option<String>,
 }
 
+/// Output container for the `stream` endpoint.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamFormat {
+    /// Decoded and re-encoded as Ogg/Opus, works for every source.
+    #[default]
+    Ogg,
+    /// The source's own file, proxied with range support.
+    Original,
+}
+
+/// Request parameters for the `stream` endpoint.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamTrackQuery {
+    pub encoded_track: String,
+    #[serde(default)]
+    pub format: StreamFormat,
+    /// Start offset in milliseconds, only applies to `ogg`.
+    pub position: Option<u64>,
+}
+
+/// Request parameters for the `recommendations` endpoint.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendationsQuery {
+    pub encoded_track: String,
+    /// Maximum number of tracks, 1-100.
+    pub limit: Option<usize>,
+    /// Comma-separated encoded tracks already played; they are left out.
+    pub history: Option<String>,
+}
+
 /// e.g. POST body: ["encoded1", "encoded2"]
 #[derive(Deserialize)]
 pub struct EncodedTracks(pub Vec<String>);
@@ -128,6 +128,8 @@ async fn handle_voice_update(
         let guild = player.guild_id.clone();
         let voice_state = player.voice.clone();
         let filter_chain = player.filter_chain.clone();
+        let encoder_config = player.encoder_config.clone();
+        let meter_config = player.meter_config.clone();
         let ping = player.ping.clone();
 
         if let Some(task) = player.gateway_task.take() {
@@ -139,25 +141,16 @@ async fn handle_voice_update(
 
         drop(player);
 
-        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
-        let session_clone = session.clone();
-        tokio::spawn(async move {
-            while let Some(event) = event_rx.recv().await {
-                let msg = crate::protocol::OutgoingMessage::Event {
-                    event: Box::new(event),
-                };
-                session_clone.send_message(&msg);
-            }
-        });
-
         let new_task = crate::server::connect_voice(crate::server::voice::VoiceConnectConfig {
             engine,
             guild_id: guild,
             user_id: uid,
             voice: voice_state,
             filter_chain,
+            encoder_config,
+            meter_config,
             ping,
-            event_tx: Some(event_tx),
+            event_tx: Some(session.event_sender()),
             frames_sent,
             frames_nulled,
         })
@@ -178,6 +171,7 @@ async fn handle_play(
     track: String,
 ) -> Result<(), String> {
     let player_arc = session.get_or_create_player(guild_id, state.clone());
+    crate::player::PlayerContext::fade_out(&player_arc).await;
     let mut player = player_arc.write().await;
 
     crate::player::start_playback(
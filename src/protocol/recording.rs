@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingFormat {
+    /// Opus frames exactly as sent to Discord, in an Ogg container.
+    #[default]
+    Ogg,
+    /// 16-bit 48 kHz stereo PCM.
+    Wav,
+}
+
+impl RecordingFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Ogg => "ogg",
+            Self::Wav => "wav",
+        }
+    }
+}
+
+/// Request body for POST /v4/sessions/{sessionId}/players/{guildId}/recording.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecordingStart {
+    pub format: RecordingFormat,
+    /// File name without extension; defaults to `{guildId}-{timestamp}`.
+    pub name: Option<String>,
+}
+
+/// A recording in progress.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingInfo {
+    pub path: String,
+    pub format: RecordingFormat,
+    /// Unix timestamp in milliseconds.
+    pub started_at: u64,
+}
+
+/// Why a recording was finalized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingEndReason {
+    /// Stopped through the REST API.
+    Stopped,
+    /// `recording.max_duration_secs` was reached.
+    MaxDuration,
+    /// `recording.max_size_mb` was reached.
+    MaxSize,
+    /// The player was destroyed or the node shut down.
+    Cleanup,
+    /// Writing to disk failed; the file holds everything written before the error.
+    Error,
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// A stretch of a track that should be skipped, e.g. a sponsor read or a
+/// non-music intro.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Segment {
+    pub category: String,
+    /// Start of the segment in milliseconds.
+    pub start: u64,
+    /// End of the segment in milliseconds.
+    pub end: u64,
+}
@@ -11,6 +11,7 @@ pub struct Stats {
     pub cpu: Cpu,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frame_stats: Option<FrameStats>,
+    pub decoder_pool: DecoderPoolStats,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -37,3 +38,18 @@ pub struct FrameStats {
     pub nulled: i32,
     pub deficit: i32,
 }
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecoderPoolStats {
+    pub size: usize,
+    pub active_tracks: usize,
+    pub workers: Vec<DecoderWorkerStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecoderWorkerStats {
+    pub tracks: usize,
+    pub load: f64,
+}
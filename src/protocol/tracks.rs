@@ -76,6 +76,25 @@ pub enum LoadResult {
     Error(LoadError),
 }
 
+impl LoadResult {
+    /// Result of searching a catalog for an ISRC: the first track reporting
+    /// that exact ISRC, otherwise the search results for the caller to score.
+    pub fn from_isrc_search(tracks: Vec<Track>, isrc: &str) -> Self {
+        if tracks.is_empty() {
+            return Self::Empty {};
+        }
+        match tracks.iter().position(|t| {
+            t.info
+                .isrc
+                .as_deref()
+                .is_some_and(|i| crate::common::utils::same_isrc(i, isrc))
+        }) {
+            Some(index) => Self::Track(tracks.into_iter().nth(index).unwrap()),
+            None => Self::Search(tracks),
+        }
+    }
+}
+
 /// Playlist data returned from a load operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -85,6 +104,18 @@ pub struct PlaylistData {
     pub tracks: Vec<Track>,
 }
 
+impl PlaylistData {
+    /// Marks the first track matching `matches` as the selected one, so
+    /// clients can start where a shared link points.
+    pub fn select(&mut self, matches: impl FnMut(&Track) -> bool) {
+        self.info.selected_track = self
+            .tracks
+            .iter()
+            .position(matches)
+            .map_or(-1, |i| i as i32);
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TextData {
@@ -112,6 +143,18 @@ pub struct PlaylistInfo {
     pub selected_track: i32,
 }
 
+/// A chapter marker, listed under `pluginInfo.chapters` on tracks that have them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    pub title: String,
+    /// Start of the chapter in milliseconds.
+    pub start: u64,
+    /// End of the chapter in milliseconds (the next chapter's start, or the track length).
+    pub end: u64,
+    pub thumbnail: Option<String>,
+}
+
 /// Error from a failed track load.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -126,3 +169,37 @@ pub struct LoadError {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cause_stack_trace: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(title: &str, isrc: Option<&str>) -> Track {
+        Track::new(TrackInfo {
+            title: title.to_owned(),
+            isrc: isrc.map(str::to_owned),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_from_isrc_search_prefers_exact_hit() {
+        let result = LoadResult::from_isrc_search(
+            vec![
+                track("Cover", None),
+                track("Remaster", Some("GBAYE0601690")),
+                track("Original", Some("gb-aye-06-01498")),
+            ],
+            "GBAYE0601498",
+        );
+        assert!(matches!(result, LoadResult::Track(t) if t.info.title == "Original"));
+
+        let result = LoadResult::from_isrc_search(vec![track("Cover", None)], "GBAYE0601498");
+        assert!(matches!(result, LoadResult::Search(tracks) if tracks.len() == 1));
+
+        assert!(matches!(
+            LoadResult::from_isrc_search(Vec::new(), "GBAYE0601498"),
+            LoadResult::Empty {}
+        ));
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use axum::{
     Router,
     middleware::{from_fn, from_fn_with_state},
-    routing::{get, post},
+    routing::{get, post, put},
 };
 
 pub mod middleware;
@@ -11,7 +11,7 @@ pub mod routes;
 
 use self::{
     middleware::{add_response_headers, check_auth},
-    routes::{lyrics, player, stats, youtube},
+    routes::{analysis, artwork, broadcast, lyrics, player, stats, stream, youtube},
 };
 use crate::server::AppState;
 
@@ -25,6 +25,9 @@ pub fn router(state: Arc<AppState>) -> Router {
         .route("/stats", get(stats::get_stats))
         .route("/decodetrack", get(stats::decode_track))
         .route("/decodetracks", post(stats::decode_tracks))
+        .route("/analyze", post(analysis::analyze_tracks))
+        .route("/recommendations", get(stats::get_recommendations))
+        .route("/stream", get(stream::stream_track))
         .route("/sessions/{session_id}/players", get(player::get_players))
         .route(
             "/sessions/{session_id}/players/{guild_id}",
@@ -32,10 +35,37 @@ pub fn router(state: Arc<AppState>) -> Router {
                 .patch(player::update_player)
                 .delete(player::destroy_player),
         )
+        .route(
+            "/sessions/{session_id}/players/{guild_id}/listen",
+            get(player::listen_player),
+        )
+        .route(
+            "/sessions/{session_id}/players/{guild_id}/recording",
+            post(player::start_recording).delete(player::stop_recording),
+        )
+        .route(
+            "/sessions/{session_id}/players/{guild_id}/sponsorblock/categories",
+            get(player::get_segment_categories)
+                .put(player::set_segment_categories)
+                .delete(player::clear_segment_categories),
+        )
         .route(
             "/sessions/{session_id}",
             get(player::get_session).patch(player::update_session),
         )
+        .route(
+            "/sessions/{session_id}/broadcasts",
+            get(broadcast::get_broadcasts).post(broadcast::create_broadcast),
+        )
+        .route(
+            "/sessions/{session_id}/broadcasts/{broadcast_id}",
+            get(broadcast::get_broadcast).delete(broadcast::delete_broadcast),
+        )
+        .route(
+            "/sessions/{session_id}/broadcasts/{broadcast_id}/players/{guild_id}",
+            put(broadcast::attach_player).delete(broadcast::detach_player),
+        )
+        .route("/artwork/{id}", get(artwork::get_artwork))
         .route("/lyrics", get(lyrics::get_lyrics))
         .route(
             "/sessions/{session_id}/players/{guild_id}/lyrics/subscribe",
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+
+use crate::{
+    common::RustalinkError,
+    protocol::{self, analysis::TrackAnalysis, tracks::Track},
+    server::AppState,
+};
+
+const PATH: &str = "/v4/analyze";
+
+/// POST /v4/analyze
+///
+/// Decodes each track and reports its tempo, key and loudness. A track that
+/// fails to analyse gets an `error` instead of failing the whole request.
+pub async fn analyze_tracks(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<protocol::EncodedTracks>,
+) -> impl IntoResponse {
+    let encoded = body.0;
+    tracing::info!("POST {}: count={}", PATH, encoded.len());
+
+    if encoded.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(RustalinkError::bad_request(
+                "No tracks to analyze provided",
+                PATH,
+            )),
+        )
+            .into_response();
+    }
+
+    let mut tracks = Vec::with_capacity(encoded.len());
+    for e in &encoded {
+        let Some(track) = Track::decode(e) else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(RustalinkError::bad_request(
+                    format!("Invalid track encoding: {}", e),
+                    PATH,
+                )),
+            )
+                .into_response();
+        };
+        tracks.push(track);
+    }
+
+    let results = futures::future::join_all(tracks.iter().map(|track| {
+        state
+            .analysis_manager
+            .analyze(&track.info, state.routeplanner.clone())
+    }))
+    .await;
+
+    let response: Vec<TrackAnalysis> = encoded
+        .into_iter()
+        .zip(results)
+        .map(|(encoded, result)| match result {
+            Ok(analysis) => TrackAnalysis {
+                encoded,
+                analysis: Some(analysis),
+                error: None,
+            },
+            Err(e) => {
+                tracing::warn!("Analysis failed for {}: {}", encoded, e);
+                TrackAnalysis {
+                    encoded,
+                    analysis: None,
+                    error: Some(e),
+                }
+            }
+        })
+        .collect();
+
+    (StatusCode::OK, Json(response)).into_response()
+}
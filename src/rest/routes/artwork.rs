@@ -0,0 +1,37 @@
+use axum::{
+    extract::Path,
+    http::{StatusCode, header},
+    response::{IntoResponse, Json, Response},
+};
+
+use crate::{common::RustalinkError, sources::metadata::artwork_store};
+
+/// GET /v4/artwork/{id}
+///
+/// Serves a cover image embedded in a local or HTTP file. Ids come from the
+/// `artworkUrl` of tracks loaded by those sources.
+pub async fn get_artwork(Path(id): Path<String>) -> Response {
+    let path = format!("/v4/artwork/{id}");
+    tracing::debug!("GET {path}");
+
+    let picture = tokio::task::spawn_blocking(move || artwork_store().get(&id))
+        .await
+        .ok()
+        .flatten();
+
+    match picture {
+        Some(picture) => (
+            [
+                (header::CONTENT_TYPE, picture.media_type),
+                (header::CACHE_CONTROL, "max-age=86400".to_owned()),
+            ],
+            picture.data,
+        )
+            .into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(RustalinkError::not_found("Artwork not found", path)),
+        )
+            .into_response(),
+    }
+}
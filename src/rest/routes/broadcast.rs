@@ -0,0 +1,259 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+
+use super::{ApiError, find_player, find_session};
+use crate::{
+    common::{
+        RustalinkError,
+        types::{BroadcastId, GuildId, SessionId},
+    },
+    player::Broadcast,
+    protocol::{BroadcastCreate, Broadcasts},
+    server::{AppState, Session},
+};
+
+fn find_broadcast(
+    session: &Session,
+    id: &BroadcastId,
+    path: &str,
+) -> Result<Arc<Broadcast>, ApiError> {
+    session
+        .broadcasts
+        .get(id)
+        .map(|b| b.value().clone())
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(RustalinkError::not_found(
+                    format!("Broadcast not found: {}", id),
+                    path,
+                )),
+            )
+        })
+}
+
+/// GET /v4/sessions/{sessionId}/broadcasts
+pub async fn get_broadcasts(
+    Path(session_id): Path<SessionId>,
+    State(state): State<Arc<AppState>>,
+) -> Response {
+    let path = format!("/v4/sessions/{}/broadcasts", session_id);
+    tracing::info!("GET {}", path);
+
+    let session = match find_session(&state, &session_id, &path) {
+        Ok(s) => s,
+        Err(e) => return e.into_response(),
+    };
+
+    let mut broadcasts: Vec<_> = session
+        .broadcasts
+        .iter()
+        .map(|kv| kv.value().to_info())
+        .collect();
+    broadcasts.sort_by(|a, b| a.id.0.cmp(&b.id.0));
+
+    (StatusCode::OK, Json(Broadcasts { broadcasts })).into_response()
+}
+
+/// POST /v4/sessions/{sessionId}/broadcasts
+pub async fn create_broadcast(
+    Path(session_id): Path<SessionId>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<BroadcastCreate>,
+) -> Response {
+    let path = format!("/v4/sessions/{}/broadcasts", session_id);
+    tracing::info!("POST {}: body={:?}", path, body);
+
+    let session = match find_session(&state, &session_id, &path) {
+        Ok(s) => s,
+        Err(e) => return e.into_response(),
+    };
+
+    let player_arc = match find_player(&session, &body.guild_id, &path) {
+        Ok(p) => p,
+        Err(e) => return e.into_response(),
+    };
+    let mut player = player_arc.write().await;
+
+    if let Some(existing) = &player.broadcast {
+        return (
+            StatusCode::CONFLICT,
+            Json(RustalinkError::conflict(
+                format!(
+                    "Player {} is already part of broadcast {}",
+                    body.guild_id, existing
+                ),
+                path,
+            )),
+        )
+            .into_response();
+    }
+
+    let broadcast =
+        match Broadcast::start(BroadcastId::generate(), &player, session.event_sender()).await {
+            Ok(b) => b,
+            Err(e) => {
+                tracing::error!("Broadcast encoder init failed: {e}");
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(RustalinkError::new(
+                        500,
+                        "Internal Server Error",
+                        format!("Failed to start broadcast: {e}"),
+                        path,
+                    )),
+                )
+                    .into_response();
+            }
+        };
+    player.broadcast = Some(broadcast.id.clone());
+    session
+        .broadcasts
+        .insert(broadcast.id.clone(), broadcast.clone());
+
+    (StatusCode::OK, Json(broadcast.to_info())).into_response()
+}
+
+/// GET /v4/sessions/{sessionId}/broadcasts/{broadcastId}
+pub async fn get_broadcast(
+    Path((session_id, broadcast_id)): Path<(SessionId, BroadcastId)>,
+    State(state): State<Arc<AppState>>,
+) -> Response {
+    let path = format!("/v4/sessions/{}/broadcasts/{}", session_id, broadcast_id);
+    tracing::info!("GET {}", path);
+
+    let broadcast = match find_session(&state, &session_id, &path)
+        .and_then(|session| find_broadcast(&session, &broadcast_id, &path))
+    {
+        Ok(b) => b,
+        Err(e) => return e.into_response(),
+    };
+
+    (StatusCode::OK, Json(broadcast.to_info())).into_response()
+}
+
+/// DELETE /v4/sessions/{sessionId}/broadcasts/{broadcastId}
+pub async fn delete_broadcast(
+    Path((session_id, broadcast_id)): Path<(SessionId, BroadcastId)>,
+    State(state): State<Arc<AppState>>,
+) -> Response {
+    let path = format!("/v4/sessions/{}/broadcasts/{}", session_id, broadcast_id);
+    tracing::info!("DELETE {}", path);
+
+    let session = match find_session(&state, &session_id, &path) {
+        Ok(s) => s,
+        Err(e) => return e.into_response(),
+    };
+
+    if !session.end_broadcast(&broadcast_id).await {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(RustalinkError::not_found(
+                format!("Broadcast not found: {}", broadcast_id),
+                path,
+            )),
+        )
+            .into_response();
+    }
+
+    StatusCode::NO_CONTENT.into_response()
+}
+
+/// PUT /v4/sessions/{sessionId}/broadcasts/{broadcastId}/players/{guildId}
+pub async fn attach_player(
+    Path((session_id, broadcast_id, guild_id)): Path<(SessionId, BroadcastId, GuildId)>,
+    State(state): State<Arc<AppState>>,
+) -> Response {
+    let path = format!(
+        "/v4/sessions/{}/broadcasts/{}/players/{}",
+        session_id, broadcast_id, guild_id
+    );
+    tracing::info!("PUT {}", path);
+
+    let session = match find_session(&state, &session_id, &path) {
+        Ok(s) => s,
+        Err(e) => return e.into_response(),
+    };
+    let broadcast = match find_broadcast(&session, &broadcast_id, &path) {
+        Ok(b) => b,
+        Err(e) => return e.into_response(),
+    };
+
+    let player_arc = match find_player(&session, &guild_id, &path) {
+        Ok(p) => p,
+        Err(e) => return e.into_response(),
+    };
+    let mut player = player_arc.write().await;
+
+    let conflict = match &player.broadcast {
+        Some(current) if current != &broadcast_id => Some(format!(
+            "Player {} is already part of broadcast {}",
+            guild_id, current
+        )),
+        _ if broadcast.source_guild_id == guild_id => Some(format!(
+            "Player {} is the source of broadcast {}",
+            guild_id, broadcast_id
+        )),
+        _ if player.track.is_some() => Some(format!(
+            "Player {} is playing a track; stop it before attaching",
+            guild_id
+        )),
+        _ => None,
+    };
+    if let Some(message) = conflict {
+        return (
+            StatusCode::CONFLICT,
+            Json(RustalinkError::conflict(message, path)),
+        )
+            .into_response();
+    }
+
+    let feed = player.engine.lock().await.feed.clone();
+    broadcast.attach(guild_id, feed);
+    player.broadcast = Some(broadcast_id);
+
+    (StatusCode::OK, Json(broadcast.to_info())).into_response()
+}
+
+/// DELETE /v4/sessions/{sessionId}/broadcasts/{broadcastId}/players/{guildId}
+pub async fn detach_player(
+    Path((session_id, broadcast_id, guild_id)): Path<(SessionId, BroadcastId, GuildId)>,
+    State(state): State<Arc<AppState>>,
+) -> Response {
+    let path = format!(
+        "/v4/sessions/{}/broadcasts/{}/players/{}",
+        session_id, broadcast_id, guild_id
+    );
+    tracing::info!("DELETE {}", path);
+
+    let session = match find_session(&state, &session_id, &path) {
+        Ok(s) => s,
+        Err(e) => return e.into_response(),
+    };
+    let broadcast = match find_broadcast(&session, &broadcast_id, &path) {
+        Ok(b) => b,
+        Err(e) => return e.into_response(),
+    };
+
+    if !broadcast.detach(&guild_id) {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(RustalinkError::not_found(
+                format!("Player {} is not attached to this broadcast", guild_id),
+                path,
+            )),
+        )
+            .into_response();
+    }
+
+    if let Some(player_arc) = session.players.get(&guild_id).map(|kv| kv.value().clone()) {
+        player_arc.write().await.broadcast = None;
+    }
+
+    StatusCode::NO_CONTENT.into_response()
+}
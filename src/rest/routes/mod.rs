@@ -1,4 +1,67 @@
+pub mod analysis;
+pub mod artwork;
+pub mod broadcast;
 pub mod lyrics;
 pub mod player;
 pub mod stats;
+pub mod stream;
 pub mod youtube;
+
+use std::sync::Arc;
+
+use axum::{http::StatusCode, response::Json};
+use tokio::sync::RwLock;
+
+use crate::{
+    common::{
+        RustalinkError,
+        types::{GuildId, SessionId},
+    },
+    player::PlayerContext,
+    server::{AppState, Session},
+};
+
+pub(crate) type ApiError = (StatusCode, Json<RustalinkError>);
+
+/// Looks up a session, answering 404 when it is gone.
+pub(crate) fn find_session(
+    state: &AppState,
+    session_id: &SessionId,
+    path: &str,
+) -> Result<Arc<Session>, ApiError> {
+    state
+        .sessions
+        .get(session_id)
+        .map(|s| s.clone())
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(RustalinkError::not_found(
+                    format!("Session not found: {}", session_id),
+                    path,
+                )),
+            )
+        })
+}
+
+/// Looks up a session's player without creating it, answering 404 when
+/// there is none.
+pub(crate) fn find_player(
+    session: &Session,
+    guild_id: &GuildId,
+    path: &str,
+) -> Result<Arc<RwLock<PlayerContext>>, ApiError> {
+    session
+        .players
+        .get(guild_id)
+        .map(|kv| kv.value().clone())
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(RustalinkError::not_found(
+                    format!("Player not found for guild: {}", guild_id),
+                    path,
+                )),
+            )
+        })
+}
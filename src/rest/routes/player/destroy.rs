@@ -45,6 +45,7 @@ pub async fn destroy_player(
                     guild_id: guild_id.clone(),
                     track: track_data,
                     reason: protocol::TrackEndReason::Cleanup,
+                    trimmed: None,
                 }),
             };
             session.send_message(&end_event);
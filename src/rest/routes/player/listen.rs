@@ -0,0 +1,73 @@
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use bytes::Bytes;
+use futures::StreamExt;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    audio::mux::OggOpusWriter,
+    rest::routes::{find_player, find_session},
+    server::AppState,
+};
+
+/// GET /v4/sessions/{sessionId}/players/{guildId}/listen
+///
+/// Streams the frames the player sends to Discord as Ogg/Opus. Listeners only
+/// receive copies, so they never slow down or alter the voice connection.
+pub async fn listen_player(
+    Path((session_id, guild_id)): Path<(
+        crate::common::types::SessionId,
+        crate::common::types::GuildId,
+    )>,
+    State(state): State<Arc<AppState>>,
+) -> Response {
+    let path = format!("/v4/sessions/{}/players/{}/listen", session_id, guild_id);
+    tracing::info!("GET {}", path);
+
+    let arc = match find_session(&state, &session_id, &path)
+        .and_then(|session| find_player(&session, &guild_id, &path))
+    {
+        Ok(p) => p,
+        Err(e) => return e.into_response(),
+    };
+
+    let rx = {
+        let engine = arc.read().await.engine.clone();
+        engine.lock().await.monitor.subscribe()
+    };
+
+    let mut writer = OggOpusWriter::new(rand::random());
+    let headers = Bytes::from(writer.headers());
+
+    let pages = futures::stream::unfold((rx, writer), |(mut rx, mut writer)| async move {
+        loop {
+            match rx.recv().await {
+                Ok(packet) => {
+                    let page = Bytes::from(writer.packet(&packet));
+                    return Some((page, (rx, writer)));
+                }
+                Err(RecvError::Lagged(missed)) => writer.skip(missed),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    let body = futures::stream::once(async move { headers })
+        .chain(pages)
+        .map(Ok::<_, Infallible>);
+
+    (
+        [
+            (header::CONTENT_TYPE, "audio/ogg"),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        Body::from_stream(body),
+    )
+        .into_response()
+}
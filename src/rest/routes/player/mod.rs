@@ -1,7 +1,13 @@
 pub mod destroy;
 pub mod get;
+pub mod listen;
+pub mod recording;
+pub mod segments;
 pub mod update;
 
 pub use destroy::destroy_player;
 pub use get::{get_player, get_players, get_session};
+pub use listen::listen_player;
+pub use recording::{start_recording, stop_recording};
+pub use segments::{clear_segment_categories, get_segment_categories, set_segment_categories};
 pub use update::{update_player, update_session};
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+
+use crate::{
+    player::Recording,
+    protocol::{RecordingEndReason, RecordingStart},
+    rest::routes::{find_player, find_session},
+    server::AppState,
+};
+
+/// POST /v4/sessions/{sessionId}/players/{guildId}/recording
+pub async fn start_recording(
+    Path((session_id, guild_id)): Path<(
+        crate::common::types::SessionId,
+        crate::common::types::GuildId,
+    )>,
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<RecordingStart>>,
+) -> impl IntoResponse {
+    let path = format!("/v4/sessions/{}/players/{}/recording", session_id, guild_id);
+    tracing::info!("POST {}", path);
+
+    if !state.config.recording.enabled {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(crate::common::RustalinkError::bad_request(
+                "Recording is disabled in the config",
+                path,
+            )),
+        )
+            .into_response();
+    }
+
+    let session = match find_session(&state, &session_id, &path) {
+        Ok(s) => s,
+        Err(e) => return e.into_response(),
+    };
+    let player_arc = match find_player(&session, &guild_id, &path) {
+        Ok(p) => p,
+        Err(e) => return e.into_response(),
+    };
+
+    let conflict = || {
+        (
+            StatusCode::CONFLICT,
+            Json(crate::common::RustalinkError::conflict(
+                "Player is already being recorded",
+                path.clone(),
+            )),
+        )
+            .into_response()
+    };
+
+    let monitor = {
+        let player = player_arc.read().await;
+        if player.recording.as_ref().is_some_and(|r| !r.is_finished()) {
+            return conflict();
+        }
+        player.engine.lock().await.monitor.clone()
+    };
+    let request = body.map(|Json(b)| b).unwrap_or_default();
+
+    // Creating the file happens without the player lock held.
+    let recording = match Recording::start(
+        &state.config.recording,
+        guild_id,
+        &monitor,
+        request,
+        session.clone(),
+    )
+    .await
+    {
+        Ok(recording) => recording,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(crate::common::RustalinkError::new(
+                    500,
+                    "Internal Server Error",
+                    format!("Failed to start recording: {}", e),
+                    path.clone(),
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    let mut player = player_arc.write().await;
+    if player.recording.as_ref().is_some_and(|r| !r.is_finished()) {
+        // Another request started one while this file was being created.
+        drop(player);
+        recording.stop(RecordingEndReason::Cleanup).await;
+        return conflict();
+    }
+    let info = recording.info.clone();
+    player.recording = Some(recording);
+    (StatusCode::OK, Json(info)).into_response()
+}
+
+/// DELETE /v4/sessions/{sessionId}/players/{guildId}/recording
+pub async fn stop_recording(
+    Path((session_id, guild_id)): Path<(
+        crate::common::types::SessionId,
+        crate::common::types::GuildId,
+    )>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let path = format!("/v4/sessions/{}/players/{}/recording", session_id, guild_id);
+    tracing::info!("DELETE {}", path);
+
+    let player_arc = state
+        .sessions
+        .get(&session_id)
+        .and_then(|s| s.players.get(&guild_id).map(|kv| kv.value().clone()));
+
+    let recording = match player_arc {
+        Some(arc) => arc.write().await.recording.take(),
+        None => None,
+    };
+
+    let Some(recording) = recording.filter(|r| !r.is_finished()) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(crate::common::RustalinkError::not_found(
+                "No recording in progress",
+                path,
+            )),
+        )
+            .into_response();
+    };
+
+    recording.stop(RecordingEndReason::Stopped).await;
+    StatusCode::NO_CONTENT.into_response()
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+
+use crate::{
+    common::types::{GuildId, SessionId},
+    rest::routes::{find_player, find_session},
+    server::AppState,
+};
+
+/// GET /v4/sessions/{sessionId}/players/{guildId}/sponsorblock/categories
+pub async fn get_segment_categories(
+    Path((session_id, guild_id)): Path<(SessionId, GuildId)>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let path = format!(
+        "/v4/sessions/{}/players/{}/sponsorblock/categories",
+        session_id, guild_id
+    );
+    tracing::info!("GET {}", path);
+
+    match find_session(&state, &session_id, &path)
+        .and_then(|session| find_player(&session, &guild_id, &path))
+    {
+        Ok(player) => {
+            let categories = player
+                .read()
+                .await
+                .segment_categories
+                .clone()
+                .unwrap_or_else(|| state.segment_manager.default_categories.clone());
+            (StatusCode::OK, Json(categories)).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// PUT /v4/sessions/{sessionId}/players/{guildId}/sponsorblock/categories
+///
+/// Takes effect from the next track.
+pub async fn set_segment_categories(
+    Path((session_id, guild_id)): Path<(SessionId, GuildId)>,
+    State(state): State<Arc<AppState>>,
+    Json(categories): Json<Vec<String>>,
+) -> impl IntoResponse {
+    let path = format!(
+        "/v4/sessions/{}/players/{}/sponsorblock/categories",
+        session_id, guild_id
+    );
+    tracing::info!("PUT {}: {:?}", path, categories);
+
+    match find_session(&state, &session_id, &path)
+        .and_then(|session| find_player(&session, &guild_id, &path))
+    {
+        Ok(player) => {
+            player.write().await.segment_categories = Some(categories);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// DELETE /v4/sessions/{sessionId}/players/{guildId}/sponsorblock/categories
+///
+/// Turns segment skipping off for the player, config defaults included.
+pub async fn clear_segment_categories(
+    Path((session_id, guild_id)): Path<(SessionId, GuildId)>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let path = format!(
+        "/v4/sessions/{}/players/{}/sponsorblock/categories",
+        session_id, guild_id
+    );
+    tracing::info!("DELETE {}", path);
+
+    match find_session(&state, &session_id, &path)
+        .and_then(|session| find_player(&session, &guild_id, &path))
+    {
+        Ok(player) => {
+            player.write().await.segment_categories = Some(Vec::new());
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
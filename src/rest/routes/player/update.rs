@@ -19,7 +19,7 @@ pub async fn update_player(
     )>,
     Query(params): Query<std::collections::HashMap<String, String>>,
     State(state): State<Arc<AppState>>,
-    Json(body): Json<PlayerUpdate>,
+    Json(mut body): Json<PlayerUpdate>,
 ) -> impl IntoResponse {
     tracing::info!(
         "PATCH /v4/sessions/{}/players/{}: body={:?}",
@@ -49,7 +49,75 @@ pub async fn update_player(
     let loading_new_track =
         body.track.is_some() || body.encoded_track.is_some() || body.identifier.is_some();
 
-    handle_player_state(&mut player, &body, loading_new_track, &guild_id, &session);
+    // Listeners play the broadcast's frames, so a track of their own would never be heard.
+    if loading_new_track
+        && let Some(id) = &player.broadcast
+        && session
+            .broadcasts
+            .get(id)
+            .is_some_and(|b| b.is_listening(&guild_id))
+    {
+        return (
+            StatusCode::CONFLICT,
+            Json(crate::common::RustalinkError::conflict(
+                format!("Player is attached to broadcast {}; detach it first", id),
+                format!("/v4/sessions/{}/players/{}", session_id, guild_id),
+            )),
+        )
+            .into_response();
+    }
+
+    if let Some(index) = body.chapter
+        && !loading_new_track
+    {
+        let start = player
+            .chapters
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|chapters| chapters.get(index))
+            .map(|c| c.start);
+        let Some(start) = start else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(crate::common::RustalinkError::bad_request(
+                    format!("The current track has no chapter {}", index),
+                    format!("/v4/sessions/{}/players/{}", session_id, guild_id),
+                )),
+            )
+                .into_response();
+        };
+        body.position = Some(start);
+    }
+
+    if let Some(config) = body.config {
+        if let Some(encoder) = config.encoder {
+            player.set_encoder(encoder).await;
+        }
+        if let Some(silence) = config.silence {
+            silence.apply(&mut player.config.silence);
+        }
+        if let Some(meter) = config.meter {
+            player.set_meter(meter).await;
+        }
+        if let Some(ducking) = config.ducking {
+            player.set_ducking(ducking).await;
+        }
+        if let Some(fade) = config.fade {
+            player.set_fade(fade).await;
+        }
+    }
+
+    // Fades wait on the mixer, so they run without holding the player lock.
+    if let Some(pos) = body.position
+        && !loading_new_track
+    {
+        drop(player);
+        PlayerContext::seek_faded(&player_arc, pos).await;
+        player = player_arc.write().await;
+    }
+
+    handle_player_state(&mut player, &body, loading_new_track, &guild_id, &session).await;
 
     if let Some(filters) = body.filters.clone()
         && let Err(e) = handle_filters(&mut player, filters, &state, &guild_id, &session).await
@@ -64,6 +132,12 @@ pub async fn update_player(
     }
 
     if let Some(track_update) = resolve_track_update(&body) {
+        if ends_current_track(&track_update, no_replace) {
+            drop(player);
+            PlayerContext::fade_out(&player_arc).await;
+            player = player_arc.write().await;
+        }
+
         let start_time_ms = if loading_new_track {
             body.position
         } else {
@@ -92,7 +166,7 @@ pub async fn update_player(
     (StatusCode::OK, Json(response)).into_response()
 }
 
-fn handle_player_state(
+async fn handle_player_state(
     player: &mut PlayerContext,
     body: &PlayerUpdate,
     loading_new_track: bool,
@@ -100,23 +174,21 @@ fn handle_player_state(
     session: &Arc<crate::server::Session>,
 ) {
     if !loading_new_track {
-        if let Some(pos) = body.position {
-            player.seek(pos);
-            if player.track.is_some() {
-                let seek_update = protocol::OutgoingMessage::PlayerUpdate {
-                    guild_id: guild_id.clone(),
-                    state: crate::player::PlayerState {
-                        time: crate::common::utils::now_ms(),
-                        position: pos,
-                        connected: !player.voice.token.is_empty(),
-                        ping: player.ping.load(std::sync::atomic::Ordering::Relaxed),
-                    },
-                };
-                let session_clone = session.clone();
-                tokio::spawn(async move {
-                    session_clone.send_message(&seek_update);
-                });
-            }
+        if body.position.is_some() && player.track.is_some() {
+            let seek_update = protocol::OutgoingMessage::PlayerUpdate {
+                guild_id: guild_id.clone(),
+                state: crate::player::PlayerState {
+                    time: crate::common::utils::now_ms(),
+                    position: player.position,
+                    connected: !player.voice.token.is_empty(),
+                    ping: player.ping.load(std::sync::atomic::Ordering::Relaxed),
+                    live_latency: player.track_handle.as_ref().and_then(|h| h.live_latency()),
+                },
+            };
+            let session_clone = session.clone();
+            tokio::spawn(async move {
+                session_clone.send_message(&seek_update);
+            });
         }
         if let Some(paused) = body.paused {
             player.set_paused(paused);
@@ -168,6 +240,7 @@ async fn handle_filters(
                 .unwrap_or(player.position),
             connected: !player.voice.token.is_empty(),
             ping: player.ping.load(std::sync::atomic::Ordering::Relaxed),
+            live_latency: player.track_handle.as_ref().and_then(|h| h.live_latency()),
         },
     });
 
@@ -198,24 +271,16 @@ async fn handle_voice(
     };
 
     if let Some(uid) = session.user_id {
-        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
-        let session_clone = session.clone();
-        tokio::spawn(async move {
-            while let Some(event) = event_rx.recv().await {
-                session_clone.send_message(&protocol::OutgoingMessage::Event {
-                    event: Box::new(event),
-                });
-            }
-        });
-
         let handle = crate::server::connect_voice(crate::server::voice::VoiceConnectConfig {
             engine: player.engine.clone(),
             guild_id: player.guild_id.clone(),
             user_id: uid,
             voice: player.voice.clone(),
             filter_chain: player.filter_chain.clone(),
+            encoder_config: player.encoder_config.clone(),
+            meter_config: player.meter_config.clone(),
             ping: player.ping.clone(),
-            event_tx: Some(event_tx),
+            event_tx: Some(session.event_sender()),
             frames_sent: player.frames_sent.clone(),
             frames_nulled: player.frames_nulled.clone(),
         })
@@ -230,6 +295,15 @@ async fn handle_voice(
     Ok(())
 }
 
+/// Whether applying `update` stops or replaces the playing track.
+fn ends_current_track(update: &crate::player::PlayerUpdateTrack, no_replace: bool) -> bool {
+    match &update.encoded {
+        Some(crate::player::state::TrackEncoded::Clear) => true,
+        Some(crate::player::state::TrackEncoded::Set(_)) => !no_replace,
+        None => update.identifier.is_some() && !no_replace,
+    }
+}
+
 fn resolve_track_update(body: &PlayerUpdate) -> Option<crate::player::PlayerUpdateTrack> {
     if let Some(t) = &body.track {
         Some(t.clone())
@@ -326,6 +400,7 @@ async fn stop_player(player: &mut PlayerContext, session: &Arc<crate::server::Se
                     user_data: serde_json::json!({}),
                 },
                 reason: protocol::TrackEndReason::Stopped,
+                trimmed: None,
             }),
         });
     }
@@ -4,4 +4,4 @@ pub mod track;
 
 pub use info::{get_info, get_stats, get_version};
 pub use routeplanner::{routeplanner_free_address, routeplanner_free_all, routeplanner_status};
-pub use track::{decode_track, decode_tracks, load_search, load_tracks};
+pub use track::{decode_track, decode_tracks, get_recommendations, load_search, load_tracks};
@@ -126,3 +126,53 @@ pub async fn decode_tracks(Json(body): Json<protocol::EncodedTracks>) -> impl In
 
     (StatusCode::OK, Json(tracks)).into_response()
 }
+
+/// GET /v4/recommendations?encodedTrack=...&limit=...&history=...
+pub async fn get_recommendations(
+    Query(params): Query<RecommendationsQuery>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    const PATH: &str = "/v4/recommendations";
+    tracing::info!("GET {}: limit={:?}", PATH, params.limit);
+
+    let Some(seed) = Track::decode(&params.encoded_track) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(crate::common::RustalinkError::bad_request(
+                "Invalid track encoding",
+                PATH,
+            )),
+        )
+            .into_response();
+    };
+
+    let mut history = Vec::new();
+    for encoded in params
+        .history
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let Some(track) = Track::decode(encoded) else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(crate::common::RustalinkError::bad_request(
+                    format!("Invalid track encoding in history: {}", encoded),
+                    PATH,
+                )),
+            )
+                .into_response();
+        };
+        history.push(track.info);
+    }
+
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let tracks = state
+        .source_manager
+        .recommendations(&seed.info, &history, limit, state.routeplanner.clone())
+        .await;
+
+    (StatusCode::OK, Json(tracks)).into_response()
+}
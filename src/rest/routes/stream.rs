@@ -0,0 +1,306 @@
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Json, Response},
+};
+use bytes::Bytes;
+use futures::StreamExt;
+
+use crate::{
+    audio::{
+        AudioFrame,
+        constants::{FRAME_SIZE_SAMPLES, MAX_OPUS_FRAME_SIZE},
+        create_client,
+        engine::Encoder,
+        mux::OggOpusWriter,
+        processor::DecoderCommand,
+    },
+    common::RustalinkError,
+    protocol::{
+        models::{StreamFormat, StreamTrackQuery},
+        tracks::Track,
+    },
+    server::AppState,
+    sources::plugin::{DirectStream, PlayableTrack},
+};
+
+const PATH: &str = "/v4/stream";
+
+/// GET /v4/stream?encodedTrack=...&format=ogg|original&position=...
+///
+/// Resolves the track the same way a player would, mirrors included, and
+/// hands the audio back over HTTP instead of sending it to Discord.
+pub async fn stream_track(
+    Query(params): Query<StreamTrackQuery>,
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Response {
+    tracing::info!(
+        "GET {}: format={:?} position={:?}",
+        PATH,
+        params.format,
+        params.position
+    );
+
+    let Some(track) = Track::decode(&params.encoded_track) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(RustalinkError::bad_request("Invalid track encoding", PATH)),
+        )
+            .into_response();
+    };
+
+    let playable = match state
+        .source_manager
+        .resolve_track(&track.info, state.routeplanner.clone())
+        .await
+    {
+        Ok(playable) => playable,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(RustalinkError::new(
+                    500,
+                    "Internal Server Error",
+                    format!("Failed to resolve track: {}", e),
+                    PATH,
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    match params.format {
+        StreamFormat::Ogg => stream_ogg(&state, playable.as_ref(), params.position).await,
+        StreamFormat::Original => match playable.direct_stream() {
+            Some(direct) => stream_original(direct, &headers).await,
+            None => (
+                StatusCode::BAD_REQUEST,
+                Json(RustalinkError::bad_request(
+                    format!(
+                        "Source '{}' has no original file to proxy, use format=ogg",
+                        track.info.source_name
+                    ),
+                    PATH,
+                )),
+            )
+                .into_response(),
+        },
+    }
+}
+
+async fn stream_ogg(
+    state: &AppState,
+    playable: &dyn PlayableTrack,
+    position: Option<u64>,
+) -> Response {
+    let encoder = match Encoder::new() {
+        Ok(encoder) => encoder,
+        Err(e) => return internal_error(format!("Failed to create encoder: {}", e)),
+    };
+
+    let (frame_rx, cmd_tx, err_rx) = playable.start_decoding(state.config.player.clone());
+    if let Some(ms) = position.filter(|&ms| ms > 0) {
+        let _ = cmd_tx.send(DecoderCommand::Seek(ms));
+    }
+
+    // Wait for audio before committing to a 200 so load failures still get a
+    // proper error response.
+    let first = tokio::select! {
+        frame = frame_rx.recv_async() => frame.ok(),
+        Ok(e) = err_rx.recv_async() => return internal_error(e),
+    };
+    let Some(first) = first else {
+        return internal_error("Track ended before producing any audio".to_owned());
+    };
+
+    let mut stream = OggStream {
+        first: Some(first),
+        frames: frame_rx,
+        _cmd_tx: cmd_tx,
+        encoder,
+        writer: OggOpusWriter::new(rand::random()),
+        pending: Vec::with_capacity(FRAME_SIZE_SAMPLES * 2),
+        opus: vec![0u8; MAX_OPUS_FRAME_SIZE],
+        seeking: position.is_some_and(|ms| ms > 0),
+        finished: false,
+    };
+    let headers = Bytes::from(stream.writer.headers());
+
+    let pages = futures::stream::unfold(stream, |mut stream| async move {
+        let chunk = stream.next().await?;
+        Some((chunk, stream))
+    });
+
+    let body = futures::stream::once(async move { headers })
+        .chain(pages)
+        .map(Ok::<_, Infallible>);
+
+    (
+        [
+            (header::CONTENT_TYPE, "audio/ogg"),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        Body::from_stream(body),
+    )
+        .into_response()
+}
+
+async fn stream_original(direct: DirectStream, headers: &HeaderMap) -> Response {
+    let client = match create_client(
+        crate::common::utils::default_user_agent(),
+        direct.local_addr,
+        direct.proxy,
+        None,
+    ) {
+        Ok(client) => client,
+        Err(e) => return internal_error(format!("Failed to create HTTP client: {}", e)),
+    };
+
+    let mut request = client.get(&direct.url);
+    if let Some(range) = headers.get(header::RANGE) {
+        request = request.header(header::RANGE, range.clone());
+    }
+
+    let upstream = match request.send().await {
+        Ok(r) if r.status().is_success() => r,
+        Ok(r) => return bad_gateway(format!("Upstream returned {}", r.status())),
+        Err(e) => return bad_gateway(format!("Upstream request failed: {}", e)),
+    };
+
+    let mut resp_headers = HeaderMap::new();
+    for name in [
+        header::CONTENT_TYPE,
+        header::CONTENT_LENGTH,
+        header::CONTENT_RANGE,
+        header::ACCEPT_RANGES,
+    ] {
+        if let Some(v) = upstream.headers().get(&name) {
+            resp_headers.insert(name, v.clone());
+        }
+    }
+
+    (
+        upstream.status(),
+        resp_headers,
+        Body::from_stream(upstream.bytes_stream()),
+    )
+        .into_response()
+}
+
+/// Re-encodes decoder output into Ogg pages. Dropping it drops the command
+/// channel, which stops the decoder when the client disconnects.
+struct OggStream {
+    first: Option<AudioFrame>,
+    frames: flume::Receiver<AudioFrame>,
+    _cmd_tx: flume::Sender<DecoderCommand>,
+    encoder: Encoder,
+    writer: OggOpusWriter,
+    pending: Vec<i16>,
+    opus: Vec<u8>,
+    /// Set until the decoder confirms the requested seek.
+    seeking: bool,
+    finished: bool,
+}
+
+impl OggStream {
+    async fn next(&mut self) -> Option<Bytes> {
+        if self.finished {
+            return None;
+        }
+
+        loop {
+            let frame = match self.first.take() {
+                Some(frame) => Ok(frame),
+                None => self.frames.recv_async().await,
+            };
+
+            match frame {
+                Ok(AudioFrame::Seeked(_) | AudioFrame::SeekFailed) => {
+                    self.seeking = false;
+                }
+                Ok(AudioFrame::Pcm(pcm)) => {
+                    if self.seeking {
+                        continue;
+                    }
+                    self.pending.extend_from_slice(&pcm);
+                    let pages = self.encode_pending();
+                    if !pages.is_empty() {
+                        return Some(pages.into());
+                    }
+                }
+                Ok(AudioFrame::Passthrough { pcm, packet }) => {
+                    if self.seeking {
+                        continue;
+                    }
+                    // A packet on a frame boundary is written as it came.
+                    if self.pending.is_empty() && pcm.len() == FRAME_SIZE_SAMPLES {
+                        return Some(self.writer.packet(&packet).into());
+                    }
+                    self.pending.extend_from_slice(&pcm);
+                    let pages = self.encode_pending();
+                    if !pages.is_empty() {
+                        return Some(pages.into());
+                    }
+                }
+                Ok(AudioFrame::Opus(packet)) => {
+                    if !self.seeking {
+                        return Some(self.writer.packet(&packet).into());
+                    }
+                }
+                Err(_) => {
+                    self.finished = true;
+                    if !self.pending.is_empty() {
+                        let len = self.pending.len().next_multiple_of(FRAME_SIZE_SAMPLES);
+                        self.pending.resize(len, 0);
+                    }
+                    let mut pages = self.encode_pending();
+                    pages.extend(self.writer.finish());
+                    return Some(pages.into());
+                }
+            }
+        }
+    }
+
+    fn encode_pending(&mut self) -> Vec<u8> {
+        let mut pages = Vec::new();
+        while self.pending.len() >= FRAME_SIZE_SAMPLES {
+            match self
+                .encoder
+                .encode(&self.pending[..FRAME_SIZE_SAMPLES], &mut self.opus)
+            {
+                Ok(size) => pages.extend(self.writer.packet(&self.opus[..size])),
+                Err(e) => {
+                    tracing::warn!("Stream encode failed: {}", e);
+                    self.writer.skip(1);
+                }
+            }
+            self.pending.drain(..FRAME_SIZE_SAMPLES);
+        }
+        pages
+    }
+}
+
+fn internal_error(message: String) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(RustalinkError::new(
+            500,
+            "Internal Server Error",
+            message,
+            PATH,
+        )),
+    )
+        .into_response()
+}
+
+fn bad_gateway(message: String) -> Response {
+    (
+        StatusCode::BAD_GATEWAY,
+        Json(RustalinkError::new(502, "Bad Gateway", message, PATH)),
+    )
+        .into_response()
+}
@@ -0,0 +1,89 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+
+use crate::{
+    common::types::AnyResult,
+    config::AppConfig,
+    protocol::{segments::Segment, tracks::TrackInfo},
+};
+
+pub mod sponsorblock;
+
+use sponsorblock::SponsorBlockProvider;
+
+/// Sources whose track identifiers are video IDs a provider understands.
+const SEGMENT_SOURCES: &[&str] = &["youtube"];
+
+#[async_trait]
+pub trait SegmentProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn load_segments(&self, video_id: &str, categories: &[String])
+    -> AnyResult<Vec<Segment>>;
+}
+
+pub struct SegmentManager {
+    pub provider: Option<Arc<dyn SegmentProvider>>,
+    pub default_categories: Vec<String>,
+    timeout: Duration,
+}
+
+impl SegmentManager {
+    pub fn new(config: &AppConfig) -> Self {
+        let segments = &config.segments;
+        let provider = segments.enabled.then(|| {
+            tracing::info!(
+                "Loaded segment provider: SponsorBlock ({})",
+                segments.provider_url
+            );
+            Arc::new(SponsorBlockProvider::new(&segments.provider_url)) as Arc<dyn SegmentProvider>
+        });
+
+        Self {
+            provider,
+            default_categories: segments.categories.clone(),
+            timeout: Duration::from_millis(segments.timeout_ms),
+        }
+    }
+
+    /// Segments to skip in `track`, sorted by start. Failures only log, so a
+    /// slow or missing provider never affects playback.
+    pub async fn load_segments(&self, track: &TrackInfo, categories: &[String]) -> Vec<Segment> {
+        let Some(provider) = &self.provider else {
+            return Vec::new();
+        };
+        if categories.is_empty() || !SEGMENT_SOURCES.contains(&track.source_name.as_str()) {
+            return Vec::new();
+        }
+
+        let mut segments = match tokio::time::timeout(
+            self.timeout,
+            provider.load_segments(&track.identifier, categories),
+        )
+        .await
+        {
+            Ok(Ok(segments)) => segments,
+            Ok(Err(e)) => {
+                tracing::warn!(
+                    "{} segment lookup failed for {}: {}",
+                    provider.name(),
+                    track.identifier,
+                    e
+                );
+                return Vec::new();
+            }
+            Err(_) => {
+                tracing::warn!(
+                    "{} segment lookup timed out for {}",
+                    provider.name(),
+                    track.identifier
+                );
+                return Vec::new();
+            }
+        };
+
+        segments.retain(|s| s.end > s.start);
+        segments.sort_by_key(|s| s.start);
+        segments
+    }
+}
@@ -0,0 +1,105 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use super::SegmentProvider;
+use crate::{common::types::AnyResult, protocol::segments::Segment};
+
+/// Client for the SponsorBlock `skipSegments` API, or any server that speaks it.
+pub struct SponsorBlockProvider {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SkipSegment {
+    category: String,
+    /// Start and end in seconds.
+    segment: [f64; 2],
+    #[serde(default)]
+    action_type: Option<String>,
+}
+
+impl SponsorBlockProvider {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+        }
+    }
+}
+
+#[async_trait]
+impl SegmentProvider for SponsorBlockProvider {
+    fn name(&self) -> &'static str {
+        "SponsorBlock"
+    }
+
+    async fn load_segments(
+        &self,
+        video_id: &str,
+        categories: &[String],
+    ) -> AnyResult<Vec<Segment>> {
+        let url = format!("{}/api/skipSegments", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .query(&[
+                ("videoID", video_id.to_owned()),
+                ("categories", serde_json::to_string(categories)?),
+            ])
+            .send()
+            .await?;
+
+        // SponsorBlock answers 404 when a video has no segments.
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+
+        let segments: Vec<SkipSegment> = resp.error_for_status()?.json().await?;
+        Ok(to_segments(segments))
+    }
+}
+
+fn to_segments(segments: Vec<SkipSegment>) -> Vec<Segment> {
+    segments
+        .into_iter()
+        // Other action types (mute, chapter, full video) aren't meant to be skipped.
+        .filter(|s| s.action_type.as_deref().is_none_or(|a| a == "skip"))
+        .map(|s| Segment {
+            category: s.category,
+            start: (s.segment[0] * 1000.0) as u64,
+            end: (s.segment[1] * 1000.0) as u64,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_skip_segments() {
+        let body = r#"[
+            {"category":"music_offtopic","actionType":"skip","segment":[0,12.5],"UUID":"a","videoDuration":200},
+            {"category":"sponsor","actionType":"mute","segment":[30,40],"UUID":"b","videoDuration":200},
+            {"category":"outro","segment":[190.25,200],"UUID":"c"}
+        ]"#;
+        let segments = to_segments(serde_json::from_str(body).unwrap());
+        assert_eq!(
+            segments,
+            vec![
+                Segment {
+                    category: "music_offtopic".into(),
+                    start: 0,
+                    end: 12_500
+                },
+                Segment {
+                    category: "outro".into(),
+                    start: 190_250,
+                    end: 200_000
+                },
+            ]
+        );
+    }
+}
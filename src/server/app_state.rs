@@ -20,6 +20,8 @@ pub struct AppState {
     pub routeplanner: Option<Arc<dyn RoutePlanner>>,
     pub source_manager: Arc<SourceManager>,
     pub lyrics_manager: Arc<crate::lyrics::LyricsManager>,
+    pub segment_manager: Arc<crate::segments::SegmentManager>,
+    pub analysis_manager: Arc<crate::analysis::AnalysisManager>,
     pub config: crate::config::AppConfig,
     pub youtube: Option<Arc<YoutubeStreamContext>>,
     pub system_state: parking_lot::Mutex<System>,
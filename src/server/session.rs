@@ -9,11 +9,11 @@ use std::{
 use axum::extract::ws::Message;
 use dashmap::DashMap;
 use parking_lot::Mutex;
-use tokio::task::AbortHandle;
+use tokio::{sync::mpsc::UnboundedSender, task::AbortHandle};
 
 use crate::{
-    common::types::{GuildId, SessionId, UserId},
-    player::PlayerContext,
+    common::types::{BroadcastId, GuildId, SessionId, UserId},
+    player::{Broadcast, PlayerContext},
     protocol,
     server::AppState,
 };
@@ -24,6 +24,7 @@ pub struct Session {
     pub session_id: SessionId,
     pub user_id: Option<UserId>,
     pub players: PlayerMap,
+    pub broadcasts: DashMap<BroadcastId, Arc<Broadcast>>,
     pub sender: parking_lot::RwLock<flume::Sender<Message>>,
     pub resumable: AtomicBool,
     pub resume_timeout: AtomicU64,
@@ -53,6 +54,7 @@ impl Session {
             session_id,
             user_id,
             players: DashMap::new(),
+            broadcasts: DashMap::new(),
             sender: parking_lot::RwLock::new(sender),
             resumable: AtomicBool::new(false),
             resume_timeout: AtomicU64::new(60),
@@ -94,6 +96,19 @@ impl Session {
     }
 
     pub async fn destroy_player(&self, guild_id: &GuildId) -> bool {
+        let sourced: Vec<BroadcastId> = self
+            .broadcasts
+            .iter()
+            .filter(|kv| &kv.value().source_guild_id == guild_id)
+            .map(|kv| kv.key().clone())
+            .collect();
+        for id in sourced {
+            self.end_broadcast(&id).await;
+        }
+        for kv in self.broadcasts.iter() {
+            kv.value().detach(guild_id);
+        }
+
         if let Some((_, player_arc)) = self.players.remove(guild_id) {
             let mut player = player_arc.write().await;
             player.destroy().await;
@@ -103,6 +118,28 @@ impl Session {
         }
     }
 
+    /// Stops a broadcast and returns its source and listeners to normal playback.
+    pub async fn end_broadcast(&self, id: &BroadcastId) -> bool {
+        let Some((_, broadcast)) = self.broadcasts.remove(id) else {
+            return false;
+        };
+
+        let mut guilds = broadcast.listeners();
+        guilds.push(broadcast.source_guild_id.clone());
+        broadcast.stop();
+
+        for guild in guilds {
+            let Some(player_arc) = self.players.get(&guild).map(|kv| kv.value().clone()) else {
+                continue;
+            };
+            let mut player = player_arc.write().await;
+            if player.broadcast.as_ref() == Some(id) {
+                player.broadcast = None;
+            }
+        }
+        true
+    }
+
     pub fn send_json(&self, json: impl Into<String>) {
         if self.paused.load(Ordering::Relaxed) {
             let mut queue = self.event_queue.lock();
@@ -122,6 +159,37 @@ impl Session {
         }
     }
 
+    /// A channel whose events are sent on to this session's client.
+    pub fn event_sender(self: &Arc<Self>) -> UnboundedSender<protocol::RustalinkEvent> {
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+        let session = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = event_rx.recv().await {
+                session.send_message(&protocol::OutgoingMessage::Event {
+                    event: Box::new(event),
+                });
+            }
+        });
+        event_tx
+    }
+
+    /// Finalizes every recording in this session without touching playback.
+    pub async fn stop_recordings(&self) {
+        let players: Vec<_> = self.players.iter().map(|kv| kv.value().clone()).collect();
+        let mut recordings = Vec::new();
+        for player_arc in players {
+            if let Some(recording) = player_arc.write().await.recording.take() {
+                recordings.push(recording);
+            }
+        }
+        futures::future::join_all(
+            recordings
+                .into_iter()
+                .map(|recording| recording.stop(protocol::RecordingEndReason::Cleanup)),
+        )
+        .await;
+    }
+
     pub async fn shutdown(&self) {
         tracing::info!("Shutting down session: {}", self.session_id);
         self.stop_all_players();
@@ -132,6 +200,10 @@ impl Session {
     }
 
     fn stop_all_players(&self) {
+        for kv in self.broadcasts.iter() {
+            kv.value().stop();
+        }
+        self.broadcasts.clear();
         for handle in self.task_handles.lock().drain(..) {
             handle.abort();
         }
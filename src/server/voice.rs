@@ -8,6 +8,7 @@ use tracing::error;
 use crate::{
     audio::filters::FilterChain,
     common::types::{ChannelId, GuildId, Shared, UserId},
+    config::player::{EncoderConfig, MeterConfig},
     gateway::{VoiceEngine, VoiceGateway},
     player::VoiceConnectionState,
     protocol::RustalinkEvent,
@@ -19,6 +20,8 @@ pub struct VoiceConnectConfig {
     pub user_id: UserId,
     pub voice: VoiceConnectionState,
     pub filter_chain: Shared<FilterChain>,
+    pub encoder_config: Shared<EncoderConfig>,
+    pub meter_config: Shared<MeterConfig>,
     pub ping: Arc<AtomicI64>,
     pub event_tx: Option<tokio::sync::mpsc::UnboundedSender<RustalinkEvent>>,
     pub frames_sent: Arc<AtomicU64>,
@@ -38,7 +41,14 @@ pub async fn connect_voice(config: VoiceConnectConfig) -> tokio::task::JoinHandl
         return tokio::spawn(async {});
     };
 
-    let mixer = config.engine.lock().await.mixer.clone();
+    let (mixer, feed, monitor) = {
+        let engine = config.engine.lock().await;
+        (
+            engine.mixer.clone(),
+            engine.feed.clone(),
+            engine.monitor.clone(),
+        )
+    };
 
     let gateway = VoiceGateway::new(crate::gateway::session::VoiceGatewayConfig {
         guild_id: config.guild_id,
@@ -48,7 +58,11 @@ pub async fn connect_voice(config: VoiceConnectConfig) -> tokio::task::JoinHandl
         token: config.voice.token,
         endpoint: config.voice.endpoint,
         mixer,
+        feed,
+        monitor,
         filter_chain: config.filter_chain,
+        encoder_config: config.encoder_config,
+        meter_config: config.meter_config,
         ping: config.ping,
         event_tx: config.event_tx,
         frames_sent: config.frames_sent,
@@ -8,6 +8,26 @@ use super::AppleMusicSource;
 use crate::protocol::tracks::{LoadResult, PlaylistData, PlaylistInfo};
 
 impl AppleMusicSource {
+    /// Several catalog entries (single, album, compilation) can share an ISRC;
+    /// the first one Apple returns is used.
+    pub(crate) async fn resolve_isrc(&self, isrc: &str) -> LoadResult {
+        let path = format!(
+            "/catalog/{}/songs?filter[isrc]={}",
+            self.country_code,
+            urlencoding::encode(isrc)
+        );
+
+        let data = match self.api_request(&path).await {
+            Some(d) => d,
+            None => return LoadResult::Empty {},
+        };
+
+        data.pointer("/data")
+            .and_then(|v| v.as_array())
+            .and_then(|items| items.iter().find_map(|item| self.build_track(item, None)))
+            .map_or(LoadResult::Empty {}, LoadResult::Track)
+    }
+
     pub(crate) async fn resolve_track(&self, id: &str) -> LoadResult {
         let path = format!("/catalog/{}/songs/{}", self.country_code, id);
 
@@ -69,6 +69,10 @@ impl SourcePlugin for AppleMusicSource {
         self.search_prefixes()
             .iter()
             .any(|p| identifier.starts_with(p))
+            || self
+                .isrc_prefixes()
+                .iter()
+                .any(|p| identifier.starts_with(p))
             || self.url_regex.is_match(identifier)
     }
 
@@ -76,6 +80,10 @@ impl SourcePlugin for AppleMusicSource {
         vec!["amsearch:"]
     }
 
+    fn isrc_prefixes(&self) -> Vec<&str> {
+        vec!["amisrc:"]
+    }
+
     fn is_mirror(&self) -> bool {
         true
     }
@@ -94,6 +102,14 @@ impl SourcePlugin for AppleMusicSource {
             return self.search(query).await;
         }
 
+        if let Some(prefix) = self
+            .isrc_prefixes()
+            .into_iter()
+            .find(|p| identifier.starts_with(p))
+        {
+            return self.resolve_isrc(&identifier[prefix.len()..]).await;
+        }
+
         if let Some(caps) = self.url_regex.captures(identifier) {
             let type_str = caps.get(1).map(|m| m.as_str()).unwrap_or("");
             let id = caps.get(2).map(|m| m.as_str()).unwrap_or("");
@@ -5,7 +5,7 @@ use rand::{Rng, distributions::Alphanumeric, thread_rng};
 use crate::sources::{
     audiomack::utils::build_auth_header,
     http::HttpTrack,
-    plugin::{DecoderOutput, PlayableTrack},
+    plugin::{DecoderOutput, DirectStream, PlayableTrack},
 };
 
 pub struct AudiomackTrack {
@@ -22,6 +22,14 @@ impl PlayableTrack for AudiomackTrack {
         };
         http_track.start_decoding(config)
     }
+
+    fn direct_stream(&self) -> Option<DirectStream> {
+        Some(DirectStream {
+            url: self.stream_url.clone(),
+            local_addr: self.local_addr,
+            proxy: None,
+        })
+    }
 }
 
 pub async fn fetch_stream_url(client: &Arc<reqwest::Client>, identifier: &str) -> Option<String> {
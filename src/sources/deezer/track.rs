@@ -294,26 +294,19 @@ impl PlayableTrack for DeezerTrack {
                     }
                 };
 
-                let mut processor = processor;
                 let track_id_for_thread = track_id_for_log.clone();
-                let spawn_res = std::thread::Builder::new()
-                    .name(format!("deezer-decoder-{}", track_id_for_log))
-                    .spawn(move || {
-                        if let Err(e) = processor.run() {
+                crate::audio::worker::submit(
+                    format!("deezer-decoder-{}", track_id_for_log),
+                    processor,
+                    move |res| {
+                        if let Err(e) = res {
                             error!(
                                 "DeezerTrack audio processor error for {}: {}",
                                 track_id_for_thread, e
                             );
                         }
-                    });
-
-                if let Err(e) = spawn_res {
-                    error!(
-                        "DeezerTrack failed to spawn decoder thread for {}: {}",
-                        track_id_for_log, e
-                    );
-                    let _ = err_tx.send(format!("Failed to spawn decoder thread: {e}"));
-                }
+                    },
+                );
             } else {
                 error!("DeezerTrack: Failed to resolve playback URL for {track_id_for_log}");
             }
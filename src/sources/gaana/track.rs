@@ -83,19 +83,20 @@ impl PlayableTrack for GaanaTrack {
                 .expect("failed to spawn gaana setup task");
 
                 match setup_res {
-                    Ok(mut processor) => {
-                        std::thread::Builder::new()
-                            .name(format!("gaana-decoder-{}", track_id_for_log))
-                            .spawn(move || {
-                                if let Err(e) = processor.run() {
+                    Ok(processor) => {
+                        crate::audio::worker::submit(
+                            format!("gaana-decoder-{}", track_id_for_log),
+                            processor,
+                            move |res| {
+                                if let Err(e) = res {
                                     tracing::error!(
                                         "GaanaTrack audio processor error for {}: {}",
                                         track_id_for_log,
                                         e
                                     );
                                 }
-                            })
-                            .expect("failed to spawn gaana decoder thread");
+                            },
+                        );
                     }
                     Err(e) => {
                         tracing::error!(
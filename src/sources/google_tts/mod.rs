@@ -1,22 +1,38 @@
-use std::sync::Arc;
+use std::{collections::VecDeque, io::Cursor, sync::Arc};
 
 use async_trait::async_trait;
+use futures::StreamExt;
+use parking_lot::Mutex;
 use regex::Regex;
-use tracing::debug;
+use symphonia::core::{
+    formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+};
+use tracing::{debug, warn};
 
 use crate::{
+    audio::create_client,
+    common::types::AnyResult,
     config::sources::GoogleTtsConfig,
-    protocol::tracks::{LoadResult, Track, TrackInfo},
-    sources::{
-        http::HttpTrack,
-        plugin::{BoxedTrack, SourcePlugin},
-    },
+    protocol::tracks::{LoadError, LoadResult, Track, TrackInfo},
+    sources::plugin::{BoxedTrack, SourcePlugin},
 };
 
+pub mod track;
+
+/// `translate_tts` cuts anything past roughly 200 characters.
+const MAX_CHUNK_CHARS: usize = 200;
+const TITLE_CHARS: usize = 50;
+/// Chunk requests in flight at once for a single text.
+const MAX_CONCURRENT_CHUNKS: usize = 4;
+/// Synthesized texts kept so playing a loaded track doesn't request it again.
+const MAX_CACHED_AUDIO: usize = 16;
+
 pub struct GoogleTtsSource {
     config: GoogleTtsConfig,
     search_prefixes: Vec<String>,
     url_pattern: Regex,
+    /// Audio synthesized by `load`, oldest first, keyed by `language:text`.
+    audio_cache: Mutex<VecDeque<(String, Arc<[u8]>)>>,
 }
 
 impl GoogleTtsSource {
@@ -25,12 +41,35 @@ impl GoogleTtsSource {
             config,
             search_prefixes: vec!["gtts:".to_string(), "speak:".to_string()],
             url_pattern: Regex::new(r"(?i)^(gtts://|speak://)").unwrap(),
+            audio_cache: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn cached_audio(&self, language: &str, text: &str) -> Option<Arc<[u8]>> {
+        let key = format!("{}:{}", language, text);
+        self.audio_cache
+            .lock()
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, audio)| audio.clone())
+    }
+
+    fn cache_audio(&self, language: &str, text: &str, audio: Arc<[u8]>) {
+        let key = format!("{}:{}", language, text);
+        let mut cache = self.audio_cache.lock();
+        cache.retain(|(k, _)| *k != key);
+        cache.push_back((key, audio));
+        while cache.len() > MAX_CACHED_AUDIO {
+            cache.pop_front();
         }
     }
 
-    fn build_track_info(&self, language: &str, text: &str) -> TrackInfo {
-        let title_text = if text.len() > 50 {
-            format!("{}...", &text[..47])
+    fn build_track_info(&self, language: &str, text: &str, length: u64) -> TrackInfo {
+        let title_text = if text.chars().count() > TITLE_CHARS {
+            format!(
+                "{}...",
+                text.chars().take(TITLE_CHARS - 3).collect::<String>()
+            )
         } else {
             text.to_string()
         };
@@ -39,27 +78,17 @@ impl GoogleTtsSource {
             identifier: format!("gtts://{}:{}", language, text),
             is_seekable: true,
             author: "Google TTS".to_string(),
-            length: 0, // length is unknown/unlimited
+            length,
             is_stream: false,
             position: 0,
             title: format!("TTS: {}", title_text),
-            uri: Some(self.build_url(language, text)),
+            uri: Some(build_url(language, text, 0, 1)),
             source_name: self.name().to_string(),
             artwork_url: None,
             isrc: None,
         }
     }
 
-    fn build_url(&self, language: &str, text: &str) -> String {
-        let encoded_text = urlencoding::encode(text);
-        format!(
-            "https://translate.google.com/translate_tts?ie=UTF-8&q={}&tl={}&total=1&idx=0&textlen={}&client=gtx",
-            encoded_text,
-            language,
-            text.len()
-        )
-    }
-
     fn parse_query(&self, identifier: &str) -> (String, String) {
         let mut path = identifier;
 
@@ -87,6 +116,191 @@ impl GoogleTtsSource {
     }
 }
 
+fn build_url(language: &str, text: &str, idx: usize, total: usize) -> String {
+    format!(
+        "https://translate.google.com/translate_tts?ie=UTF-8&q={}&tl={}&total={}&idx={}&textlen={}&client=gtx",
+        urlencoding::encode(text),
+        language,
+        total,
+        idx,
+        text.chars().count()
+    )
+}
+
+/// Splits `text` into request-sized chunks, preferring sentence ends, then
+/// spaces and commas, and only cutting inside a word when nothing else fits.
+fn split_text(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        current.push(c);
+        if matches!(
+            c,
+            '.' | '!' | '?' | ';' | '\n' | '。' | '！' | '？' | '；' | '।'
+        ) {
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    sentences.push(current);
+
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    for sentence in sentences {
+        for piece in split_long(&sentence) {
+            if chunk.chars().count() + piece.chars().count() > MAX_CHUNK_CHARS {
+                chunks.push(std::mem::take(&mut chunk));
+            }
+            chunk.push_str(&piece);
+        }
+    }
+    chunks.push(chunk);
+
+    chunks
+        .into_iter()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+/// Breaks a single over-long sentence at spaces or commas, or by characters
+/// for scripts written without spaces.
+fn split_long(sentence: &str) -> Vec<String> {
+    if sentence.chars().count() <= MAX_CHUNK_CHARS {
+        return vec![sentence.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    let mut piece = String::new();
+    for c in sentence.chars() {
+        piece.push(c);
+        if c.is_whitespace() || matches!(c, ',' | '、' | '，') {
+            pieces.push(std::mem::take(&mut piece));
+        }
+    }
+    pieces.push(piece);
+
+    pieces
+        .into_iter()
+        .flat_map(|p| {
+            let chars: Vec<char> = p.chars().collect();
+            chars
+                .chunks(MAX_CHUNK_CHARS)
+                .map(|c| c.iter().collect::<String>())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Fetches every chunk and joins them into one MP3 stream.
+async fn synthesize(client: &reqwest::Client, language: &str, text: &str) -> AnyResult<Arc<[u8]>> {
+    let chunks = split_text(text);
+    let total = chunks.len();
+    debug!("Google TTS: synthesizing {} chunk(s)", total);
+
+    let mut responses = futures::stream::iter(chunks.iter().enumerate().map(|(idx, chunk)| {
+        let url = build_url(language, chunk, idx, total);
+        async move {
+            let res = client.get(&url).send().await?.error_for_status()?;
+            res.bytes().await
+        }
+    }))
+    .buffered(MAX_CONCURRENT_CHUNKS);
+
+    let mut audio = Vec::new();
+    while let Some(bytes) = responses.next().await {
+        audio.extend_from_slice(mp3_payload(&bytes?));
+    }
+    Ok(audio.into())
+}
+
+/// Strips the ID3v2 tag and the Xing/Info frame so chunks can be concatenated
+/// without the decoder trusting the first chunk's frame count.
+fn mp3_payload(data: &[u8]) -> &[u8] {
+    let mut data = data;
+    if data.len() >= 10 && &data[..3] == b"ID3" {
+        let size = data[6..10]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 7) | (b & 0x7f) as usize);
+        let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+        data = data.get(10 + size + footer..).unwrap_or_default();
+    }
+
+    if let Some(len) = mp3_frame_len(data) {
+        let frame = &data[..len.min(data.len())];
+        if frame.windows(4).any(|w| w == b"Xing" || w == b"Info") {
+            return data.get(len..).unwrap_or_default();
+        }
+    }
+    data
+}
+
+/// Length of the MPEG audio layer III frame starting at `data[0]`.
+fn mp3_frame_len(data: &[u8]) -> Option<usize> {
+    const BITRATES_V1: [u32; 16] = [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 0,
+    ];
+    const BITRATES_V2: [u32; 16] = [
+        0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160, 0,
+    ];
+
+    if data.len() < 4 || data[0] != 0xff || data[1] & 0xe0 != 0xe0 {
+        return None;
+    }
+    let version = (data[1] >> 3) & 0x03;
+    let layer = (data[1] >> 1) & 0x03;
+    if version == 1 || layer != 1 {
+        return None;
+    }
+
+    let mpeg1 = version == 3;
+    let bitrate = if mpeg1 { BITRATES_V1 } else { BITRATES_V2 }[(data[2] >> 4) as usize];
+    let sample_rate = match ((data[2] >> 2) & 0x03, version) {
+        (0, 3) => 44100,
+        (1, 3) => 48000,
+        (2, 3) => 32000,
+        (0, 2) => 22050,
+        (1, 2) => 24000,
+        (2, 2) => 16000,
+        (0, 0) => 11025,
+        (1, 0) => 12000,
+        (2, 0) => 8000,
+        _ => return None,
+    };
+    if bitrate == 0 {
+        return None;
+    }
+
+    let padding = ((data[2] >> 1) & 0x01) as u32;
+    let coefficient = if mpeg1 { 144 } else { 72 };
+    Some((coefficient * bitrate * 1000 / sample_rate + padding) as usize)
+}
+
+/// Sums packet durations; the joined stream has no header carrying a total.
+fn mp3_duration_ms(audio: Arc<[u8]>) -> AnyResult<u64> {
+    let mss = MediaSourceStream::new(Box::new(Cursor::new(audio)), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension("mp3");
+
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )?
+        .format;
+    let sample_rate = format
+        .default_track()
+        .and_then(|t| t.codec_params.sample_rate)
+        .ok_or("missing sample rate")?;
+
+    let mut frames = 0u64;
+    while let Ok(packet) = format.next_packet() {
+        frames += packet.dur;
+    }
+    Ok(frames * 1000 / sample_rate as u64)
+}
+
 #[async_trait]
 impl SourcePlugin for GoogleTtsSource {
     fn name(&self) -> &str {
@@ -103,7 +317,7 @@ impl SourcePlugin for GoogleTtsSource {
     async fn load(
         &self,
         identifier: &str,
-        _routeplanner: Option<Arc<dyn crate::routeplanner::RoutePlanner>>,
+        routeplanner: Option<Arc<dyn crate::routeplanner::RoutePlanner>>,
     ) -> LoadResult {
         debug!("Google TTS loading: {}", identifier);
 
@@ -113,7 +327,38 @@ impl SourcePlugin for GoogleTtsSource {
             return LoadResult::Empty {};
         }
 
-        let info = self.build_track_info(&language, &text);
+        // The duration is only known once the audio exists, so it is kept
+        // for playback instead of being synthesized again.
+        let synthesized = match create_client(
+            crate::common::utils::default_user_agent(),
+            routeplanner.and_then(|rp| rp.get_address()),
+            None,
+            None,
+        ) {
+            Ok(client) => synthesize(&client, &language, &text).await,
+            Err(e) => Err(e),
+        };
+        let audio = match synthesized {
+            Ok(audio) => audio,
+            Err(e) => {
+                warn!("Google TTS synthesis failed: {}", e);
+                return LoadResult::Error(LoadError {
+                    message: Some("Failed to synthesize text".to_owned()),
+                    severity: crate::common::Severity::Common,
+                    cause: e.to_string(),
+                    cause_stack_trace: None,
+                });
+            }
+        };
+        self.cache_audio(&language, &text, audio.clone());
+
+        let length = tokio::task::spawn_blocking(move || mp3_duration_ms(audio))
+            .await
+            .ok()
+            .and_then(|r| r.ok())
+            .unwrap_or(0);
+
+        let info = self.build_track_info(&language, &text, length);
         LoadResult::Track(Track::new(info))
     }
 
@@ -123,13 +368,13 @@ impl SourcePlugin for GoogleTtsSource {
         routeplanner: Option<Arc<dyn crate::routeplanner::RoutePlanner>>,
     ) -> Option<BoxedTrack> {
         let (language, text) = self.parse_query(identifier);
-        let url = self.build_url(&language, &text);
+        let audio = self.cached_audio(&language, &text);
 
-        // Use HttpTrack to decode the audio stream
-        Some(Box::new(HttpTrack {
-            url,
+        Some(Box::new(track::GoogleTtsTrack {
+            audio,
+            language,
+            text,
             local_addr: routeplanner.and_then(|rp| rp.get_address()),
-            proxy: None, // Google TTS doesn't currently support proxy config directly in the new implementation, similar to Spotify
         }))
     }
 
@@ -137,3 +382,42 @@ impl SourcePlugin for GoogleTtsSource {
         self.search_prefixes.iter().map(|s| s.as_str()).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_text_on_sentences() {
+        let sentence = "This sentence is about forty characters. ";
+        let text = sentence.repeat(8);
+        let chunks = split_text(&text);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(|c| c.chars().count() <= MAX_CHUNK_CHARS));
+        assert!(chunks.iter().all(|c| c.ends_with('.')));
+    }
+
+    #[test]
+    fn test_split_text_without_spaces() {
+        let text = "あ".repeat(450);
+        let chunks = split_text(&text);
+        assert_eq!(
+            chunks.iter().map(|c| c.chars().count()).collect::<Vec<_>>(),
+            vec![200, 200, 50]
+        );
+    }
+
+    #[test]
+    fn test_mp3_payload_strips_info_frame() {
+        // MPEG-2 layer III, 32 kbps, 24 kHz: 72 * 32000 / 24000 = 96 bytes.
+        let mut info = vec![0xff, 0xf3, 0x44, 0xc4];
+        info.extend_from_slice(b"Info");
+        info.resize(96, 0);
+        let audio = [0xff, 0xf3, 0x44, 0xc4, 1, 2, 3];
+
+        let mut data = b"ID3\x03\x00\x00\x00\x00\x00\x02xx".to_vec();
+        data.extend_from_slice(&info);
+        data.extend_from_slice(&audio);
+        assert_eq!(mp3_payload(&data), &audio);
+    }
+}
@@ -0,0 +1,87 @@
+use std::{io::Cursor, net::IpAddr, sync::Arc};
+
+use tracing::error;
+
+use super::synthesize;
+use crate::{
+    audio::{
+        AudioFrame, create_client,
+        processor::{AudioProcessor, DecoderCommand},
+    },
+    common::types::AudioFormat,
+    sources::plugin::{DecoderOutput, PlayableTrack},
+};
+
+/// Long text is synthesized in several requests; the chunks are joined into a
+/// single MP3 stream before decoding so playback and seeking stay seamless.
+pub struct GoogleTtsTrack {
+    /// Audio already synthesized when the track was loaded.
+    pub audio: Option<Arc<[u8]>>,
+    pub language: String,
+    pub text: String,
+    pub local_addr: Option<IpAddr>,
+}
+
+impl PlayableTrack for GoogleTtsTrack {
+    fn start_decoding(&self, config: crate::config::player::PlayerConfig) -> DecoderOutput {
+        let (tx, rx) = flume::bounded::<AudioFrame>((config.buffer_duration_ms / 20) as usize);
+        let (cmd_tx, cmd_rx) = flume::unbounded::<DecoderCommand>();
+        let (err_tx, err_rx) = flume::bounded::<String>(1);
+
+        let cached = self.audio.clone();
+        let language = self.language.clone();
+        let text = self.text.clone();
+        let local_addr = self.local_addr;
+
+        let handle = tokio::runtime::Handle::current();
+        tokio::task::spawn_blocking(move || {
+            let _guard = handle.enter();
+            let audio = match cached {
+                Some(audio) => Ok(audio),
+                None => create_client(
+                    crate::common::utils::default_user_agent(),
+                    local_addr,
+                    None,
+                    None,
+                )
+                .and_then(|client| handle.block_on(synthesize(&client, &language, &text))),
+            };
+            let audio = match audio {
+                Ok(audio) => audio,
+                Err(e) => {
+                    error!("Google TTS synthesis failed: {e}");
+                    let _ = err_tx.send(format!("Failed to synthesize text: {e}"));
+                    return;
+                }
+            };
+
+            let reader = Box::new(Cursor::new(audio)) as Box<dyn symphonia::core::io::MediaSource>;
+            match AudioProcessor::new(
+                reader,
+                Some(AudioFormat::Mp3),
+                tx,
+                cmd_rx,
+                Some(err_tx.clone()),
+                config,
+            ) {
+                Ok(processor) => {
+                    crate::audio::worker::submit(
+                        format!("google-tts-decoder-{}", language),
+                        processor,
+                        |res| {
+                            if let Err(e) = res {
+                                error!("Google TTS audio processor error: {e}");
+                            }
+                        },
+                    );
+                }
+                Err(e) => {
+                    error!("Google TTS failed to initialize processor: {e}");
+                    let _ = err_tx.send(format!("Failed to initialize processor: {e}"));
+                }
+            }
+        });
+
+        (rx, cmd_tx, err_rx)
+    }
+}
@@ -4,10 +4,7 @@ use std::sync::{Arc, OnceLock};
 use async_trait::async_trait;
 use regex::Regex;
 use symphonia::core::{
-    codecs::CODEC_TYPE_NULL,
-    formats::FormatOptions,
-    io::MediaSourceStream,
-    meta::{MetadataOptions, StandardTagKey},
+    codecs::CODEC_TYPE_NULL, formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions,
     probe::Hint,
 };
 use tracing::{debug, error, warn};
@@ -21,7 +18,8 @@ use crate::{
     protocol::tracks::{LoadError, LoadResult, Track, TrackInfo},
     sources::{
         SourcePlugin,
-        plugin::{DecoderOutput, PlayableTrack},
+        metadata::{ArtworkKey, FileTags},
+        plugin::{DecoderOutput, DirectStream, PlayableTrack},
     },
 };
 
@@ -43,7 +41,7 @@ impl HttpSource {
         Self
     }
 
-    fn probe_metadata(url: String, local_addr: Option<std::net::IpAddr>) -> AnyResult<TrackInfo> {
+    fn probe_metadata(url: String, local_addr: Option<std::net::IpAddr>) -> AnyResult<Track> {
         let source = reader::HttpReader::new(&url, local_addr, None)?;
         let mut hint = Hint::new();
 
@@ -60,15 +58,16 @@ impl HttpSource {
             hint.with_extension(ext);
         }
 
-        let probed = symphonia::default::get_probe().format(
+        let mut probed = symphonia::default::get_probe().format(
             &hint,
             mss,
             &FormatOptions::default(),
             &MetadataOptions::default(),
         )?;
+        let tags = FileTags::read(&mut probed);
 
-        let mut format = probed.format;
-        let track = format
+        let track = probed
+            .format
             .tracks()
             .iter()
             .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
@@ -84,40 +83,19 @@ impl HttpSource {
             0
         };
 
-        let mut title = String::new();
-        let mut author = String::new();
-
-        if let Some(metadata) = format.metadata().current() {
-            if let Some(tag) = metadata
-                .tags()
-                .iter()
-                .find(|t| t.std_key == Some(StandardTagKey::TrackTitle))
-            {
-                title = tag.value.to_string();
-            }
-            if let Some(tag) = metadata
-                .tags()
-                .iter()
-                .find(|t| t.std_key == Some(StandardTagKey::Artist))
-            {
-                author = tag.value.to_string();
-            }
-        }
-
-        if title.is_empty() {
-            title = url
-                .split('/')
+        let title = tags.title.clone().unwrap_or_else(|| {
+            url.split('/')
                 .next_back()
                 .and_then(|s| s.split('?').next())
                 .unwrap_or("Unknown Title")
-                .to_owned();
-        }
-
-        if author.is_empty() {
-            author = "Unknown Artist".to_owned();
-        }
+                .to_owned()
+        });
+        let author = tags
+            .artist
+            .clone()
+            .unwrap_or_else(|| "Unknown Artist".to_owned());
 
-        Ok(TrackInfo {
+        let mut info = TrackInfo {
             identifier: url.clone(),
             author,
             length: duration,
@@ -125,11 +103,15 @@ impl HttpSource {
             is_stream: false,
             position: 0,
             title,
-            uri: Some(url),
+            uri: Some(url.clone()),
             source_name: "http".to_owned(),
             artwork_url: None,
             isrc: None,
-        })
+        };
+        // Remote files are not fetched again, so their pictures stay in memory.
+        tags.apply_to(&mut info, ArtworkKey::Memory(&url));
+
+        Ok(tags.track(info))
     }
 }
 
@@ -160,7 +142,7 @@ impl SourcePlugin for HttpSource {
         .await;
 
         match probe_result {
-            Ok(Ok(info)) => LoadResult::Track(Track::new(info)),
+            Ok(Ok(track)) => LoadResult::Track(track),
             Ok(Err(e)) => {
                 warn!("Probing failed for {identifier}: {e}");
                 // This mimics Lavaplayer's behavior where unknown formats return null.
@@ -234,15 +216,16 @@ impl PlayableTrack for HttpTrack {
                 .map(crate::common::types::AudioFormat::from_ext);
 
             match AudioProcessor::new(reader, kind, tx, cmd_rx, Some(err_tx.clone()), config) {
-                Ok(mut processor) => {
-                    std::thread::Builder::new()
-                        .name(format!("http-decoder-{}", url))
-                        .spawn(move || {
-                            if let Err(e) = processor.run() {
+                Ok(processor) => {
+                    crate::audio::worker::submit(
+                        format!("http-decoder-{}", url),
+                        processor,
+                        |res| {
+                            if let Err(e) = res {
                                 error!("HTTP track audio processor error: {e}");
                             }
-                        })
-                        .expect("failed to spawn http decoder thread");
+                        },
+                    );
                 }
                 Err(e) => {
                     error!("HTTP track failed to initialize processor: {e}");
@@ -253,4 +236,13 @@ impl PlayableTrack for HttpTrack {
 
         (rx, cmd_tx, err_rx)
     }
+
+    fn direct_stream(&self) -> Option<DirectStream> {
+        // Icecast `icy://` links need the decoder's own reader.
+        self.url.starts_with("http").then(|| DirectStream {
+            url: self.url.clone(),
+            local_addr: self.local_addr,
+            proxy: self.proxy.clone(),
+        })
+    }
 }
@@ -85,6 +85,10 @@ impl crate::sources::plugin::SourcePlugin for JioSaavnSource {
         self.search_prefixes()
             .iter()
             .any(|p| identifier.starts_with(p))
+            || self
+                .isrc_prefixes()
+                .iter()
+                .any(|p| identifier.starts_with(p))
             || self
                 .rec_prefixes()
                 .iter()
@@ -96,6 +100,10 @@ impl crate::sources::plugin::SourcePlugin for JioSaavnSource {
         vec!["jssearch:"]
     }
 
+    fn isrc_prefixes(&self) -> Vec<&str> {
+        vec!["jsisrc:"]
+    }
+
     fn rec_prefixes(&self) -> Vec<&str> {
         vec!["jsrec:"]
     }
@@ -117,6 +125,16 @@ impl crate::sources::plugin::SourcePlugin for JioSaavnSource {
             }
         }
 
+        // JioSaavn has no ISRC filter; the code is searched as text.
+        for prefix in self.isrc_prefixes() {
+            if let Some(isrc) = identifier.strip_prefix(prefix) {
+                return match self.search(isrc).await {
+                    LoadResult::Search(tracks) => LoadResult::from_isrc_search(tracks, isrc),
+                    other => other,
+                };
+            }
+        }
+
         if let Some(caps) = url_regex().captures(identifier) {
             let type_ = caps.name("type").map(|m| m.as_str()).unwrap_or("");
             let id = caps.name("id").map(|m| m.as_str()).unwrap_or("");
@@ -70,19 +70,20 @@ impl PlayableTrack for JioSaavnTrack {
                 .or(Some(crate::common::types::AudioFormat::Mp4));
 
             match AudioProcessor::new(reader, kind, tx, cmd_rx, Some(err_tx.clone()), config) {
-                Ok(mut processor) => {
-                    std::thread::Builder::new()
-                        .name(format!("jiosaavn-decoder-{}", url))
-                        .spawn(move || {
-                            if let Err(e) = processor.run() {
+                Ok(processor) => {
+                    crate::audio::worker::submit(
+                        format!("jiosaavn-decoder-{}", url),
+                        processor,
+                        move |res| {
+                            if let Err(e) = res {
                                 tracing::error!(
                                     "JioSaavn audio processor error for {}: {}",
                                     url,
                                     e
                                 );
                             }
-                        })
-                        .expect("failed to spawn jiosaavn decoder thread");
+                        },
+                    );
                 }
                 Err(e) => {
                     tracing::error!("JioSaavn failed to initialize processor for {}: {}", url, e);
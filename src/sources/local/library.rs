@@ -0,0 +1,296 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use tracing::{debug, warn};
+
+use super::LocalSource;
+use crate::{
+    common::types::AudioFormat,
+    protocol::tracks::{Track, TrackInfo},
+    sources::metadata::FileTags,
+};
+
+/// A scanned file with the tags `localsearch:` matches against.
+#[derive(Clone)]
+pub struct LibraryEntry {
+    pub info: TrackInfo,
+    /// Tags without the picture, which is re-read on request.
+    pub tags: FileTags,
+    modified: Option<SystemTime>,
+    /// Lowercased title, artist and album.
+    haystack: String,
+}
+
+impl LibraryEntry {
+    pub fn new(info: TrackInfo, tags: FileTags, modified: Option<SystemTime>) -> Self {
+        let haystack = format!(
+            "{} {} {}",
+            info.title,
+            info.author,
+            tags.album.as_deref().unwrap_or_default()
+        )
+        .to_lowercase();
+        Self {
+            info,
+            tags,
+            modified,
+            haystack,
+        }
+    }
+
+    pub fn track(&self) -> Track {
+        self.tags.track(self.info.clone())
+    }
+}
+
+/// Index of every audio file under the configured roots.
+#[derive(Default)]
+pub struct Library {
+    roots: Vec<PathBuf>,
+    entries: HashMap<PathBuf, LibraryEntry>,
+}
+
+impl Library {
+    pub fn new(roots: &[String]) -> Self {
+        let roots = roots
+            .iter()
+            .filter_map(|root| match std::fs::canonicalize(root) {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    warn!("Local library root '{}' is unavailable: {}", root, e);
+                    None
+                }
+            })
+            .collect();
+        Self {
+            roots,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn has_roots(&self) -> bool {
+        !self.roots.is_empty()
+    }
+
+    /// Without configured roots every readable path is allowed, as before.
+    pub fn is_allowed(&self, path: &Path) -> bool {
+        if self.roots.is_empty() {
+            return true;
+        }
+        std::fs::canonicalize(path)
+            .map(|path| self.roots.iter().any(|root| path.starts_with(root)))
+            .unwrap_or(false)
+    }
+
+    pub fn entry(&self, path: &Path) -> Option<&LibraryEntry> {
+        self.entries.get(path)
+    }
+
+    /// Every token must appear in the title, artist or album; title hits rank first.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<Track> {
+        let query = query.trim().to_lowercase();
+        let tokens: Vec<&str> = query.split_whitespace().collect();
+        if tokens.is_empty() {
+            return Vec::new();
+        }
+
+        let mut hits: Vec<(u8, &PathBuf, &LibraryEntry)> = self
+            .entries
+            .iter()
+            .filter(|(_, e)| tokens.iter().all(|t| e.haystack.contains(t)))
+            .map(|(path, e)| {
+                let rank = if e.info.title.to_lowercase().contains(&query) {
+                    0
+                } else if e.info.author.to_lowercase().contains(&query) {
+                    1
+                } else {
+                    2
+                };
+                (rank, path, e)
+            })
+            .collect();
+        hits.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+
+        hits.into_iter()
+            .take(limit)
+            .map(|(_, _, e)| e.track())
+            .collect()
+    }
+
+    /// Walks the roots again, re-reading tags only for new or modified files.
+    pub fn scan(
+        roots: &[PathBuf],
+        previous: &HashMap<PathBuf, LibraryEntry>,
+    ) -> HashMap<PathBuf, LibraryEntry> {
+        let mut entries = HashMap::new();
+        for path in roots.iter().flat_map(|root| audio_files(root)) {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            if let Some(entry) = previous.get(&path)
+                && entry.modified == modified
+            {
+                entries.insert(path, entry.clone());
+                continue;
+            }
+
+            match LocalSource::probe_file(&path.to_string_lossy()) {
+                Ok((info, tags)) => {
+                    entries.insert(path, LibraryEntry::new(info, tags, modified));
+                }
+                Err(e) => debug!("Local library: skipping '{}': {}", path.display(), e),
+            }
+        }
+        entries
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    pub fn entries(&self) -> &HashMap<PathBuf, LibraryEntry> {
+        &self.entries
+    }
+
+    pub fn replace(&mut self, entries: HashMap<PathBuf, LibraryEntry>) {
+        self.entries = entries;
+    }
+}
+
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| AudioFormat::from_ext(e) != AudioFormat::Unknown)
+}
+
+pub fn is_m3u(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("m3u") || e.eq_ignore_ascii_case("m3u8"))
+}
+
+/// Audio files under `dir`, recursively, in path order.
+pub fn audio_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(t) if t.is_dir() => pending.push(path),
+                Ok(_) if is_audio_file(&path) => files.push(path),
+                _ => {}
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Entries of an M3U/M3U8 playlist, relative paths resolved against its folder.
+pub fn m3u_entries(path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let content = std::fs::read_to_string(path)?;
+    let base = path.parent().unwrap_or(Path::new("."));
+    Ok(content
+        .lines()
+        .map(|l| l.trim().trim_start_matches('\u{feff}'))
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| {
+            let entry = Path::new(l.strip_prefix("file://").unwrap_or(l));
+            if entry.is_absolute() {
+                entry.to_path_buf()
+            } else {
+                base.join(entry)
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, author: &str, album: &str) -> LibraryEntry {
+        LibraryEntry::new(
+            TrackInfo {
+                identifier: format!("/music/{title}.flac"),
+                is_seekable: true,
+                author: author.to_owned(),
+                length: 0,
+                is_stream: false,
+                position: 0,
+                title: title.to_owned(),
+                uri: None,
+                source_name: "local".to_owned(),
+                artwork_url: None,
+                isrc: None,
+            },
+            FileTags {
+                album: Some(album.to_owned()),
+                ..Default::default()
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn test_search_matches_all_tokens() {
+        let mut library = Library::default();
+        library.replace(HashMap::from([
+            (
+                PathBuf::from("/music/a.flac"),
+                entry("Blue", "Band", "Night Songs"),
+            ),
+            (
+                PathBuf::from("/music/b.flac"),
+                entry("Night Drive", "Other", "Roads"),
+            ),
+            (
+                PathBuf::from("/music/c.flac"),
+                entry("Red", "Band", "Day Songs"),
+            ),
+        ]));
+
+        let titles = |q: &str| -> Vec<String> {
+            library
+                .search(q, 10)
+                .into_iter()
+                .map(|t| t.info.title)
+                .collect()
+        };
+        assert_eq!(titles("night"), vec!["Night Drive", "Blue"]);
+        assert_eq!(titles("band songs day"), vec!["Red"]);
+        assert!(titles("  ").is_empty());
+    }
+
+    #[test]
+    fn test_roots_guard_and_m3u() {
+        let root = std::env::temp_dir().join(format!("rustalink-library-{}", std::process::id()));
+        let music = root.join("music");
+        std::fs::create_dir_all(&music).unwrap();
+        std::fs::write(music.join("song.mp3"), b"").unwrap();
+        std::fs::write(
+            music.join("list.m3u"),
+            "#EXTM3U\n#EXTINF:1,Song\nsong.mp3\n/etc/passwd\n",
+        )
+        .unwrap();
+
+        let library = Library::new(&[music.to_string_lossy().into_owned()]);
+        assert!(library.is_allowed(&music.join("song.mp3")));
+        assert!(!library.is_allowed(Path::new("/etc/passwd")));
+        assert!(!library.is_allowed(&music.join("../music/../../")));
+
+        let entries = m3u_entries(&music.join("list.m3u")).unwrap();
+        assert_eq!(
+            entries,
+            vec![music.join("song.mp3"), PathBuf::from("/etc/passwd")]
+        );
+        assert_eq!(audio_files(&root), vec![music.join("song.mp3")]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,68 +1,120 @@
 use std::{
     io::{Read, Seek, SeekFrom},
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use async_trait::async_trait;
+use parking_lot::RwLock;
 use symphonia::core::{
     codecs::CODEC_TYPE_NULL,
     formats::FormatOptions,
     io::MediaSourceStream,
-    meta::{MetadataOptions, StandardTagKey},
-    probe::Hint,
+    meta::MetadataOptions,
+    probe::{Hint, ProbeResult},
 };
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
 
 use crate::{
     audio::{
         AudioFrame,
         processor::{AudioProcessor, DecoderCommand},
     },
-    common::Severity,
-    protocol::tracks::{LoadError, LoadResult, Track, TrackInfo},
+    common::{Severity, types::AnyResult},
+    config::sources::LocalSourceConfig,
+    protocol::tracks::{LoadError, LoadResult, PlaylistData, PlaylistInfo, Track, TrackInfo},
     sources::{
         SourcePlugin,
+        metadata::{ArtworkKey, FileTags},
         plugin::{DecoderOutput, PlayableTrack},
     },
 };
 
-pub struct LocalSource;
+pub mod library;
+
+use library::{Library, audio_files, is_m3u};
+
+const SEARCH_PREFIX: &str = "localsearch:";
+
+pub struct LocalSource {
+    config: LocalSourceConfig,
+    library: Arc<RwLock<Library>>,
+}
 
 impl Default for LocalSource {
     fn default() -> Self {
-        Self::new()
+        Self::new(LocalSourceConfig::default())
     }
 }
 
 impl LocalSource {
-    pub fn new() -> Self {
-        Self
+    pub fn new(config: LocalSourceConfig) -> Self {
+        let library = Arc::new(RwLock::new(Library::new(&config.roots)));
+
+        if library.read().has_roots() {
+            let library = library.clone();
+            let interval = config.rescan_interval_secs;
+            tokio::spawn(async move {
+                loop {
+                    let (roots, previous) = {
+                        let library = library.read();
+                        (library.roots().to_vec(), library.entries().clone())
+                    };
+                    match tokio::task::spawn_blocking(move || Library::scan(&roots, &previous))
+                        .await
+                    {
+                        Ok(entries) => {
+                            let mut library = library.write();
+                            if entries.len() != library.entries().len() {
+                                info!("Local library indexed {} tracks", entries.len());
+                            }
+                            library.replace(entries);
+                        }
+                        Err(e) => error!("Local library scan failed: {e}"),
+                    }
+
+                    if interval == 0 {
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_secs(interval)).await;
+                }
+            });
+        }
+
+        Self { config, library }
     }
 
-    fn probe_file(path: &str) -> Result<TrackInfo, Box<dyn std::error::Error + Send + Sync>> {
+    fn probe(path: &Path) -> AnyResult<ProbeResult> {
         let file = std::fs::File::open(path)?;
-        let path_obj = Path::new(path);
-        let ext = path_obj
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|s| s.to_lowercase());
-
         let mut hint = Hint::new();
-        if let Some(ref e) = ext {
-            hint.with_extension(e);
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            hint.with_extension(&ext.to_lowercase());
         }
 
         let mss = MediaSourceStream::new(Box::new(file), Default::default());
-        let probed = symphonia::default::get_probe().format(
+        Ok(symphonia::default::get_probe().format(
             &hint,
             mss,
             &FormatOptions::default(),
             &MetadataOptions::default(),
-        )?;
+        )?)
+    }
+
+    /// Tags only; embedded artwork is served by re-reading the file.
+    pub(crate) fn read_tags(path: &Path) -> AnyResult<FileTags> {
+        Ok(FileTags::read(&mut Self::probe(path)?))
+    }
 
-        let mut format = probed.format;
-        let track = format
+    /// Reads duration and tags. The picture is registered with the artwork
+    /// store and dropped from the returned tags.
+    pub(crate) fn probe_file(path: &str) -> AnyResult<(TrackInfo, FileTags)> {
+        let path_obj = Path::new(path);
+        let mut probed = Self::probe(path_obj)?;
+        let mut tags = FileTags::read(&mut probed);
+
+        let track = probed
+            .format
             .tracks()
             .iter()
             .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
@@ -80,37 +132,20 @@ impl LocalSource {
             })
             .unwrap_or(0);
 
-        // Metadata extraction
-        let mut title = String::new();
-        let mut author = String::new();
-
-        if let Some(meta) = format.metadata().current() {
-            for tag in meta.tags() {
-                match tag.std_key {
-                    Some(StandardTagKey::TrackTitle) => title = tag.value.to_string(),
-                    Some(StandardTagKey::Artist) | Some(StandardTagKey::AlbumArtist)
-                        if author.is_empty() =>
-                    {
-                        author = tag.value.to_string();
-                    }
-                    _ => {}
-                }
-            }
-        }
-
         // Fallback: use filename if metadata is missing
-        if title.is_empty() {
-            title = path_obj
+        let title = tags.title.clone().unwrap_or_else(|| {
+            path_obj
                 .file_stem()
                 .and_then(|s| s.to_str())
                 .unwrap_or("Unknown")
-                .to_owned();
-        }
-        if author.is_empty() {
-            author = "Unknown Artist".to_owned();
-        }
+                .to_owned()
+        });
+        let author = tags
+            .artist
+            .clone()
+            .unwrap_or_else(|| "Unknown Artist".to_owned());
 
-        Ok(TrackInfo {
+        let mut info = TrackInfo {
             identifier: path.to_owned(),
             is_seekable: true,
             author,
@@ -122,8 +157,90 @@ impl LocalSource {
             source_name: "local".to_owned(),
             artwork_url: None,
             isrc: None,
+        };
+        tags.apply_to(&mut info, ArtworkKey::File(path_obj));
+        tags.picture = None;
+
+        Ok((info, tags))
+    }
+
+    /// Indexed tags when the file has been scanned, otherwise a fresh probe.
+    fn track_for(library: &Library, path: &Path) -> Option<Track> {
+        if let Some(entry) = std::fs::canonicalize(path)
+            .ok()
+            .and_then(|p| library.entry(&p).cloned())
+        {
+            return Some(entry.track());
+        }
+        match Self::probe_file(&path.to_string_lossy()) {
+            Ok((info, tags)) => Some(tags.track(info)),
+            Err(e) => {
+                debug!("Local source: skipping '{}': {e}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Directories play every audio file below them; `.m3u` files play their
+    /// entries in order. Entries outside the library roots are dropped.
+    fn load_playlist(library: &Library, path: &Path) -> AnyResult<PlaylistData> {
+        let files = if path.is_dir() {
+            audio_files(path)
+        } else {
+            library::m3u_entries(path)?
+        };
+
+        let tracks: Vec<Track> = files
+            .iter()
+            .filter(|f| f.is_file() && library.is_allowed(f))
+            .filter_map(|f| Self::track_for(library, f))
+            .collect();
+
+        let name = if path.is_dir() {
+            path.file_name()
+        } else {
+            path.file_stem()
+        }
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Local Playlist".to_owned());
+
+        Ok(PlaylistData {
+            info: PlaylistInfo {
+                name,
+                selected_track: -1,
+            },
+            plugin_info: serde_json::json!({
+                "type": "playlist",
+                "url": format!("file://{}", path.display()),
+                "totalTracks": tracks.len()
+            }),
+            tracks,
         })
     }
+
+    fn load_path(library: &Library, path: &str) -> AnyResult<LoadResult> {
+        let path_buf = PathBuf::from(path);
+        if !library.is_allowed(&path_buf) {
+            return Err("Path is outside the configured library roots".into());
+        }
+
+        if path_buf.is_dir() || is_m3u(&path_buf) {
+            // Without roots every path is allowed, so a directory could be
+            // the whole filesystem.
+            if !library.has_roots() {
+                return Err("Directories and playlists need configured library roots".into());
+            }
+            let playlist = Self::load_playlist(library, &path_buf)?;
+            return Ok(if playlist.tracks.is_empty() {
+                LoadResult::Empty {}
+            } else {
+                LoadResult::Playlist(playlist)
+            });
+        }
+
+        let (info, tags) = Self::probe_file(path)?;
+        Ok(LoadResult::Track(tags.track(info)))
+    }
 }
 
 #[async_trait]
@@ -133,8 +250,15 @@ impl SourcePlugin for LocalSource {
     }
 
     fn can_handle(&self, identifier: &str) -> bool {
-        let path = identifier.strip_prefix("file://").unwrap_or(identifier);
-        Path::new(path).is_file()
+        if identifier.starts_with(SEARCH_PREFIX) {
+            return true;
+        }
+        let path = Path::new(identifier.strip_prefix("file://").unwrap_or(identifier));
+        path.is_file() || (path.is_dir() && self.library.read().has_roots())
+    }
+
+    fn search_prefixes(&self) -> Vec<&str> {
+        vec![SEARCH_PREFIX]
     }
 
     async fn load(
@@ -142,20 +266,31 @@ impl SourcePlugin for LocalSource {
         identifier: &str,
         _routeplanner: Option<Arc<dyn crate::routeplanner::RoutePlanner>>,
     ) -> LoadResult {
+        if let Some(query) = identifier.strip_prefix(SEARCH_PREFIX) {
+            let tracks = self.library.read().search(query, self.config.search_limit);
+            return if tracks.is_empty() {
+                LoadResult::Empty {}
+            } else {
+                LoadResult::Search(tracks)
+            };
+        }
+
         let path = identifier
             .strip_prefix("file://")
             .unwrap_or(identifier)
             .to_owned();
-        debug!("Local source probing file: {path}");
+        debug!("Local source probing path: {path}");
 
+        let library = self.library.clone();
         let path_clone = path.clone();
         let result =
-            tokio::task::spawn_blocking(move || LocalSource::probe_file(&path_clone)).await;
+            tokio::task::spawn_blocking(move || Self::load_path(&library.read(), &path_clone))
+                .await;
 
         match result {
-            Ok(Ok(info)) => LoadResult::Track(Track::new(info)),
+            Ok(Ok(result)) => result,
             Ok(Err(e)) => {
-                warn!("Local source: failed to probe '{path}': {e}");
+                warn!("Local source: failed to load '{path}': {e}");
                 LoadResult::Error(LoadError {
                     message: Some(format!("Failed to load local file: {e}")),
                     severity: Severity::Suspicious,
@@ -185,7 +320,9 @@ impl SourcePlugin for LocalSource {
             .unwrap_or(identifier)
             .to_owned();
 
-        if !Path::new(&path).is_file() {
+        // Encoded tracks can be crafted, so the guard applies here too.
+        if !Path::new(&path).is_file() || !self.library.read().is_allowed(Path::new(&path)) {
+            warn!("Local source: refusing to play '{path}'");
             return None;
         }
 
@@ -257,15 +394,16 @@ impl PlayableTrack for LocalTrack {
                 .map(crate::common::types::AudioFormat::from_ext);
 
             match AudioProcessor::new(source, kind, tx, cmd_rx, Some(err_tx.clone()), config) {
-                Ok(mut processor) => {
-                    std::thread::Builder::new()
-                        .name(format!("local-decoder-{}", path))
-                        .spawn(move || {
-                            if let Err(e) = processor.run() {
+                Ok(processor) => {
+                    crate::audio::worker::submit(
+                        format!("local-decoder-{}", path),
+                        processor,
+                        |res| {
+                            if let Err(e) = res {
                                 error!("LocalTrack audio processor error: {e}");
                             }
-                        })
-                        .expect("failed to spawn local decoder thread");
+                        },
+                    );
                 }
                 Err(e) => {
                     error!("LocalTrack failed to initialize processor: {e}");
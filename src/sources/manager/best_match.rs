@@ -2,15 +2,33 @@ use std::sync::Arc;
 
 use futures::stream::{FuturesOrdered, FuturesUnordered, StreamExt};
 
-use crate::sources::{manager::SourceManager, plugin::BoxedTrack};
+use crate::{
+    common::utils::same_isrc,
+    sources::{manager::SourceManager, plugin::BoxedTrack},
+};
 
 pub struct MirrorResult {
     pub track: BoxedTrack,
     pub score: f64,
     pub provider: String,
+    /// The candidate reported the same ISRC as the original track.
+    pub exact_isrc: bool,
 }
 
-fn normalize(s: &str) -> String {
+/// How far a provider's candidates are trusted. A candidate with the
+/// original's ISRC is always a guaranteed match.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Trust {
+    /// Candidates must clear the similarity thresholds.
+    Scored,
+    /// An ISRC provider: a direct lookup is trusted unless it reports a
+    /// different ISRC; search results are scored.
+    Isrc,
+    /// Any candidate is accepted.
+    Any,
+}
+
+pub(super) fn normalize(s: &str) -> String {
     let lower = s.to_lowercase();
 
     let mut stripped = String::with_capacity(lower.len());
@@ -127,6 +145,23 @@ fn score_match(
             * cfg.weight_duration
 }
 
+/// How closely `candidate` matches `original` by title, artist and length.
+pub(super) fn similarity(
+    original: &crate::protocol::tracks::TrackInfo,
+    candidate: &crate::protocol::tracks::TrackInfo,
+    cfg: &crate::config::server::BestMatchConfig,
+) -> f64 {
+    score_match(
+        &original.title,
+        &original.author,
+        original.length,
+        &candidate.title,
+        &candidate.author,
+        candidate.length,
+        cfg,
+    )
+}
+
 fn fmt_ms(ms: u64) -> String {
     let s = ms / 1_000;
     format!("{}:{:02}", s / 60, s % 60)
@@ -198,7 +233,16 @@ pub async fn resolve_scored(
     if !isrc_providers.is_empty() {
         let mut futs: FuturesOrdered<_> = isrc_providers
             .iter()
-            .map(|p| search_provider(manager, track_info, p, routeplanner.clone(), cfg, true))
+            .map(|p| {
+                search_provider(
+                    manager,
+                    track_info,
+                    p,
+                    routeplanner.clone(),
+                    cfg,
+                    Trust::Isrc,
+                )
+            })
             .collect();
 
         while let Some(result) = futs.next().await {
@@ -221,7 +265,16 @@ pub async fn resolve_scored(
     if !free_providers.is_empty() {
         let mut futs: FuturesUnordered<_> = free_providers
             .iter()
-            .map(|p| search_provider(manager, track_info, p, routeplanner.clone(), cfg, false))
+            .map(|p| {
+                search_provider(
+                    manager,
+                    track_info,
+                    p,
+                    routeplanner.clone(),
+                    cfg,
+                    Trust::Scored,
+                )
+            })
             .collect();
 
         while let Some(result) = futs.next().await {
@@ -235,7 +288,7 @@ pub async fn resolve_scored(
                     mr.score,
                 );
 
-                if mr.score >= cfg.immediate_use {
+                if mr.exact_isrc || mr.score >= cfg.immediate_use {
                     return Ok(mr.track);
                 }
 
@@ -253,7 +306,7 @@ pub async fn resolve_scored(
             provider,
             routeplanner.clone(),
             cfg,
-            true,
+            Trust::Any,
         )
         .await
         {
@@ -294,14 +347,16 @@ async fn search_provider(
     resolved_provider: &str,
     routeplanner: Option<Arc<dyn crate::routeplanner::RoutePlanner>>,
     cfg: &crate::config::server::BestMatchConfig,
-    trust_any: bool,
+    trust: Trust,
 ) -> Option<MirrorResult> {
     use crate::protocol::tracks::LoadResult;
 
-    let candidates: Vec<crate::protocol::tracks::TrackInfo> =
+    let (candidates, direct): (Vec<crate::protocol::tracks::TrackInfo>, bool) =
         match manager.load(resolved_provider, routeplanner.clone()).await {
-            LoadResult::Track(t) => vec![t.info],
-            LoadResult::Search(tracks) => tracks.into_iter().take(10).map(|t| t.info).collect(),
+            LoadResult::Track(t) => (vec![t.info], true),
+            LoadResult::Search(tracks) => {
+                (tracks.into_iter().take(10).map(|t| t.info).collect(), false)
+            }
             _ => return None,
         };
 
@@ -309,26 +364,54 @@ async fn search_provider(
         return None;
     }
 
-    let mut scored: Vec<(f64, crate::protocol::tracks::TrackInfo)> = candidates
+    let isrc = original.isrc.as_deref().filter(|i| !i.is_empty());
+    let mut scored: Vec<(bool, f64, crate::protocol::tracks::TrackInfo)> = candidates
         .into_iter()
         .map(|info| {
-            let s = score_match(
-                &original.title,
-                &original.author,
-                original.length,
-                &info.title,
-                &info.author,
-                info.length,
-                cfg,
-            );
-            (s, info)
+            let exact = isrc
+                .zip(info.isrc.as_deref())
+                .is_some_and(|(a, b)| same_isrc(a, b));
+            let s = if exact {
+                1.0
+            } else {
+                score_match(
+                    &original.title,
+                    &original.author,
+                    original.length,
+                    &info.title,
+                    &info.author,
+                    info.length,
+                    cfg,
+                )
+            };
+            (exact, s, info)
         })
         .collect();
-    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
 
-    let top_score = scored[0].0;
+    if trust == Trust::Isrc
+        && direct
+        && !scored[0].0
+        && let (Some(wanted), Some(found)) = (isrc, scored[0].2.isrc.as_deref())
+    {
+        tracing::debug!(
+            "Mirror provider '{}' returned ISRC {} instead of {}",
+            resolved_provider,
+            found,
+            wanted
+        );
+        return None;
+    }
+
+    scored.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then_with(|| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal))
+    });
+
+    let (top_exact, top_score) = (scored[0].0, scored[0].1);
 
-    let (limit, threshold): (usize, f64) = if trust_any {
+    let (limit, threshold): (usize, f64) = if top_exact {
+        (scored.iter().take_while(|c| c.0).count(), 0.0)
+    } else if trust == Trust::Any || (trust == Trust::Isrc && direct) {
         (scored.len(), 0.0)
     } else if top_score >= cfg.immediate_use {
         (1, cfg.immediate_use)
@@ -338,7 +421,7 @@ async fn search_provider(
         (3, cfg.min_similarity)
     };
 
-    for (score, info) in scored.into_iter().take(limit) {
+    for (exact_isrc, score, info) in scored.into_iter().take(limit) {
         if score < threshold {
             break;
         }
@@ -350,6 +433,7 @@ async fn search_provider(
                 track,
                 score,
                 provider: resolved_provider.to_string(),
+                exact_isrc,
             });
         }
     }
@@ -6,6 +6,7 @@ use crate::{
 };
 
 mod best_match;
+mod recommendations;
 mod registration;
 mod resolver;
 
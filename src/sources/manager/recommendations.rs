@@ -0,0 +1,218 @@
+use std::{collections::HashSet, sync::Arc};
+
+use crate::{
+    common::utils::normalize_isrc,
+    protocol::tracks::{LoadResult, Track, TrackInfo},
+    sources::manager::{SourceManager, best_match},
+};
+
+/// Keys a track is recognised by when filtering recommendations.
+fn track_keys(info: &TrackInfo) -> Vec<String> {
+    let mut keys = vec![format!("id:{}:{}", info.source_name, info.identifier)];
+    if let Some(uri) = &info.uri {
+        keys.push(format!("uri:{uri}"));
+    }
+    if let Some(isrc) = info.isrc.as_deref().map(normalize_isrc)
+        && !isrc.is_empty()
+    {
+        keys.push(format!("isrc:{isrc}"));
+    }
+    let title = best_match::normalize(&info.title);
+    let author = best_match::normalize(&info.author);
+    if !title.is_empty() && !author.is_empty() {
+        keys.push(format!("meta:{title}|{author}"));
+    }
+    keys
+}
+
+impl SourceManager {
+    /// Tracks to play after `seed`. The seed's own source is asked first;
+    /// otherwise the seed is mapped by ISRC, then by title and artist, to
+    /// another source with a recommendation prefix. The seed, `history` and
+    /// duplicates are left out.
+    pub async fn recommendations(
+        &self,
+        seed: &TrackInfo,
+        history: &[TrackInfo],
+        limit: usize,
+        routeplanner: Option<Arc<dyn crate::routeplanner::RoutePlanner>>,
+    ) -> Vec<Track> {
+        let mut seen: HashSet<String> = std::iter::once(seed)
+            .chain(history)
+            .flat_map(track_keys)
+            .collect();
+
+        let own = self
+            .sources
+            .iter()
+            .find(|s| s.name() == seed.source_name)
+            .and_then(|s| {
+                s.rec_prefixes()
+                    .first()
+                    .map(|p| format!("{p}{}", seed.identifier))
+            });
+        if let Some(identifier) = own {
+            let tracks = self
+                .load_recommendations(&identifier, &mut seen, limit, routeplanner.clone())
+                .await;
+            if !tracks.is_empty() {
+                return tracks;
+            }
+        }
+
+        match self.mapped_rec_identifier(seed, routeplanner.clone()).await {
+            Some(identifier) => {
+                self.load_recommendations(&identifier, &mut seen, limit, routeplanner)
+                    .await
+            }
+            None => Vec::new(),
+        }
+    }
+
+    async fn load_recommendations(
+        &self,
+        identifier: &str,
+        seen: &mut HashSet<String>,
+        limit: usize,
+        routeplanner: Option<Arc<dyn crate::routeplanner::RoutePlanner>>,
+    ) -> Vec<Track> {
+        tracing::debug!("Loading recommendations from '{}'", identifier);
+        let tracks = match self.load(identifier, routeplanner).await {
+            LoadResult::Playlist(playlist) => playlist.tracks,
+            LoadResult::Search(tracks) => tracks,
+            LoadResult::Track(track) => vec![track],
+            _ => return Vec::new(),
+        };
+
+        tracks
+            .into_iter()
+            .filter(|t| {
+                let keys = track_keys(&t.info);
+                let fresh = keys.iter().all(|k| !seen.contains(k));
+                seen.extend(keys);
+                fresh
+            })
+            .take(limit)
+            .collect()
+    }
+
+    /// Finds the seed on another source with a recommendation prefix, by
+    /// ISRC first and then by title and artist.
+    async fn mapped_rec_identifier(
+        &self,
+        seed: &TrackInfo,
+        routeplanner: Option<Arc<dyn crate::routeplanner::RoutePlanner>>,
+    ) -> Option<String> {
+        let others = self
+            .sources
+            .iter()
+            .filter(|s| s.name() != seed.source_name && !s.rec_prefixes().is_empty());
+
+        if let Some(isrc) = seed.isrc.as_deref().filter(|i| !i.is_empty()) {
+            for source in others.clone() {
+                let Some(isrc_prefix) = source.isrc_prefixes().first().copied() else {
+                    continue;
+                };
+                if let LoadResult::Track(track) = source
+                    .load(&format!("{isrc_prefix}{isrc}"), routeplanner.clone())
+                    .await
+                {
+                    return Some(format!(
+                        "{}{}",
+                        source.rec_prefixes()[0],
+                        track.info.identifier
+                    ));
+                }
+            }
+        }
+
+        let cfg = self
+            .mirrors
+            .as_ref()
+            .map(|m| m.best_match.clone())
+            .unwrap_or_default();
+        let query = format!("{} {}", seed.title, seed.author);
+        for source in others {
+            let Some(search_prefix) = source.search_prefixes().first().copied() else {
+                continue;
+            };
+            let candidates = match source
+                .load(&format!("{search_prefix}{query}"), routeplanner.clone())
+                .await
+            {
+                LoadResult::Search(tracks) => tracks,
+                LoadResult::Track(track) => vec![track],
+                _ => continue,
+            };
+            if let Some(track) = candidates
+                .into_iter()
+                .take(5)
+                .find(|t| best_match::similarity(seed, &t.info, &cfg) >= cfg.high_confidence)
+            {
+                return Some(format!(
+                    "{}{}",
+                    source.rec_prefixes()[0],
+                    track.info.identifier
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_keys_match_across_sources() {
+        let seed = TrackInfo {
+            identifier: "3n3Ppam7vgaVa1iaRUc9Lp".to_owned(),
+            title: "Mr. Brightside".to_owned(),
+            author: "The Killers".to_owned(),
+            isrc: Some("USIR20400274".to_owned()),
+            source_name: "spotify".to_owned(),
+            ..Default::default()
+        };
+        let same_isrc = TrackInfo {
+            identifier: "gGdGFtwCNBE".to_owned(),
+            title: "The Killers - Mr. Brightside (Official Music Video)".to_owned(),
+            author: "TheKillersVEVO".to_owned(),
+            isrc: Some("US-IR2-04-00274".to_owned()),
+            source_name: "youtube".to_owned(),
+            ..Default::default()
+        };
+        let same_meta = TrackInfo {
+            identifier: "3135556".to_owned(),
+            title: "Mr Brightside".to_owned(),
+            author: "The Killers".to_owned(),
+            source_name: "deezer".to_owned(),
+            ..Default::default()
+        };
+        let other = TrackInfo {
+            identifier: "0eGsygTp906u18L0Oimnem".to_owned(),
+            title: "Somebody Told Me".to_owned(),
+            author: "The Killers".to_owned(),
+            source_name: "spotify".to_owned(),
+            ..Default::default()
+        };
+
+        let seen: HashSet<String> = track_keys(&seed).into_iter().collect();
+        let known = |info: &TrackInfo| track_keys(info).iter().any(|k| seen.contains(k));
+        assert!(known(&same_isrc));
+        assert!(known(&same_meta));
+        assert!(!known(&other));
+    }
+
+    #[test]
+    fn test_untitled_tracks_are_not_matched_by_metadata() {
+        let untitled = |identifier: &str| TrackInfo {
+            identifier: identifier.to_owned(),
+            source_name: "http".to_owned(),
+            ..Default::default()
+        };
+        let seen: HashSet<String> = track_keys(&untitled("a")).into_iter().collect();
+        assert!(!track_keys(&untitled("b")).iter().any(|k| seen.contains(k)));
+    }
+}
@@ -526,8 +526,10 @@ fn register_extra_sources(sources: &mut Vec<BoxedSource>, config: &crate::config
     }
 
     // Local Source
-    if config.sources.local.as_ref().is_some_and(|c| c.enabled) {
+    if let Some(c) = config.sources.local.as_ref()
+        && c.enabled
+    {
         tracing::info!("Loaded source: local");
-        sources.push(Box::new(LocalSource::new()));
+        sources.push(Box::new(LocalSource::new(c.clone())));
     }
 }
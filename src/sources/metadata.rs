@@ -0,0 +1,240 @@
+//! Tag reading shared by the local and HTTP sources, and the store behind
+//! `/v4/artwork/{id}` for embedded cover images.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use symphonia::core::{
+    meta::{MetadataRevision, StandardTagKey, StandardVisualKey},
+    probe::ProbeResult,
+};
+
+use crate::protocol::tracks::{Track, TrackInfo};
+
+/// In-memory pictures (from HTTP files) kept before the oldest are dropped.
+const MAX_MEMORY_ARTWORK: usize = 256;
+
+#[derive(Debug, Clone)]
+pub struct Picture {
+    pub media_type: String,
+    pub data: Bytes,
+}
+
+/// Tags read from a file's metadata.
+#[derive(Debug, Clone, Default)]
+pub struct FileTags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub track_number: Option<u32>,
+    pub genre: Option<String>,
+    pub isrc: Option<String>,
+    pub picture: Option<Picture>,
+}
+
+impl FileTags {
+    /// Reads the tags found while probing (ID3v2, APE) and those in the
+    /// container itself; the container wins where both are set.
+    pub fn read(probed: &mut ProbeResult) -> Self {
+        let mut tags = Self::default();
+        if let Some(meta) = probed.metadata.get()
+            && let Some(revision) = meta.current()
+        {
+            tags.apply(revision);
+        }
+        if let Some(revision) = probed.format.metadata().current() {
+            tags.apply(revision);
+        }
+        tags
+    }
+
+    fn apply(&mut self, revision: &MetadataRevision) {
+        for tag in revision.tags() {
+            let value = tag.value.to_string().trim().to_owned();
+            if value.is_empty() {
+                continue;
+            }
+            match tag.std_key {
+                Some(StandardTagKey::TrackTitle) => self.title = Some(value),
+                Some(StandardTagKey::Artist) => self.artist = Some(value),
+                Some(StandardTagKey::AlbumArtist) if self.artist.is_none() => {
+                    self.artist = Some(value)
+                }
+                Some(StandardTagKey::Album) => self.album = Some(value),
+                // "3/12" in ID3, plain "3" elsewhere.
+                Some(StandardTagKey::TrackNumber) => {
+                    self.track_number = value.split('/').next().and_then(|n| n.parse().ok())
+                }
+                Some(StandardTagKey::Genre) => self.genre = Some(value),
+                Some(StandardTagKey::IdentIsrc) => self.isrc = Some(value.to_uppercase()),
+                _ => {}
+            }
+        }
+
+        let visuals = revision.visuals();
+        if let Some(visual) = visuals
+            .iter()
+            .find(|v| v.usage == Some(StandardVisualKey::FrontCover))
+            .or_else(|| visuals.first())
+        {
+            self.picture = Some(Picture {
+                media_type: visual.media_type.clone(),
+                data: Bytes::copy_from_slice(&visual.data),
+            });
+        }
+    }
+
+    /// Fills `isrc` and `artworkUrl`; the picture is registered under `key`.
+    pub fn apply_to(&self, info: &mut TrackInfo, key: ArtworkKey) {
+        if info.isrc.is_none() {
+            info.isrc = self.isrc.clone();
+        }
+        if info.artwork_url.is_none()
+            && let Some(picture) = &self.picture
+        {
+            info.artwork_url = Some(artwork_store().insert(key, picture));
+        }
+    }
+
+    /// A track for `info` with these tags under `pluginInfo`.
+    pub fn track(&self, info: TrackInfo) -> Track {
+        let mut track = Track::new(info);
+        track.plugin_info = self.plugin_info();
+        track
+    }
+
+    /// The extra tags under `pluginInfo`, in the key style other sources use.
+    pub fn plugin_info(&self) -> serde_json::Value {
+        let mut info = serde_json::Map::new();
+        if let Some(album) = &self.album {
+            info.insert("albumName".into(), album.clone().into());
+        }
+        if let Some(number) = self.track_number {
+            info.insert("trackNumber".into(), number.into());
+        }
+        if let Some(genre) = &self.genre {
+            info.insert("genre".into(), genre.clone().into());
+        }
+        serde_json::Value::Object(info)
+    }
+}
+
+/// Where a picture can be found again.
+pub enum ArtworkKey<'a> {
+    /// Re-read from the file on request, so large libraries cost no memory.
+    File(&'a Path),
+    /// Kept in memory, keyed by the URL it came from.
+    Memory(&'a str),
+}
+
+enum StoredArtwork {
+    File(PathBuf),
+    Memory(Picture),
+}
+
+#[derive(Default)]
+pub struct ArtworkStore {
+    entries: Mutex<(HashMap<String, StoredArtwork>, VecDeque<String>)>,
+}
+
+impl ArtworkStore {
+    /// Registers a picture and returns its `/v4/artwork/{id}` path. Ids are
+    /// derived from the key, so the same file always gets the same id.
+    fn insert(&self, key: ArtworkKey, picture: &Picture) -> String {
+        let mut hasher = DefaultHasher::new();
+        let stored = match key {
+            ArtworkKey::File(path) => {
+                path.hash(&mut hasher);
+                StoredArtwork::File(path.to_path_buf())
+            }
+            ArtworkKey::Memory(url) => {
+                url.hash(&mut hasher);
+                StoredArtwork::Memory(picture.clone())
+            }
+        };
+        let id = format!("{:016x}", hasher.finish());
+
+        let mut guard = self.entries.lock();
+        let (entries, memory_order) = &mut *guard;
+        if matches!(stored, StoredArtwork::Memory(_)) && !entries.contains_key(&id) {
+            memory_order.push_back(id.clone());
+            if memory_order.len() > MAX_MEMORY_ARTWORK
+                && let Some(oldest) = memory_order.pop_front()
+            {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(id.clone(), stored);
+
+        format!("/v4/artwork/{id}")
+    }
+
+    /// Looks a picture up by id. File-backed entries are re-read, so call
+    /// this off the async runtime.
+    pub fn get(&self, id: &str) -> Option<Picture> {
+        let path = match self.entries.lock().0.get(id)? {
+            StoredArtwork::Memory(picture) => return Some(picture.clone()),
+            StoredArtwork::File(path) => path.clone(),
+        };
+        crate::sources::local::LocalSource::read_tags(&path)
+            .ok()
+            .and_then(|tags| tags.picture)
+    }
+}
+
+static ARTWORK_STORE: OnceLock<ArtworkStore> = OnceLock::new();
+
+pub fn artwork_store() -> &'static ArtworkStore {
+    ARTWORK_STORE.get_or_init(ArtworkStore::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_to_registers_artwork() {
+        let tags = FileTags {
+            album: Some("Night Songs".to_owned()),
+            track_number: Some(3),
+            isrc: Some("USRC17607839".to_owned()),
+            picture: Some(Picture {
+                media_type: "image/png".to_owned(),
+                data: Bytes::from_static(b"png"),
+            }),
+            ..Default::default()
+        };
+        let mut info = TrackInfo {
+            identifier: "https://example.com/a.mp3".to_owned(),
+            is_seekable: true,
+            author: "Band".to_owned(),
+            length: 0,
+            is_stream: false,
+            position: 0,
+            title: "Blue".to_owned(),
+            uri: None,
+            source_name: "http".to_owned(),
+            artwork_url: None,
+            isrc: None,
+        };
+        tags.apply_to(&mut info, ArtworkKey::Memory("https://example.com/a.mp3"));
+
+        assert_eq!(info.isrc.as_deref(), Some("USRC17607839"));
+        let url = info.artwork_url.unwrap();
+        let id = url.strip_prefix("/v4/artwork/").unwrap();
+        let picture = artwork_store().get(id).unwrap();
+        assert_eq!(picture.media_type, "image/png");
+        assert_eq!(&picture.data[..], b"png");
+
+        assert_eq!(
+            tags.plugin_info(),
+            serde_json::json!({ "albumName": "Night Songs", "trackNumber": 3 })
+        );
+    }
+}
@@ -86,19 +86,20 @@ impl PlayableTrack for MixcloudTrack {
             .expect("failed to spawn mixcloud setup task");
 
             match setup_res {
-                Ok(mut processor) => {
-                    std::thread::Builder::new()
-                        .name(format!("mixcloud-decoder-{}", uri))
-                        .spawn(move || {
-                            if let Err(e) = processor.run() {
+                Ok(processor) => {
+                    crate::audio::worker::submit(
+                        format!("mixcloud-decoder-{}", uri),
+                        processor,
+                        move |res| {
+                            if let Err(e) = res {
                                 tracing::error!(
                                     "Mixcloud audio processor error for {}: {}",
                                     uri,
                                     e
                                 );
                             }
-                        })
-                        .expect("failed to spawn mixcloud decoder thread");
+                        },
+                    );
                 }
                 Err(e) => {
                     tracing::error!("Mixcloud failed to initialize processor for {}: {}", uri, e);
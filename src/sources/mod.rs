@@ -1,5 +1,6 @@
 // Core management and traits
 pub mod manager;
+pub mod metadata;
 pub mod plugin;
 
 pub use manager::SourceManager;
@@ -4,9 +4,9 @@ use async_trait::async_trait;
 use flume::{Receiver, Sender};
 
 use crate::{
-    audio::{AudioFrame, processor::DecoderCommand},
+    audio::{AudioFrame, playback::LiveEdge, processor::DecoderCommand},
     config::HttpProxyConfig,
-    protocol::tracks::{LoadResult, SearchResult},
+    protocol::tracks::{Chapter, LoadResult, SearchResult},
     routeplanner::RoutePlanner,
 };
 
@@ -20,10 +20,33 @@ pub type DecoderOutput = (
     Receiver<String>,
 );
 
+/// A single HTTP resource holding a track's audio in its original container.
+pub struct DirectStream {
+    pub url: String,
+    pub local_addr: Option<std::net::IpAddr>,
+    pub proxy: Option<HttpProxyConfig>,
+}
+
 /// A track capable of initializing its own decoding process.
 pub trait PlayableTrack: Send + Sync {
     /// Starts the decoding process with the provided player configuration.
     fn start_decoding(&self, config: crate::config::player::PlayerConfig) -> DecoderOutput;
+
+    /// Returns the original media URL when the track can be fetched as-is.
+    /// Segmented or encrypted streams return `None`.
+    fn direct_stream(&self) -> Option<DirectStream> {
+        None
+    }
+
+    /// Chapter markers known for this track, empty when it has none.
+    fn chapters(&self) -> Vec<Chapter> {
+        Vec::new()
+    }
+
+    /// Live edge the track reports while it plays as a live stream.
+    fn live_edge(&self) -> Option<Arc<LiveEdge>> {
+        None
+    }
 }
 
 pub type BoxedTrack = Box<dyn PlayableTrack>;
@@ -10,7 +10,7 @@ use super::{
     track::{SoundCloudStreamKind, SoundCloudTrack},
 };
 use crate::{
-    protocol::tracks::{LoadResult, PlaylistData, PlaylistInfo, Track, TrackInfo},
+    protocol::tracks::{LoadResult, PlaylistData, PlaylistInfo, SearchResult, Track, TrackInfo},
     sources::{SourcePlugin, plugin::PlayableTrack},
 };
 
@@ -64,6 +64,13 @@ fn user_url_re() -> &'static Regex {
     })
 }
 
+/// Lowercased URL path, so `www.`/`m.` hosts and query strings still match a permalink.
+fn permalink_path(url: &str) -> Option<String> {
+    reqwest::Url::parse(url)
+        .ok()
+        .map(|u| u.path().trim_end_matches('/').to_ascii_lowercase())
+}
+
 fn search_url_re() -> &'static Regex {
     static RE: OnceLock<Regex> = OnceLock::new();
     RE.get_or_init(|| {
@@ -427,6 +434,122 @@ impl SoundCloudSource {
         }
     }
 
+    /// Universal search; the collection mixes tracks, users and sets.
+    async fn search_all(&self, query: &str, types: &[String]) -> Option<SearchResult> {
+        let client_id = self.token_tracker.get_client_id().await?;
+
+        let req_url = format!(
+            "{}/search?q={}&client_id={}&limit={}&offset=0",
+            BASE_URL,
+            urlencoding::encode(query),
+            client_id,
+            self.config.search_limit
+        );
+
+        let resp = match self.client.get(&req_url).send().await {
+            Ok(r) => r,
+            Err(e) => {
+                error!("SoundCloud search error: {}", e);
+                return None;
+            }
+        };
+        if resp.status().as_u16() == 401 {
+            self.token_tracker.invalidate().await;
+            return None;
+        }
+        if !resp.status().is_success() {
+            return None;
+        }
+        let json: Value = resp.json().await.ok()?;
+
+        let wants = |kind: &str| types.is_empty() || types.iter().any(|t| t == kind);
+        let mut result = SearchResult {
+            tracks: Vec::new(),
+            albums: Vec::new(),
+            artists: Vec::new(),
+            playlists: Vec::new(),
+            texts: Vec::new(),
+            plugin: serde_json::json!({}),
+        };
+
+        for item in json
+            .get("collection")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            match item.get("kind").and_then(|v| v.as_str()) {
+                Some("track") if wants("track") => {
+                    if let Ok(track) = self.parse_track(item) {
+                        result.tracks.push(track);
+                    }
+                }
+                Some("user") if wants("artist") => {
+                    result.artists.extend(Self::parse_entity(item, "artist"));
+                }
+                Some("playlist") => {
+                    if item.get("is_album").and_then(|v| v.as_bool()) == Some(true) {
+                        if wants("album") {
+                            result.albums.extend(Self::parse_entity(item, "album"));
+                        }
+                    } else if wants("playlist") {
+                        result
+                            .playlists
+                            .extend(Self::parse_entity(item, "playlist"));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Some(result)
+    }
+
+    /// A user or set from search results, in the same shape `/v4/loadsearch`
+    /// uses for every source.
+    fn parse_entity(json: &Value, kind: &str) -> Option<PlaylistData> {
+        let url = json.get("permalink_url").and_then(|v| v.as_str())?;
+        let (name, author, artwork) = if kind == "artist" {
+            let username = json.get("username").and_then(|v| v.as_str())?.to_owned();
+            (username.clone(), username, json.get("avatar_url"))
+        } else {
+            let author = json
+                .get("user")
+                .and_then(|u| u.get("username"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("Unknown")
+                .to_owned();
+            let artwork = json
+                .get("artwork_url")
+                .filter(|v| !v.is_null())
+                .or_else(|| json.get("user").and_then(|u| u.get("avatar_url")));
+            (
+                json.get("title").and_then(|v| v.as_str())?.to_owned(),
+                author,
+                artwork,
+            )
+        };
+        let artwork_url = artwork
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.replace("-large", "-t500x500"));
+
+        Some(PlaylistData {
+            info: PlaylistInfo {
+                name,
+                selected_track: -1,
+            },
+            plugin_info: serde_json::json!({
+              "type": kind,
+              "url": url,
+              "artworkUrl": artwork_url,
+              "author": author,
+              "totalTracks": json.get("track_count").and_then(|v| v.as_u64()).unwrap_or(0)
+            }),
+            tracks: Vec::new(),
+        })
+    }
+
     async fn load_single_track(&self, url: &str) -> LoadResult {
         let client_id = match self.token_tracker.get_client_id().await {
             Some(id) => id,
@@ -885,12 +1008,36 @@ impl SourcePlugin for SoundCloudSource {
         }
 
         if track_url_re().is_match(&url) {
+            // `?in=user/sets/name` opens the track inside that set.
+            if let Some(set) = crate::common::utils::query_param(&url, "in")
+                && set.contains("/sets/")
+                && let LoadResult::Playlist(mut playlist) = self
+                    .load_playlist(&format!("https://soundcloud.com/{}", set.trim_matches('/')))
+                    .await
+            {
+                let selected = permalink_path(&url);
+                playlist.select(|t| t.info.uri.as_deref().and_then(permalink_path) == selected);
+                return LoadResult::Playlist(playlist);
+            }
             return self.load_single_track(&url).await;
         }
 
         LoadResult::Empty {}
     }
 
+    async fn load_search(
+        &self,
+        query: &str,
+        types: &[String],
+        _routeplanner: Option<Arc<dyn crate::routeplanner::RoutePlanner>>,
+    ) -> Option<SearchResult> {
+        let prefix = self
+            .search_prefixes()
+            .into_iter()
+            .find(|p| query.starts_with(p))?;
+        self.search_all(query[prefix.len()..].trim(), types).await
+    }
+
     async fn get_track(
         &self,
         identifier: &str,
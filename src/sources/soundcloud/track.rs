@@ -8,7 +8,7 @@ use crate::{
         processor::{AudioProcessor, DecoderCommand},
     },
     config::HttpProxyConfig,
-    sources::plugin::{DecoderOutput, PlayableTrack},
+    sources::plugin::{DecoderOutput, DirectStream, PlayableTrack},
 };
 
 /// What kind of SoundCloud stream this track uses.
@@ -181,6 +181,19 @@ impl PlayableTrack for SoundCloudTrack {
 
         (rx, cmd_tx, err_rx)
     }
+
+    fn direct_stream(&self) -> Option<DirectStream> {
+        match self.kind {
+            SoundCloudStreamKind::ProgressiveMp3 | SoundCloudStreamKind::ProgressiveAac => {
+                Some(DirectStream {
+                    url: self.stream_url.clone(),
+                    local_addr: self.local_addr,
+                    proxy: self.proxy.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
 }
 
 fn run_processor(
@@ -193,15 +206,16 @@ fn run_processor(
     identifier: String,
 ) {
     match AudioProcessor::new(reader, kind, tx, cmd_rx, Some(err_tx.clone()), config) {
-        Ok(mut p) => {
-            std::thread::Builder::new()
-                .name(format!("soundcloud-decoder-{}", identifier))
-                .spawn(move || {
-                    if let Err(e) = p.run() {
+        Ok(p) => {
+            crate::audio::worker::submit(
+                format!("soundcloud-decoder-{}", identifier),
+                p,
+                move |res| {
+                    if let Err(e) = res {
                         error!("SoundCloud AudioProcessor error for {}: {}", identifier, e);
                     }
-                })
-                .expect("failed to spawn soundcloud decoder thread");
+                },
+            );
         }
         Err(e) => {
             error!(
@@ -15,6 +15,16 @@ pub mod recommendations;
 pub mod search;
 pub mod token;
 
+/// Album and playlist links shared from a track carry `?highlight=spotify:track:<id>`.
+fn select_highlighted(result: &mut LoadResult, url: &str) {
+    if let LoadResult::Playlist(playlist) = result
+        && let Some(highlight) = crate::common::utils::query_param(url, "highlight")
+    {
+        let id = highlight.rsplit(':').next().unwrap_or_default();
+        playlist.select(|t| t.info.identifier == id);
+    }
+}
+
 fn url_regex() -> &'static Regex {
     static REGEX: OnceLock<Regex> = OnceLock::new();
     REGEX.get_or_init(|| {
@@ -112,6 +122,18 @@ impl SpotifySource {
         .await
     }
 
+    /// Searches with the `isrc:` filter; results only count as an exact hit
+    /// when their own ISRC agrees.
+    async fn search_isrc(&self, isrc: &str) -> LoadResult {
+        match self
+            .get_autocomplete(&format!("isrc:{isrc}"), &["track".to_owned()])
+            .await
+        {
+            Some(res) => LoadResult::from_isrc_search(res.tracks, isrc),
+            None => LoadResult::Empty {},
+        }
+    }
+
     pub fn base_request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
         builder.header(reqwest::header::USER_AGENT, "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.6998.178 Spotify/1.2.65.255 Safari/537.36")
     }
@@ -127,6 +149,10 @@ impl SourcePlugin for SpotifySource {
         self.search_prefixes()
             .iter()
             .any(|p| identifier.starts_with(p))
+            || self
+                .isrc_prefixes()
+                .iter()
+                .any(|p| identifier.starts_with(p))
             || self
                 .rec_prefixes()
                 .iter()
@@ -138,6 +164,10 @@ impl SourcePlugin for SpotifySource {
         vec!["spsearch:"]
     }
 
+    fn isrc_prefixes(&self) -> Vec<&str> {
+        vec!["spisrc:"]
+    }
+
     fn is_mirror(&self) -> bool {
         true
     }
@@ -169,6 +199,14 @@ impl SourcePlugin for SpotifySource {
             };
         }
 
+        if let Some(prefix) = self
+            .isrc_prefixes()
+            .into_iter()
+            .find(|p| identifier.starts_with(p))
+        {
+            return self.search_isrc(&identifier[prefix.len()..]).await;
+        }
+
         if let Some(prefix) = self
             .rec_prefixes()
             .into_iter()
@@ -220,7 +258,7 @@ impl SourcePlugin for SpotifySource {
                     }
                 }
                 "album" => {
-                    return metadata::SpotifyMetadata::fetch_album(
+                    let mut result = metadata::SpotifyMetadata::fetch_album(
                         &self.client,
                         &self.token_tracker,
                         id,
@@ -230,9 +268,11 @@ impl SourcePlugin for SpotifySource {
                         isrc_binary_regex(),
                     )
                     .await;
+                    select_highlighted(&mut result, identifier);
+                    return result;
                 }
                 "playlist" => {
-                    return metadata::SpotifyMetadata::fetch_playlist(
+                    let mut result = metadata::SpotifyMetadata::fetch_playlist(
                         &self.client,
                         &self.token_tracker,
                         id,
@@ -242,6 +282,8 @@ impl SourcePlugin for SpotifySource {
                         isrc_binary_regex(),
                     )
                     .await;
+                    select_highlighted(&mut result, identifier);
+                    return result;
                 }
                 "artist" => {
                     return metadata::SpotifyMetadata::fetch_artist(
@@ -69,18 +69,19 @@ impl PlayableTrack for TidalTrack {
             .expect("TidalTrack: reader setup spawn_blocking failed");
 
             match setup_res {
-                Ok(mut processor) => {
-                    std::thread::Builder::new()
-                        .name(format!("tidal-decoder-{}", identifier))
-                        .spawn(move || {
-                            if let Err(e) = processor.run() {
+                Ok(processor) => {
+                    crate::audio::worker::submit(
+                        format!("tidal-decoder-{}", identifier),
+                        processor,
+                        move |res| {
+                            if let Err(e) = res {
                                 error!(
                                     "TidalTrack audio processor error for {}: {}",
                                     identifier, e
                                 );
                             }
-                        })
-                        .expect("failed to spawn tidal decoder thread");
+                        },
+                    );
                 }
                 Err(e) => {
                     error!(
@@ -202,6 +202,7 @@ impl SourcePlugin for TwitchSource {
             stream_url,
             local_addr,
             proxy: self.proxy.clone(),
+            live_edge: Default::default(),
         }))
     }
 
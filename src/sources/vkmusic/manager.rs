@@ -373,6 +373,7 @@ impl SourcePlugin for VkMusicSource {
 
     fn can_handle(&self, id: &str) -> bool {
         self.search_prefixes().iter().any(|p| id.starts_with(p))
+            || self.isrc_prefixes().iter().any(|p| id.starts_with(p))
             || self.rec_prefixes().iter().any(|p| id.starts_with(p))
             || self.track_re.is_match(id)
             || self.playlist_z_re.is_match(id)
@@ -385,6 +386,10 @@ impl SourcePlugin for VkMusicSource {
         vec!["vksearch:"]
     }
 
+    fn isrc_prefixes(&self) -> Vec<&str> {
+        vec!["vkisrc:"]
+    }
+
     fn rec_prefixes(&self) -> Vec<&str> {
         vec!["vkrec:"]
     }
@@ -402,6 +407,19 @@ impl SourcePlugin for VkMusicSource {
             return self.search(identifier.strip_prefix(prefix).unwrap()).await;
         }
 
+        // VK has no ISRC filter; the code is searched as text.
+        if let Some(prefix) = self
+            .isrc_prefixes()
+            .into_iter()
+            .find(|p| identifier.starts_with(p))
+        {
+            let isrc = identifier.strip_prefix(prefix).unwrap();
+            return match self.search(isrc).await {
+                LoadResult::Search(tracks) => LoadResult::from_isrc_search(tracks, isrc),
+                other => other,
+            };
+        }
+
         if let Some(prefix) = self
             .rec_prefixes()
             .into_iter()
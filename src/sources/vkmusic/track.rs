@@ -2,7 +2,7 @@ use std::net::IpAddr;
 
 use crate::sources::{
     http::HttpTrack,
-    plugin::{DecoderOutput, PlayableTrack},
+    plugin::{DecoderOutput, DirectStream, PlayableTrack},
 };
 
 pub struct VkMusicTrack {
@@ -20,4 +20,12 @@ impl PlayableTrack for VkMusicTrack {
         }
         .start_decoding(config)
     }
+
+    fn direct_stream(&self) -> Option<DirectStream> {
+        Some(DirectStream {
+            url: self.stream_url.clone(),
+            local_addr: self.local_addr,
+            proxy: self.proxy.clone(),
+        })
+    }
 }
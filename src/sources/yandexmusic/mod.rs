@@ -100,6 +100,22 @@ impl YandexMusicSource {
         LoadResult::Search(tracks)
     }
 
+    /// Yandex has no ISRC filter, so the code is searched as text.
+    async fn search_isrc(&self, isrc: &str) -> LoadResult {
+        let data = match self
+            .api_request(
+                "/search",
+                Some(&[("text", isrc), ("type", "track"), ("page", "0")]),
+            )
+            .await
+        {
+            Some(d) => d,
+            None => return LoadResult::Empty {},
+        };
+
+        LoadResult::from_isrc_search(self.parse_tracks(&data["tracks"]["results"]), isrc)
+    }
+
     async fn load_search_internal(
         &self,
         query: &str,
@@ -468,6 +484,10 @@ impl SourcePlugin for YandexMusicSource {
         self.search_prefixes()
             .iter()
             .any(|p| identifier.starts_with(p))
+            || self
+                .isrc_prefixes()
+                .iter()
+                .any(|p| identifier.starts_with(p))
             || self
                 .rec_prefixes()
                 .iter()
@@ -481,6 +501,10 @@ impl SourcePlugin for YandexMusicSource {
         vec!["ymsearch:"]
     }
 
+    fn isrc_prefixes(&self) -> Vec<&str> {
+        vec!["ymisrc:"]
+    }
+
     fn rec_prefixes(&self) -> Vec<&str> {
         vec!["ymrec:"]
     }
@@ -498,6 +522,16 @@ impl SourcePlugin for YandexMusicSource {
             return self.search(identifier.strip_prefix(prefix).unwrap()).await;
         }
 
+        if let Some(prefix) = self
+            .isrc_prefixes()
+            .into_iter()
+            .find(|p| identifier.starts_with(p))
+        {
+            return self
+                .search_isrc(identifier.strip_prefix(prefix).unwrap())
+                .await;
+        }
+
         if let Some(prefix) = self
             .rec_prefixes()
             .into_iter()
@@ -0,0 +1,96 @@
+use std::collections::{HashMap, VecDeque};
+
+use parking_lot::Mutex;
+
+use crate::protocol::tracks::{Chapter, Track};
+
+/// Videos whose chapters are remembered between loading and playing them.
+const MAX_ENTRIES: usize = 512;
+
+/// Chapters parsed while loading a video, kept until it is played.
+///
+/// Encoded tracks don't carry `pluginInfo`, so the chapters found in the
+/// load response are held here by video id and handed to the playable track
+/// instead of fetching the video again at track start.
+#[derive(Default)]
+pub struct ChapterCache {
+    inner: Mutex<Entries>,
+}
+
+#[derive(Default)]
+struct Entries {
+    by_id: HashMap<String, Vec<Chapter>>,
+    order: VecDeque<String>,
+}
+
+impl ChapterCache {
+    /// Remembers the chapters listed in a loaded track's `pluginInfo`.
+    pub fn remember(&self, track: &Track) {
+        let Some(chapters) = track
+            .plugin_info
+            .get("chapters")
+            .cloned()
+            .and_then(|v| serde_json::from_value::<Vec<Chapter>>(v).ok())
+            .filter(|c| !c.is_empty())
+        else {
+            return;
+        };
+
+        let mut entries = self.inner.lock();
+        let id = track.info.identifier.clone();
+        if entries.by_id.insert(id.clone(), chapters).is_none() {
+            entries.order.push_back(id);
+        }
+        while entries.order.len() > MAX_ENTRIES {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.by_id.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn get(&self, video_id: &str) -> Vec<Chapter> {
+        self.inner
+            .lock()
+            .by_id
+            .get(video_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::tracks::TrackInfo;
+
+    fn track(id: &str) -> Track {
+        let mut track = Track::new(TrackInfo {
+            identifier: id.to_string(),
+            is_seekable: true,
+            author: String::new(),
+            length: 60_000,
+            is_stream: false,
+            position: 0,
+            title: String::new(),
+            uri: None,
+            artwork_url: None,
+            isrc: None,
+            source_name: "youtube".to_string(),
+        });
+        track.plugin_info = serde_json::json!({
+            "chapters": [{ "title": "Intro", "start": 0, "end": 60_000, "thumbnail": null }]
+        });
+        track
+    }
+
+    #[test]
+    fn test_oldest_video_is_evicted_first() {
+        let cache = ChapterCache::default();
+        for i in 0..=MAX_ENTRIES {
+            cache.remember(&track(&i.to_string()));
+        }
+        assert!(cache.get("0").is_empty());
+        assert_eq!(cache.get("1")[0].title, "Intro");
+        assert_eq!(cache.get(&MAX_ENTRIES.to_string()).len(), 1);
+    }
+}
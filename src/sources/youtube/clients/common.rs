@@ -401,6 +401,60 @@ pub async fn make_player_request(opts: PlayerRequestOptions<'_>) -> AnyResult<Va
     Ok(res.json().await?)
 }
 
+/// Visitor data from either a full InnerTube context or the bare
+/// `{ "visitorData": ... }` object the source passes to clients.
+fn context_visitor_data(context: &Value) -> Option<&str> {
+    context
+        .get("client")
+        .and_then(|c| c.get("visitorData"))
+        .and_then(|v| v.as_str())
+        .or_else(|| context.get("visitorData").and_then(|v| v.as_str()))
+}
+
+/// Posts `request` to an InnerTube endpoint such as `search` or `browse`,
+/// with the calling client's context added to the body.
+pub async fn make_innertube_request(
+    http: &reqwest::Client,
+    config: &ClientConfig<'_>,
+    api: &str,
+    origin: Option<&str>,
+    endpoint: &str,
+    context: &Value,
+    request: Value,
+) -> AnyResult<Value> {
+    let visitor_data = context_visitor_data(context);
+
+    let mut body = json!({ "context": config.build_context(visitor_data) });
+    if let (Some(body), Value::Object(request)) = (body.as_object_mut(), request) {
+        body.extend(request);
+    }
+
+    let url = format!("{}/youtubei/v1/{}?prettyPrint=false", api, endpoint);
+
+    let mut req = http
+        .post(&url)
+        .header("User-Agent", config.user_agent)
+        .header("X-YouTube-Client-Name", config.client_id)
+        .header("X-YouTube-Client-Version", config.client_version)
+        .header("X-Goog-Api-Format-Version", "2");
+
+    if let Some(vd) = visitor_data {
+        req = req.header("X-Goog-Visitor-Id", vd);
+    }
+
+    if let Some(orig_url) = origin {
+        req = req.header("Origin", orig_url);
+    }
+
+    let res = req.json(&body).send().await?;
+    let status = res.status();
+    if !status.is_success() {
+        return Err(format!("{} request failed (status={})", endpoint, status).into());
+    }
+
+    Ok(res.json().await?)
+}
+
 pub async fn make_next_request(
     http: &reqwest::Client,
     config: &ClientConfig<'_>,
@@ -61,6 +61,38 @@ pub trait YouTubeClient: Send + Sync {
         oauth: Arc<YouTubeOAuth>,
     ) -> AnyResult<Option<(Vec<Track>, String)>>;
 
+    /// Unfiltered search returning the raw InnerTube response, so channels,
+    /// playlists and albums come back alongside videos.
+    async fn search_raw(
+        &self,
+        _query: &str,
+        _context: &Value,
+        _oauth: Arc<YouTubeOAuth>,
+    ) -> AnyResult<Option<Value>> {
+        Ok(None)
+    }
+
+    /// Raw InnerTube `browse` call. `request` carries either a `browseId`
+    /// (and optional `params`) or a `continuation` token.
+    async fn browse(
+        &self,
+        _request: Value,
+        _context: &Value,
+        _oauth: Arc<YouTubeOAuth>,
+    ) -> AnyResult<Option<Value>> {
+        Ok(None)
+    }
+
+    /// Resolves a channel URL such as `/@handle` to its `UC...` browse id.
+    async fn resolve_browse_id(
+        &self,
+        _url: &str,
+        _context: &Value,
+        _oauth: Arc<YouTubeOAuth>,
+    ) -> AnyResult<Option<String>> {
+        Ok(None)
+    }
+
     async fn get_player_body(
         &self,
         _track_id: &str,
@@ -5,7 +5,7 @@ use serde_json::{Value, json};
 
 use super::{
     YouTubeClient,
-    common::{INNERTUBE_API, resolve_format_url, select_best_audio_format},
+    common::{INNERTUBE_API, make_innertube_request, resolve_format_url, select_best_audio_format},
 };
 use crate::{
     common::types::AnyResult,
@@ -176,6 +176,70 @@ impl YouTubeClient for WebClient {
         Ok(tracks)
     }
 
+    async fn search_raw(
+        &self,
+        query: &str,
+        context: &Value,
+        _oauth: Arc<YouTubeOAuth>,
+    ) -> AnyResult<Option<Value>> {
+        make_innertube_request(
+            &self.http,
+            &self.config(),
+            INNERTUBE_API,
+            None,
+            "search",
+            context,
+            json!({ "query": query }),
+        )
+        .await
+        .map(Some)
+    }
+
+    async fn browse(
+        &self,
+        request: Value,
+        context: &Value,
+        _oauth: Arc<YouTubeOAuth>,
+    ) -> AnyResult<Option<Value>> {
+        make_innertube_request(
+            &self.http,
+            &self.config(),
+            INNERTUBE_API,
+            None,
+            "browse",
+            context,
+            request,
+        )
+        .await
+        .map(Some)
+    }
+
+    async fn resolve_browse_id(
+        &self,
+        url: &str,
+        context: &Value,
+        _oauth: Arc<YouTubeOAuth>,
+    ) -> AnyResult<Option<String>> {
+        let Ok(response) = make_innertube_request(
+            &self.http,
+            &self.config(),
+            INNERTUBE_API,
+            None,
+            "navigation/resolve_url",
+            context,
+            json!({ "url": url }),
+        )
+        .await
+        else {
+            return Ok(None);
+        };
+
+        Ok(response
+            .pointer("/endpoint/browseEndpoint/browseId")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_owned()))
+    }
+
     async fn get_track_info(
         &self,
         track_id: &str,
@@ -9,7 +9,9 @@ use crate::{
     protocol::tracks::{Track, TrackInfo},
     sources::youtube::{
         cipher::YouTubeCipherManager,
-        clients::common::{ClientConfig, extract_thumbnail, is_duration, parse_duration},
+        clients::common::{
+            ClientConfig, extract_thumbnail, is_duration, make_innertube_request, parse_duration,
+        },
         oauth::YouTubeOAuth,
     },
 };
@@ -275,6 +277,44 @@ impl YouTubeClient for WebRemixClient {
         Ok(tracks)
     }
 
+    async fn search_raw(
+        &self,
+        query: &str,
+        context: &Value,
+        _oauth: Arc<YouTubeOAuth>,
+    ) -> AnyResult<Option<Value>> {
+        make_innertube_request(
+            &self.http,
+            &self.config(),
+            MUSIC_API,
+            Some(MUSIC_API),
+            "search",
+            context,
+            json!({ "query": query }),
+        )
+        .await
+        .map(Some)
+    }
+
+    async fn browse(
+        &self,
+        request: Value,
+        context: &Value,
+        _oauth: Arc<YouTubeOAuth>,
+    ) -> AnyResult<Option<Value>> {
+        make_innertube_request(
+            &self.http,
+            &self.config(),
+            MUSIC_API,
+            Some(MUSIC_API),
+            "browse",
+            context,
+            request,
+        )
+        .await
+        .map(Some)
+    }
+
     async fn get_track_info(
         &self,
         track_id: &str,
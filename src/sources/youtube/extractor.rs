@@ -1,6 +1,20 @@
+use std::{collections::HashSet, sync::OnceLock};
+
+use regex::Regex;
 use serde_json::Value;
 
-use crate::protocol::tracks::{Track, TrackInfo};
+use crate::protocol::tracks::{
+    Chapter, PlaylistData, PlaylistInfo, SearchResult, Track, TrackInfo,
+};
+
+/// YouTube only turns description timestamps into chapters when there are at
+/// least this many, starting at 0:00.
+const MIN_DESCRIPTION_CHAPTERS: usize = 3;
+
+fn timestamp_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b((?:\d{1,2}:)?\d{1,2}:\d{2})\b").unwrap())
+}
 
 pub fn extract_from_player(body: &Value, source_name: &str) -> Option<Track> {
     let details = body
@@ -34,7 +48,7 @@ pub fn extract_from_player(body: &Value, source_name: &str) -> Option<Track> {
         .and_then(|url| url.as_str())
         .map(|s| s.to_string());
 
-    let track = Track::new(TrackInfo {
+    let mut track = Track::new(TrackInfo {
         identifier: video_id.to_string(),
         is_seekable: !is_stream,
         author,
@@ -52,9 +66,103 @@ pub fn extract_from_player(body: &Value, source_name: &str) -> Option<Track> {
         source_name: source_name.to_string(),
     });
 
+    if !is_stream {
+        let chapters = extract_chapters(body, length_seconds * 1000);
+        if !chapters.is_empty() {
+            track.plugin_info = serde_json::json!({ "chapters": chapters });
+        }
+    }
+
     Some(track)
 }
 
+/// Reads chapter markers from a player or next response, falling back to
+/// timestamps in the video description.
+pub fn extract_chapters(body: &Value, length_ms: u64) -> Vec<Chapter> {
+    let mut markers = marker_chapters(body);
+    if markers.is_empty() {
+        markers = body
+            .get("videoDetails")
+            .and_then(|d| d.get("shortDescription"))
+            .and_then(|v| v.as_str())
+            .map(description_chapters)
+            .unwrap_or_default();
+    }
+
+    let ends: Vec<u64> = markers
+        .iter()
+        .skip(1)
+        .map(|(_, start, _)| *start)
+        .chain(std::iter::once(length_ms))
+        .collect();
+
+    markers
+        .into_iter()
+        .zip(ends)
+        .filter(|((_, start, _), end)| end > start)
+        .map(|((title, start, thumbnail), end)| Chapter {
+            title,
+            start,
+            end,
+            thumbnail,
+        })
+        .collect()
+}
+
+fn marker_chapters(body: &Value) -> Vec<(String, u64, Option<String>)> {
+    let Some(markers) = find_key(body, "markersMap").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+
+    markers
+        .iter()
+        .filter_map(|m| m.get("value")?.get("chapters")?.as_array())
+        .next()
+        .map(|chapters| {
+            chapters
+                .iter()
+                .filter_map(|c| {
+                    let renderer = c.get("chapterRenderer")?;
+                    Some((
+                        get_text(renderer.get("title")?)?,
+                        renderer.get("timeRangeStartMillis")?.as_u64()?,
+                        get_thumbnail(renderer),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn description_chapters(description: &str) -> Vec<(String, u64, Option<String>)> {
+    let chapters: Vec<(String, u64, Option<String>)> = description
+        .lines()
+        .filter_map(|line| {
+            let m = timestamp_regex().find(line)?;
+            let title = format!("{} {}", &line[..m.start()], &line[m.end()..]);
+            let title = title
+                .trim_matches(|c: char| c.is_whitespace() || "-–—|:()[]".contains(c))
+                .to_owned();
+            Some((title, parse_duration(m.as_str()) as u64, None))
+        })
+        .collect();
+
+    let valid = chapters.len() >= MIN_DESCRIPTION_CHAPTERS
+        && chapters[0].1 == 0
+        && chapters.windows(2).all(|w| w[0].1 < w[1].1);
+    if valid { chapters } else { Vec::new() }
+}
+
+fn find_key<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(map) => map
+            .get(key)
+            .or_else(|| map.values().find_map(|v| find_key(v, key))),
+        Value::Array(arr) => arr.iter().find_map(|v| find_key(v, key)),
+        _ => None,
+    }
+}
+
 pub fn extract_from_next(body: &Value, source_name: &str) -> Option<(Vec<Track>, String)> {
     let contents_root = body.get("contents").and_then(|c| {
         c.get("singleColumnWatchNextResults")
@@ -139,8 +247,13 @@ pub fn extract_from_browse(body: &Value, source_name: &str) -> Option<(Vec<Track
                         .and_then(|m| m.get("header"))
                         .and_then(|h| h.get("musicDetailHeaderRenderer"))
                 })
+                .or_else(|| h.get("musicImmersiveHeaderRenderer"))
+                .or_else(|| h.get("musicVisualHeaderRenderer"))
         })
+        // Newer YouTube Music album pages put the header inside the first tab.
+        .or_else(|| find_key(body, "musicResponsiveHeaderRenderer"))
         .and_then(|h| h.get("title"))
+        .or_else(|| body.pointer("/header/pageHeaderRenderer/pageTitle"))
         .and_then(get_text)
         .unwrap_or_else(|| "Unknown Playlist".to_string());
 
@@ -220,6 +333,274 @@ pub fn extract_from_browse(body: &Value, source_name: &str) -> Option<(Vec<Track
     Some((tracks, title))
 }
 
+/// Collects every result kind from an unfiltered web or YouTube Music search
+/// response. `types` follows `/v4/loadsearch`; an empty list means all kinds.
+pub fn extract_search_result(body: &Value, types: &[String], source_name: &str) -> SearchResult {
+    let mut result = SearchResult {
+        tracks: Vec::new(),
+        albums: Vec::new(),
+        artists: Vec::new(),
+        playlists: Vec::new(),
+        texts: Vec::new(),
+        plugin: serde_json::json!({}),
+    };
+    collect_search_items(body, source_name, &mut result, &mut HashSet::new());
+
+    let wants = |kind: &str| types.is_empty() || types.iter().any(|t| t == kind);
+    if !wants("track") {
+        result.tracks.clear();
+    }
+    if !wants("album") {
+        result.albums.clear();
+    }
+    if !wants("artist") {
+        result.artists.clear();
+    }
+    if !wants("playlist") {
+        result.playlists.clear();
+    }
+    result
+}
+
+fn collect_search_items(
+    value: &Value,
+    source_name: &str,
+    result: &mut SearchResult,
+    seen: &mut HashSet<String>,
+) {
+    match value {
+        Value::Array(arr) => {
+            for item in arr {
+                collect_search_items(item, source_name, result, seen);
+            }
+        }
+        Value::Object(map) => {
+            if map.contains_key("videoRenderer") {
+                if let Some(track) = extract_track(value, source_name)
+                    && seen.insert(track.info.identifier.clone())
+                {
+                    result.tracks.push(track);
+                }
+            } else if let Some(channel) = map.get("channelRenderer") {
+                push_entity(&mut result.artists, seen, web_channel(channel));
+            } else if let Some(playlist) = map.get("playlistRenderer") {
+                push_entity(&mut result.playlists, seen, web_playlist(playlist));
+            } else if let Some(lockup) = map.get("lockupViewModel") {
+                push_entity(&mut result.playlists, seen, lockup_playlist(lockup));
+            } else if let Some(item) = map.get("musicResponsiveListItemRenderer") {
+                match music_page_type(item) {
+                    Some("MUSIC_PAGE_TYPE_ALBUM") => {
+                        push_entity(&mut result.albums, seen, music_entity(item, "album"))
+                    }
+                    Some("MUSIC_PAGE_TYPE_ARTIST") => {
+                        push_entity(&mut result.artists, seen, music_entity(item, "artist"))
+                    }
+                    Some("MUSIC_PAGE_TYPE_PLAYLIST") => {
+                        push_entity(&mut result.playlists, seen, music_entity(item, "playlist"))
+                    }
+                    _ => {
+                        if let Some(track) = extract_track(value, source_name)
+                            && seen.insert(track.info.identifier.clone())
+                        {
+                            result.tracks.push(track);
+                        }
+                    }
+                }
+            } else {
+                for v in map.values() {
+                    collect_search_items(v, source_name, result, seen);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn push_entity(
+    list: &mut Vec<PlaylistData>,
+    seen: &mut HashSet<String>,
+    entity: Option<PlaylistData>,
+) {
+    if let Some(entity) = entity
+        && let Some(url) = entity.plugin_info.get("url").and_then(|u| u.as_str())
+        && seen.insert(url.to_owned())
+    {
+        list.push(entity);
+    }
+}
+
+fn search_entity(
+    kind: &str,
+    name: String,
+    url: String,
+    artwork_url: Option<String>,
+    author: String,
+    total_tracks: u64,
+) -> PlaylistData {
+    PlaylistData {
+        info: PlaylistInfo {
+            name,
+            selected_track: -1,
+        },
+        plugin_info: serde_json::json!({
+          "type": kind,
+          "url": url,
+          "artworkUrl": artwork_url,
+          "author": author,
+          "totalTracks": total_tracks
+        }),
+        tracks: Vec::new(),
+    }
+}
+
+fn web_channel(renderer: &Value) -> Option<PlaylistData> {
+    let id = renderer.get("channelId").and_then(|v| v.as_str())?;
+    let name = renderer.get("title").and_then(get_text)?;
+    Some(search_entity(
+        "artist",
+        name.clone(),
+        format!("https://www.youtube.com/channel/{}", id),
+        get_thumbnail(renderer).map(absolute_url),
+        name,
+        0,
+    ))
+}
+
+fn web_playlist(renderer: &Value) -> Option<PlaylistData> {
+    let id = renderer.get("playlistId").and_then(|v| v.as_str())?;
+    let name = renderer.get("title").and_then(get_text)?;
+    let author = renderer
+        .get("shortBylineText")
+        .or_else(|| renderer.get("longBylineText"))
+        .and_then(get_text)
+        .unwrap_or_else(|| "Unknown Author".to_owned());
+    let total = renderer
+        .get("videoCount")
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0);
+    let artwork = renderer
+        .pointer("/thumbnails/0/thumbnails")
+        .and_then(|t| t.as_array())
+        .and_then(|t| t.last())
+        .and_then(|t| t.get("url"))
+        .and_then(|u| u.as_str())
+        .map(|u| u.split('?').next().unwrap_or(u).to_owned());
+    Some(search_entity(
+        "playlist",
+        name,
+        format!("https://www.youtube.com/playlist?list={}", id),
+        artwork,
+        author,
+        total,
+    ))
+}
+
+/// Newer web search responses render playlists as `lockupViewModel`s.
+fn lockup_playlist(lockup: &Value) -> Option<PlaylistData> {
+    if lockup.get("contentType").and_then(|v| v.as_str()) != Some("LOCKUP_CONTENT_TYPE_PLAYLIST") {
+        return None;
+    }
+    let id = lockup.get("contentId").and_then(|v| v.as_str())?;
+    let metadata = lockup.pointer("/metadata/lockupMetadataViewModel")?;
+    let name = metadata
+        .pointer("/title/content")
+        .and_then(|v| v.as_str())?
+        .to_owned();
+    let author = metadata
+        .pointer("/metadata/contentMetadataViewModel/metadataRows/0/metadataParts/0/text/content")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Unknown Author")
+        .to_owned();
+    let artwork = lockup
+        .pointer("/contentImage/collectionThumbnailViewModel/primaryThumbnail/thumbnailViewModel/image/sources")
+        .and_then(|s| s.as_array())
+        .and_then(|s| s.last())
+        .and_then(|s| s.get("url"))
+        .and_then(|u| u.as_str())
+        .map(|u| u.split('?').next().unwrap_or(u).to_owned());
+    // The video count only shows up in the thumbnail badge ("12 videos").
+    let total = find_key(lockup, "thumbnailBadgeViewModel")
+        .and_then(|b| b.get("text"))
+        .and_then(|t| t.as_str())
+        .and_then(|t| t.split_whitespace().next())
+        .and_then(|n| n.replace(',', "").parse().ok())
+        .unwrap_or(0);
+    Some(search_entity(
+        "playlist",
+        name,
+        format!("https://www.youtube.com/playlist?list={}", id),
+        artwork,
+        author,
+        total,
+    ))
+}
+
+fn music_page_type(renderer: &Value) -> Option<&str> {
+    renderer
+        .pointer("/navigationEndpoint/browseEndpoint/browseEndpointContextSupportedConfigs/browseEndpointContextMusicConfig/pageType")
+        .and_then(|v| v.as_str())
+}
+
+fn music_entity(renderer: &Value, kind: &str) -> Option<PlaylistData> {
+    let browse_id = renderer
+        .pointer("/navigationEndpoint/browseEndpoint/browseId")
+        .and_then(|v| v.as_str())?;
+    let columns = renderer.get("flexColumns").and_then(|c| c.as_array())?;
+    let column_text = |i: usize| {
+        columns
+            .get(i)
+            .and_then(|c| c.pointer("/musicResponsiveListItemFlexColumnRenderer/text"))
+    };
+    let title = column_text(0).and_then(get_text)?;
+
+    // The second column reads "Album • Artist • 2020" or "Playlist • Author • 1.2M views";
+    // the author is the first run that isn't the kind label, a separator or a year.
+    let author = column_text(1)
+        .and_then(|t| t.get("runs"))
+        .and_then(|r| r.as_array())
+        .and_then(|runs| {
+            runs.iter()
+                .filter_map(|r| r.get("text").and_then(|t| t.as_str()))
+                .map(str::trim)
+                .skip(1)
+                .find(|t| !t.is_empty() && *t != "•" && t.parse::<u32>().is_err())
+                .map(str::to_owned)
+        });
+
+    let artwork = crate::sources::youtube::clients::common::extract_thumbnail(renderer, None);
+    let (name, url, author) = match kind {
+        "artist" => (
+            format!("{title}'s Top Tracks"),
+            format!("https://music.youtube.com/channel/{browse_id}"),
+            title,
+        ),
+        "playlist" => (
+            title,
+            format!(
+                "https://music.youtube.com/playlist?list={}",
+                browse_id.strip_prefix("VL").unwrap_or(browse_id)
+            ),
+            author.unwrap_or_else(|| "Unknown Author".to_owned()),
+        ),
+        _ => (
+            title,
+            format!("https://music.youtube.com/browse/{browse_id}"),
+            author.unwrap_or_else(|| "Unknown Artist".to_owned()),
+        ),
+    };
+    Some(search_entity(kind, name, url, artwork, author, 0))
+}
+
+/// Channel avatars come back protocol-relative (`//yt3.ggpht.com/...`).
+fn absolute_url(url: String) -> String {
+    if url.starts_with("//") {
+        format!("https:{url}")
+    } else {
+        url
+    }
+}
+
 pub fn find_section_list(value: &Value) -> Option<&Value> {
     if let Some(list) = value.get("sectionListRenderer") {
         return Some(list);
@@ -251,9 +632,72 @@ pub fn find_section_list(value: &Value) -> Option<&Value> {
     {
         return find_section_list(primary);
     }
+    // Album tracks sit in `secondaryContents`; the tabs only hold the header.
+    if let Some(browse) = value.get("twoColumnBrowseResultsRenderer") {
+        return browse
+            .get("secondaryContents")
+            .and_then(find_section_list)
+            .or_else(|| find_section_list(browse));
+    }
+    if let Some(browse) = value.get("singleColumnBrowseResultsRenderer") {
+        return find_section_list(browse);
+    }
     None
 }
 
+/// Tracks and the next token from a browse `continuation` response.
+pub fn extract_continuation(body: &Value, source_name: &str) -> (Vec<Track>, Option<String>) {
+    let items: Vec<&Value> = body
+        .get("onResponseReceivedActions")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|a| a.pointer("/appendContinuationItemsAction/continuationItems"))
+        .filter_map(|c| c.as_array())
+        .flatten()
+        .collect();
+
+    let tracks = items
+        .iter()
+        .filter_map(|item| extract_track(item, source_name))
+        .collect();
+    (tracks, items.into_iter().find_map(continuation_token))
+}
+
+/// Token of the first `continuationItemRenderer` under `value`.
+pub fn continuation_token(value: &Value) -> Option<String> {
+    find_key(value, "continuationItemRenderer")
+        .and_then(|c| c.pointer("/continuationEndpoint/continuationCommand/token"))
+        .and_then(|t| t.as_str())
+        .map(|t| t.to_string())
+}
+
+/// Album tracks often leave the artist column empty and carry no artwork of
+/// their own, so both are taken from the album header.
+pub fn fill_album_metadata(body: &Value, tracks: &mut [Track]) {
+    let header = find_key(body, "musicResponsiveHeaderRenderer")
+        .or_else(|| body.pointer("/header/musicDetailHeaderRenderer"));
+    let artist = header
+        .and_then(|h| {
+            h.get("straplineTextOne")
+                .or_else(|| h.pointer("/subtitle/runs/2"))
+        })
+        .and_then(get_text);
+    let artwork =
+        header.and_then(|h| crate::sources::youtube::clients::common::extract_thumbnail(h, None));
+
+    for track in tracks {
+        if let Some(artist) = &artist
+            && track.info.author == "Unknown Artist"
+        {
+            track.info.author = artist.clone();
+        }
+        if track.info.artwork_url.is_none() {
+            track.info.artwork_url = artwork.clone();
+        }
+    }
+}
+
 pub fn extract_track(item: &Value, source_name: &str) -> Option<Track> {
     // Check for different renderer types
     let renderer = item
@@ -361,6 +805,14 @@ pub fn extract_track(item: &Value, source_name: &str) -> Option<Track> {
                     .and_then(|s| s.parse::<i64>().ok())
                     .map(|s| s * 1000)
             })
+            .or_else(|| {
+                // YouTube Music list rows keep the duration in a fixed column.
+                renderer
+                    .pointer("/fixedColumns/0/musicResponsiveListItemFixedColumnRenderer/text")
+                    .and_then(get_text)
+                    .filter(|s| s.contains(':'))
+                    .map(|s| parse_duration(&s))
+            })
             .unwrap_or(0)
     };
 
@@ -417,3 +869,134 @@ fn get_thumbnail(renderer: &Value) -> Option<String> {
         .and_then(|url| url.as_str())
         .map(|s| s.split('?').next().unwrap_or(s).to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_description_chapters() {
+        let body = json!({
+            "videoDetails": {
+                "shortDescription": "Tracklist:\n0:00 Intro\n02:30 - First Song\nSecond Song (1:05:10)\nthanks for listening"
+            }
+        });
+        let chapters = extract_chapters(&body, 4_000_000);
+        let starts: Vec<(&str, u64, u64)> = chapters
+            .iter()
+            .map(|c| (c.title.as_str(), c.start, c.end))
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                ("Intro", 0, 150_000),
+                ("First Song", 150_000, 3_910_000),
+                ("Second Song", 3_910_000, 4_000_000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_description_needs_chapters_from_zero() {
+        let body = json!({
+            "videoDetails": { "shortDescription": "1:00 a\n2:00 b\n3:00 c" }
+        });
+        assert!(extract_chapters(&body, 240_000).is_empty());
+    }
+
+    #[test]
+    fn test_search_result_kinds() {
+        let album_nav = json!({ "browseEndpoint": {
+            "browseId": "MPREb_abc",
+            "browseEndpointContextSupportedConfigs": { "browseEndpointContextMusicConfig": {
+                "pageType": "MUSIC_PAGE_TYPE_ALBUM"
+            } }
+        } });
+        let body = json!({ "contents": [
+            { "videoRenderer": { "videoId": "vid1", "title": { "runs": [{ "text": "Song" }] } } },
+            { "channelRenderer": {
+                "channelId": "UC123",
+                "title": { "simpleText": "Channel" },
+                "thumbnail": { "thumbnails": [{ "url": "//yt3.ggpht.com/a" }] }
+            } },
+            { "musicResponsiveListItemRenderer": {
+                "navigationEndpoint": album_nav,
+                "flexColumns": [
+                    { "musicResponsiveListItemFlexColumnRenderer": { "text": { "runs": [{ "text": "Record" }] } } },
+                    { "musicResponsiveListItemFlexColumnRenderer": { "text": { "runs": [
+                        { "text": "Album" }, { "text": " • " }, { "text": "Band" }, { "text": " • " }, { "text": "2020" }
+                    ] } } }
+                ]
+            } }
+        ] });
+
+        let all = extract_search_result(&body, &[], "youtube");
+        assert_eq!(all.tracks[0].info.identifier, "vid1");
+        assert_eq!(
+            all.artists[0].plugin_info["url"],
+            "https://www.youtube.com/channel/UC123"
+        );
+        assert_eq!(
+            all.artists[0].plugin_info["artworkUrl"],
+            "https://yt3.ggpht.com/a"
+        );
+        assert_eq!(all.albums[0].info.name, "Record");
+        assert_eq!(all.albums[0].plugin_info["author"], "Band");
+
+        let albums = extract_search_result(&body, &["album".to_owned()], "youtube");
+        assert!(albums.tracks.is_empty() && albums.artists.is_empty());
+        assert_eq!(albums.albums.len(), 1);
+    }
+
+    #[test]
+    fn test_album_browse() {
+        let body = json!({ "contents": { "twoColumnBrowseResultsRenderer": {
+            "tabs": [{ "tabRenderer": { "content": { "sectionListRenderer": { "contents": [
+                { "musicResponsiveHeaderRenderer": {
+                    "title": { "runs": [{ "text": "Record" }] },
+                    "straplineTextOne": { "runs": [{ "text": "Band" }] }
+                } }
+            ] } } } }],
+            "secondaryContents": { "sectionListRenderer": { "contents": [
+                { "musicShelfRenderer": { "contents": [
+                    { "musicResponsiveListItemRenderer": {
+                        "playlistItemData": { "videoId": "vid1" },
+                        "flexColumns": [
+                            { "musicResponsiveListItemFlexColumnRenderer": { "text": { "runs": [{ "text": "Opener" }] } } }
+                        ],
+                        "fixedColumns": [
+                            { "musicResponsiveListItemFixedColumnRenderer": { "text": { "runs": [{ "text": "3:25" }] } } }
+                        ]
+                    } }
+                ] } }
+            ] } }
+        } } });
+
+        let (mut tracks, title) = extract_from_browse(&body, "youtube").unwrap();
+        fill_album_metadata(&body, &mut tracks);
+        assert_eq!(title, "Record");
+        assert_eq!(tracks[0].info.title, "Opener");
+        assert_eq!(tracks[0].info.author, "Band");
+        assert_eq!(tracks[0].info.length, 205_000);
+    }
+
+    #[test]
+    fn test_marker_chapters_take_precedence() {
+        let body = json!({
+            "videoDetails": { "shortDescription": "0:00 a\n1:00 b\n2:00 c" },
+            "playerOverlays": { "playerBar": { "markersMap": [{
+                "key": "DESCRIPTION_CHAPTERS",
+                "value": { "chapters": [
+                    { "chapterRenderer": { "title": { "simpleText": "Start" }, "timeRangeStartMillis": 0 } },
+                    { "chapterRenderer": { "title": { "simpleText": "Drop" }, "timeRangeStartMillis": 90000 } }
+                ] }
+            }] } }
+        });
+        let chapters = extract_chapters(&body, 180_000);
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[1].title, "Drop");
+        assert_eq!(chapters[1].end, 180_000);
+    }
+}
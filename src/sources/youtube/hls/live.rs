@@ -0,0 +1,666 @@
+//! Live HLS playback with a rolling segment window.
+//!
+//! A fetch thread follows the live playlist and appends every new segment to
+//! a [`LiveWindow`] that keeps the last `live_window_secs` of audio. Readers
+//! pull segments out of the window by sequence number, so a seek only has to
+//! restart the decoder on a fresh reader positioned at the wanted segment.
+//! Positions are on the stream timeline: 0 is the first segment fetched.
+//! Streams with an `#EXT-X-MAP` init segment hand it to every reader before
+//! its first media segment.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Seek, SeekFrom},
+    net::IpAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use parking_lot::{Condvar, Mutex};
+use symphonia::core::io::MediaSource;
+use tracing::{debug, error, warn};
+
+use super::{
+    fetch_and_demux_into,
+    fetcher::fetch_segment_into,
+    parser::parse_m3u8,
+    resolve_resource_static,
+    resolver::{fetch_text, resolve_media_playlist},
+    types::{M3u8Playlist, Resource},
+};
+use crate::{
+    audio::{
+        AudioFrame,
+        playback::LiveEdge,
+        processor::{AudioProcessor, DecoderCommand},
+    },
+    common::types::AnyResult,
+    config::{HttpProxyConfig, player::PlayerConfig},
+    sources::youtube::{cipher::YouTubeCipherManager, utils::DEFAULT_USER_AGENT},
+};
+
+/// Segments always kept regardless of the window, so a reader at the live
+/// edge is not skipped ahead when several segments land at once.
+const MIN_SEGMENTS: usize = 4;
+
+/// How often a blocked reader re-checks for cancellation.
+const READ_POLL: Duration = Duration::from_millis(200);
+
+struct LiveSegment {
+    start_ms: u64,
+    data: Arc<[u8]>,
+}
+
+struct WindowState {
+    segments: VecDeque<LiveSegment>,
+    /// Sequence number of `segments[0]`.
+    first_seq: u64,
+    /// End of the newest segment on the stream timeline (ms).
+    edge_ms: u64,
+    /// Init segment from `#EXT-X-MAP`, read before the first media segment.
+    init: Option<Arc<[u8]>>,
+    ended: bool,
+}
+
+/// Rolling buffer of the most recent segments of a live stream.
+pub struct LiveWindow {
+    state: Mutex<WindowState>,
+    cvar: Condvar,
+    window_ms: u64,
+    closed: AtomicBool,
+}
+
+impl LiveWindow {
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            state: Mutex::new(WindowState {
+                segments: VecDeque::new(),
+                first_seq: 0,
+                edge_ms: 0,
+                init: None,
+                ended: false,
+            }),
+            cvar: Condvar::new(),
+            window_ms,
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Appends a segment at the live edge, dropping those that fell out of
+    /// the window.
+    pub fn push(&self, duration_ms: u64, data: Vec<u8>) {
+        let mut state = self.state.lock();
+        let start_ms = state.edge_ms;
+        state.edge_ms += duration_ms;
+        state.segments.push_back(LiveSegment {
+            start_ms,
+            data: data.into(),
+        });
+
+        let floor = state.edge_ms.saturating_sub(self.window_ms);
+        while state.segments.len() > MIN_SEGMENTS && state.segments[1].start_ms <= floor {
+            state.segments.pop_front();
+            state.first_seq += 1;
+        }
+        self.cvar.notify_all();
+    }
+
+    /// End of the newest fetched segment (ms).
+    pub fn edge_ms(&self) -> u64 {
+        self.state.lock().edge_ms
+    }
+
+    pub fn set_init(&self, data: Vec<u8>) {
+        self.state.lock().init = Some(data.into());
+    }
+
+    fn init(&self) -> Option<Arc<[u8]>> {
+        self.state.lock().init.clone()
+    }
+
+    /// Marks the stream as over; readers drain what is left and then end.
+    pub fn finish(&self) {
+        self.state.lock().ended = true;
+        self.cvar.notify_all();
+    }
+
+    /// Stops the fetch thread and ends every reader.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.finish();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    /// Segment to resume from for a seek to `ms` and where it starts. Seeks
+    /// before the window land on the oldest segment, seeks past the live edge
+    /// on the newest.
+    pub fn locate(&self, ms: u64) -> (u64, u64) {
+        let state = self.state.lock();
+        let index = state
+            .segments
+            .iter()
+            .rposition(|seg| seg.start_ms <= ms)
+            .unwrap_or(0);
+
+        match state.segments.get(index) {
+            Some(seg) => (state.first_seq + index as u64, seg.start_ms),
+            None => (state.first_seq, state.edge_ms),
+        }
+    }
+
+    /// Waits for segment `seq`, or the oldest one still held if it was
+    /// evicted. `None` once the stream ended or `cancel` is set.
+    fn next(&self, seq: u64, cancel: &AtomicBool) -> Option<(u64, Arc<[u8]>)> {
+        let mut state = self.state.lock();
+        loop {
+            if cancel.load(Ordering::Acquire) {
+                return None;
+            }
+
+            let seq = seq.max(state.first_seq);
+            if let Some(seg) = state.segments.get((seq - state.first_seq) as usize) {
+                return Some((seq, seg.data.clone()));
+            }
+            if state.ended {
+                return None;
+            }
+            self.cvar.wait_for(&mut state, READ_POLL);
+        }
+    }
+}
+
+/// Reads a live window sequentially from a given segment.
+pub struct LiveHlsReader {
+    window: Arc<LiveWindow>,
+    cancel: Arc<AtomicBool>,
+    next_seq: u64,
+    current: Arc<[u8]>,
+    /// Media segment waiting behind the init segment.
+    queued: Option<Arc<[u8]>>,
+    started: bool,
+    pos: usize,
+}
+
+impl LiveHlsReader {
+    pub fn new(window: Arc<LiveWindow>, seq: u64, cancel: Arc<AtomicBool>) -> Self {
+        Self {
+            window,
+            cancel,
+            next_seq: seq,
+            current: Arc::from(Vec::new()),
+            queued: None,
+            started: false,
+            pos: 0,
+        }
+    }
+}
+
+impl Read for LiveHlsReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.pos < self.current.len() {
+                let n = buf.len().min(self.current.len() - self.pos);
+                buf[..n].copy_from_slice(&self.current[self.pos..self.pos + n]);
+                self.pos += n;
+                return Ok(n);
+            }
+
+            if let Some(data) = self.queued.take() {
+                self.current = data;
+                self.pos = 0;
+                continue;
+            }
+
+            match self.window.next(self.next_seq, &self.cancel) {
+                Some((seq, data)) => {
+                    self.pos = 0;
+                    self.next_seq = seq + 1;
+                    let init = if self.started {
+                        None
+                    } else {
+                        self.window.init()
+                    };
+                    self.started = true;
+                    match init {
+                        Some(init) => {
+                            self.current = init;
+                            self.queued = Some(data);
+                        }
+                        None => self.current = data,
+                    }
+                }
+                None => return Ok(0),
+            }
+        }
+    }
+}
+
+impl Seek for LiveHlsReader {
+    fn seek(&mut self, _: SeekFrom) -> io::Result<u64> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "live readers are repositioned by restarting on a new segment",
+        ))
+    }
+}
+
+impl MediaSource for LiveHlsReader {
+    fn is_seekable(&self) -> bool {
+        false
+    }
+    fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
+
+pub fn build_client(
+    local_addr: Option<IpAddr>,
+    proxy: Option<HttpProxyConfig>,
+) -> AnyResult<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(DEFAULT_USER_AGENT)
+        .timeout(Duration::from_secs(15));
+
+    if let Some(ip) = local_addr {
+        builder = builder.local_address(ip);
+    }
+
+    if let Some(ref cfg) = proxy
+        && let Some(ref url) = cfg.url
+    {
+        let mut p = reqwest::Proxy::all(url)?;
+        if let (Some(u), Some(pw)) = (&cfg.username, &cfg.password) {
+            p = p.basic_auth(u, pw);
+        }
+        builder = builder.proxy(p);
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Whether a media playlist is still being appended to.
+pub fn is_live_playlist(text: &str) -> bool {
+    !text.contains("#EXT-X-ENDLIST")
+}
+
+/// One refresh of a live media playlist.
+pub struct LivePlaylist {
+    pub segments: Vec<Resource>,
+    pub map: Option<Resource>,
+    /// Sequence number of `segments[0]` (`#EXT-X-MEDIA-SEQUENCE`).
+    pub media_sequence: u64,
+    pub target_duration: f64,
+    pub ended: bool,
+}
+
+pub fn parse_live_playlist(text: &str, base_url: &str) -> LivePlaylist {
+    let (segments, map) = match parse_m3u8(text, base_url) {
+        M3u8Playlist::Media { segments, map } => (segments, map),
+        M3u8Playlist::Master { .. } => (Vec::new(), None),
+    };
+    let mut playlist = LivePlaylist {
+        segments,
+        map,
+        media_sequence: 0,
+        target_duration: 6.0,
+        ended: !is_live_playlist(text),
+    };
+
+    for line in text.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("#EXT-X-TARGETDURATION:")
+            && let Ok(d) = rest.trim().parse::<f64>()
+        {
+            playlist.target_duration = d;
+        } else if let Some(rest) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:")
+            && let Ok(seq) = rest.trim().parse::<u64>()
+        {
+            playlist.media_sequence = seq;
+        }
+    }
+
+    playlist
+}
+
+/// A live stream to follow and how to fetch it.
+pub struct LiveSource {
+    pub client: reqwest::Client,
+    pub manifest_url: String,
+    /// Media playlist URL and text, when they were already fetched to find
+    /// out the stream is live.
+    pub playlist: Option<(String, String)>,
+    pub cipher_manager: Option<Arc<YouTubeCipherManager>>,
+    pub player_url: Option<String>,
+    /// Updated with the window's edge as segments arrive.
+    pub edge: Arc<LiveEdge>,
+}
+
+/// Follows the live playlist into `window` until it is closed or the stream
+/// ends.
+fn fetch_loop(window: Arc<LiveWindow>, source: LiveSource, handle: tokio::runtime::Handle) {
+    let LiveSource {
+        client,
+        manifest_url,
+        playlist,
+        cipher_manager,
+        player_url,
+        edge,
+    } = source;
+
+    let (media_url, mut pending_text) = match playlist {
+        Some((url, text)) => (url, Some(text)),
+        None => loop {
+            match handle.block_on(resolve_media_playlist(&client, &manifest_url)) {
+                Ok((url, text)) => break (url, Some(text)),
+                Err(e) => {
+                    warn!("Live HLS: playlist resolve failed: {e}");
+                    if !sleep_unless_closed(&window, Duration::from_secs(2)) {
+                        return;
+                    }
+                }
+            }
+        },
+    };
+
+    let mut next_seq: Option<u64> = None;
+    let mut init_url: Option<String> = None;
+
+    while !window.is_closed() {
+        let text = match pending_text.take() {
+            Some(text) => text,
+            None => match handle.block_on(fetch_text(&client, &media_url)) {
+                Ok(t) => t,
+                Err(e) => {
+                    warn!("Live HLS: playlist refresh failed: {e}");
+                    if !sleep_unless_closed(&window, Duration::from_secs(2)) {
+                        break;
+                    }
+                    continue;
+                }
+            },
+        };
+
+        let playlist = parse_live_playlist(&text, &media_url);
+        let wait = Duration::from_secs_f64((playlist.target_duration / 2.0).max(1.0));
+
+        if let Some(map) = &playlist.map
+            && init_url.as_deref() != Some(map.url.as_str())
+        {
+            let mut data = Vec::new();
+            let fetched = resolve_resource_static(map, &cipher_manager, &player_url)
+                .and_then(|res| handle.block_on(fetch_segment_into(&client, &res, &mut data)));
+            if let Err(e) = fetched {
+                warn!("Live HLS: init segment fetch failed: {e}");
+                if !sleep_unless_closed(&window, wait) {
+                    break;
+                }
+                continue;
+            }
+            window.set_init(data);
+            init_url = Some(map.url.clone());
+        }
+
+        for (seq, seg) in (playlist.media_sequence..).zip(playlist.segments) {
+            if window.is_closed() {
+                return;
+            }
+            if next_seq.is_some_and(|next| seq < next) {
+                continue;
+            }
+
+            let mut payload = Vec::new();
+            let fetched = resolve_resource_static(&seg, &cipher_manager, &player_url)
+                .and_then(|res| handle.block_on(fetch_and_demux_into(&client, &res, &mut payload)));
+            if let Err(e) = fetched {
+                warn!("Live HLS: segment {seq} fetch error: {e}");
+                continue;
+            }
+
+            let duration_ms = (seg.duration.unwrap_or(playlist.target_duration) * 1000.0) as u64;
+            window.push(duration_ms, payload);
+            edge.set(window.edge_ms());
+            next_seq = Some(seq + 1);
+        }
+
+        if playlist.ended {
+            debug!("Live HLS: stream ended");
+            break;
+        }
+
+        if !sleep_unless_closed(&window, wait) {
+            break;
+        }
+    }
+
+    window.finish();
+}
+
+/// Sleeps in short steps; `false` if the window was closed meanwhile.
+fn sleep_unless_closed(window: &LiveWindow, total: Duration) -> bool {
+    let mut left = total;
+    while !left.is_zero() {
+        if window.is_closed() {
+            return false;
+        }
+        let step = left.min(READ_POLL);
+        std::thread::sleep(step);
+        left -= step;
+    }
+    !window.is_closed()
+}
+
+/// Plays a live HLS stream into `tx`, answering seeks from the rolling window
+/// by restarting the decoder on the segment that holds the target. Seeking
+/// past the live edge jumps back to live.
+pub async fn run_live(
+    name: String,
+    source: LiveSource,
+    config: PlayerConfig,
+    tx: flume::Sender<AudioFrame>,
+    cmd_rx: flume::Receiver<DecoderCommand>,
+    err_tx: flume::Sender<String>,
+) {
+    let window = Arc::new(LiveWindow::new(config.live_window_secs * 1000));
+
+    let fetch_window = window.clone();
+    let handle = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || fetch_loop(fetch_window, source, handle));
+
+    play_window(&name, &window, config, tx, cmd_rx, err_tx).await;
+    window.close();
+}
+
+async fn play_window(
+    name: &str,
+    window: &Arc<LiveWindow>,
+    config: PlayerConfig,
+    tx: flume::Sender<AudioFrame>,
+    cmd_rx: flume::Receiver<DecoderCommand>,
+    err_tx: flume::Sender<String>,
+) {
+    let mut seq = 0;
+    let mut seek: Option<u64> = None;
+
+    loop {
+        if let Some(ms) = seek.take() {
+            let (target, start_ms) = window.locate(ms);
+            debug!(
+                "Live HLS: seek to {}ms -> segment {} at {}ms (edge {}ms)",
+                ms,
+                target,
+                start_ms,
+                window.edge_ms()
+            );
+            seq = target;
+            if tx.send_async(AudioFrame::Seeked(start_ms)).await.is_err() {
+                return;
+            }
+        }
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let reader = Box::new(LiveHlsReader::new(window.clone(), seq, cancel.clone()))
+            as Box<dyn MediaSource>;
+        let (inner_cmd_tx, inner_cmd_rx) = flume::unbounded();
+
+        let processor_tx = tx.clone();
+        let processor_err_tx = err_tx.clone();
+        let processor_config = config.clone();
+        // Segments are ADTS demuxed from TS or fMP4 behind an init segment;
+        // the probe tells them apart.
+        let mut opening = tokio::task::spawn_blocking(move || {
+            AudioProcessor::new(
+                reader,
+                None,
+                processor_tx,
+                inner_cmd_rx,
+                Some(processor_err_tx),
+                processor_config,
+            )
+        });
+
+        // The processor probes the first segment while opening, which can
+        // take a while at the live edge; stay responsive to commands.
+        let opened = loop {
+            tokio::select! {
+                res = &mut opening => break res,
+                cmd = cmd_rx.recv_async() => match cmd {
+                    Ok(DecoderCommand::Seek(ms)) => seek = Some(ms),
+                    Ok(DecoderCommand::Stop) | Err(_) => {
+                        cancel.store(true, Ordering::Release);
+                        return;
+                    }
+                },
+            }
+        };
+
+        let processor = match opened {
+            Ok(Ok(processor)) => processor,
+            Ok(Err(e)) => {
+                error!("Live HLS processor init failed for {}: {}", name, e);
+                let _ = err_tx.send(format!("Failed to initialize processor: {e}"));
+                return;
+            }
+            Err(e) => {
+                error!("Live HLS processor init panicked for {}: {}", name, e);
+                return;
+            }
+        };
+
+        if seek.is_some() {
+            continue;
+        }
+
+        let (done_tx, mut done_rx) = tokio::sync::oneshot::channel();
+        crate::audio::worker::submit(name.to_string(), processor, move |res| {
+            let _ = done_tx.send(res);
+        });
+
+        loop {
+            tokio::select! {
+                cmd = cmd_rx.recv_async() => match cmd {
+                    Ok(DecoderCommand::Seek(ms)) => {
+                        cancel.store(true, Ordering::Release);
+                        let _ = inner_cmd_tx.send(DecoderCommand::Stop);
+                        // Wait for the old decoder so none of its audio lands
+                        // after the seek marker.
+                        let _ = (&mut done_rx).await;
+                        seek = Some(ms);
+                        break;
+                    }
+                    Ok(DecoderCommand::Stop) | Err(_) => {
+                        cancel.store(true, Ordering::Release);
+                        let _ = inner_cmd_tx.send(DecoderCommand::Stop);
+                        return;
+                    }
+                },
+                res = &mut done_rx => {
+                    if let Ok(Err(e)) = res {
+                        error!("Live HLS processor error for {}: {}", name, e);
+                        let _ = err_tx.send(e.to_string());
+                    }
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled(window_ms: u64, segments: u8) -> LiveWindow {
+        let window = LiveWindow::new(window_ms);
+        for i in 0..segments {
+            window.push(2000, vec![i; 4]);
+        }
+        window
+    }
+
+    #[test]
+    fn test_window_evicts_segments_older_than_the_window() {
+        let window = filled(10_000, 10);
+        // Edge is at 20s; segments that ended by 10s have fallen out.
+        assert_eq!(window.edge_ms(), 20_000);
+        assert_eq!(window.locate(0), (5, 10_000));
+    }
+
+    #[test]
+    fn test_window_keeps_a_minimum_at_the_live_edge() {
+        let window = filled(0, 10);
+        assert_eq!(window.locate(0), (6, 12_000));
+    }
+
+    #[test]
+    fn test_locate_clamps_to_the_live_edge() {
+        let window = filled(60_000, 5);
+        assert_eq!(window.locate(4500), (2, 4000));
+        assert_eq!(window.locate(u64::MAX), (4, 8000));
+    }
+
+    #[test]
+    fn test_reader_skips_evicted_segments_and_ends_with_the_stream() {
+        let window = Arc::new(filled(10_000, 10));
+        window.finish();
+
+        let mut reader = LiveHlsReader::new(window, 0, Arc::new(AtomicBool::new(false)));
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).unwrap();
+
+        let expected: Vec<u8> = (5..10).flat_map(|i| [i; 4]).collect();
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_reader_starts_with_the_init_segment() {
+        let window = Arc::new(filled(60_000, 3));
+        window.set_init(vec![9; 2]);
+        window.finish();
+
+        let mut reader = LiveHlsReader::new(window, 1, Arc::new(AtomicBool::new(false)));
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).unwrap();
+        assert_eq!(out, [9, 9, 1, 1, 1, 1, 2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn test_parse_live_playlist_reads_durations() {
+        let text = "#EXTM3U\n#EXT-X-TARGETDURATION:2\n#EXT-X-MEDIA-SEQUENCE:41\n#EXT-X-MAP:URI=\"init.mp4\"\n#EXTINF:2.002,live\nseg1.ts\n#EXTINF:1.5,\nseg2.ts\n";
+        let playlist = parse_live_playlist(text, "https://example.com/live/index.m3u8");
+        assert_eq!(playlist.target_duration, 2.0);
+        assert_eq!(playlist.media_sequence, 41);
+        assert!(!playlist.ended);
+        assert_eq!(
+            playlist.map.map(|m| m.url).as_deref(),
+            Some("https://example.com/live/init.mp4")
+        );
+        assert_eq!(playlist.segments.len(), 2);
+        assert_eq!(playlist.segments[0].url, "https://example.com/live/seg1.ts");
+        assert_eq!(playlist.segments[1].duration, Some(1.5));
+    }
+}
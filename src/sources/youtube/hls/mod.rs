@@ -1,5 +1,6 @@
 use crate::common::types::AnyResult;
 pub mod fetcher;
+pub mod live;
 pub mod parser;
 pub mod resolver;
 pub mod ts_demux;
@@ -19,9 +20,10 @@ use symphonia::core::io::MediaSource;
 
 use self::{
     fetcher::fetch_segment_into,
+    parser::parse_m3u8,
     resolver::{resolve_playlist, resolve_url_string},
     ts_demux::extract_adts_from_ts,
-    types::Resource,
+    types::{M3u8Playlist, Resource},
 };
 use crate::{config::HttpProxyConfig, sources::youtube::cipher::YouTubeCipherManager};
 
@@ -136,6 +138,34 @@ impl HlsReader {
         let (segment_urls, map_url) =
             handle.block_on(async { resolve_playlist(&client, manifest_url).await })?;
 
+        Self::from_segments(client, segment_urls, map_url, cipher_manager, player_url)
+    }
+
+    /// Plays a media playlist that was already fetched from `media_url`.
+    pub fn from_playlist(
+        client: reqwest::Client,
+        media_url: &str,
+        text: &str,
+        cipher_manager: Option<Arc<YouTubeCipherManager>>,
+        player_url: Option<String>,
+    ) -> AnyResult<Self> {
+        match parse_m3u8(text, media_url) {
+            M3u8Playlist::Media { segments, map } => {
+                Self::from_segments(client, segments, map, cipher_manager, player_url)
+            }
+            M3u8Playlist::Master { .. } => Err("HLS playlist did not resolve to media".into()),
+        }
+    }
+
+    fn from_segments(
+        client: reqwest::Client,
+        segment_urls: Vec<Resource>,
+        map_url: Option<Resource>,
+        cipher_manager: Option<Arc<YouTubeCipherManager>>,
+        player_url: Option<String>,
+    ) -> AnyResult<Self> {
+        let handle = tokio::runtime::Handle::current();
+
         if segment_urls.is_empty() {
             return Err("HLS playlist contained no segments".into());
         }
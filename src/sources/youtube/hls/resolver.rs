@@ -10,6 +10,20 @@ pub async fn resolve_playlist(
     client: &reqwest::Client,
     url: &str,
 ) -> AnyResult<(Vec<Resource>, Option<Resource>)> {
+    let (media_url, text) = resolve_media_playlist(client, url).await?;
+
+    match parse_m3u8(&text, &media_url) {
+        M3u8Playlist::Media { segments, map } => Ok((segments, map)),
+        M3u8Playlist::Master { .. } => Err("HLS playlist did not resolve to media".into()),
+    }
+}
+
+/// Follows a master playlist down to the media playlist that would be played,
+/// returning its URL and text.
+pub async fn resolve_media_playlist(
+    client: &reqwest::Client,
+    url: &str,
+) -> AnyResult<(String, String)> {
     let text = fetch_text(client, url).await?;
     let playlist = parse_m3u8(&text, url);
 
@@ -44,7 +58,7 @@ pub async fn resolve_playlist(
 
                         if let Some(uri) = rendition {
                             tracing::debug!("HLS: selected audio group {} -> {}", group_id, uri);
-                            return Box::pin(resolve_playlist(client, uri)).await;
+                            return Box::pin(resolve_media_playlist(client, uri)).await;
                         }
                     }
 
@@ -56,12 +70,12 @@ pub async fn resolve_playlist(
                         v.audio_group,
                         v.url
                     );
-                    Box::pin(resolve_playlist(client, &v.url)).await
+                    Box::pin(resolve_media_playlist(client, &v.url)).await
                 }
                 None => Err("HLS master playlist has no variants".into()),
             }
         }
-        M3u8Playlist::Media { segments, map } => Ok((segments, map)),
+        M3u8Playlist::Media { .. } => Ok((url.to_string(), text)),
     }
 }
 
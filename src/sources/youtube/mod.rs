@@ -13,6 +13,7 @@ use crate::{
     sources::{SourcePlugin, plugin::BoxedTrack},
 };
 
+pub mod chapters;
 pub mod cipher;
 pub mod clients;
 pub mod extractor;
@@ -24,6 +25,7 @@ pub mod utils;
 
 pub mod track;
 
+use chapters::ChapterCache;
 use cipher::YouTubeCipherManager;
 use clients::{
     YouTubeClient, android::AndroidClient, android_vr::AndroidVrClient, ios::IosClient,
@@ -35,6 +37,7 @@ use oauth::YouTubeOAuth;
 
 pub struct YouTubeSource {
     search_prefixes: Vec<String>,
+    isrc_prefixes: Vec<String>,
     rec_prefixes: Vec<String>,
     url_regex: Regex,
     search_clients: Vec<Arc<dyn YouTubeClient>>,
@@ -44,10 +47,21 @@ pub struct YouTubeSource {
     oauth: Arc<YouTubeOAuth>,
     cipher_manager: Arc<YouTubeCipherManager>,
     visitor_data: SharedRw<Option<String>>,
+    channel_load_limit: usize,
+    chapters: ChapterCache,
     #[allow(dead_code)]
     http: Arc<reqwest::Client>,
 }
 
+/// Non-video pages that load as playlists.
+enum BrowseTarget {
+    Album(String),
+    Artist(String),
+    Channel(String),
+    /// `/@handle`, `/c/name` and `/user/name`, which need resolving first.
+    ChannelUrl,
+}
+
 pub struct YoutubeStreamContext {
     pub clients: Vec<Arc<dyn YouTubeClient>>,
     pub oauth: Arc<YouTubeOAuth>,
@@ -182,6 +196,7 @@ impl YouTubeSource {
 
         Self {
             search_prefixes: vec!["ytsearch:".to_string(), "ytmsearch:".to_string()],
+            isrc_prefixes: vec!["ytmisrc:".to_string()],
             rec_prefixes: vec!["ytrec:".to_string()],
             url_regex: Regex::new(r"(?:youtube\.com|youtu\.be)").unwrap(),
             search_clients,
@@ -191,6 +206,8 @@ impl YouTubeSource {
             oauth,
             cipher_manager,
             visitor_data,
+            channel_load_limit: config.channel_load_limit,
+            chapters: ChapterCache::default(),
             http,
         }
     }
@@ -329,6 +346,59 @@ impl YouTubeSource {
         }
     }
 
+    /// Every configured client once, music clients first when `prefer_music`.
+    fn all_clients(&self, prefer_music: bool) -> Vec<&Arc<dyn YouTubeClient>> {
+        let is_music =
+            |c: &Arc<dyn YouTubeClient>| c.name().contains("Music") || c.name().contains("Remix");
+
+        let mut seen = std::collections::HashSet::new();
+        let mut clients: Vec<&Arc<dyn YouTubeClient>> = [
+            &self.music_search_clients[..],
+            &self.search_clients[..],
+            &self.resolve_clients[..],
+            &self.playback_clients[..],
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|c| seen.insert(c.name()))
+        .collect();
+        clients.sort_by_key(|c| is_music(c) != prefer_music);
+        clients
+    }
+
+    fn browse_target(&self, identifier: &str) -> Option<BrowseTarget> {
+        let url = reqwest::Url::parse(identifier).ok()?;
+        let is_music = url.host_str()?.starts_with("music.");
+        let mut segments = url.path_segments()?.filter(|s| !s.is_empty());
+        let first = segments.next()?;
+        let id = segments.next().unwrap_or_default().to_owned();
+
+        match first {
+            "browse" if id.starts_with("MPREb") => Some(BrowseTarget::Album(id)),
+            "channel" | "browse" if is_music && id.starts_with("UC") => {
+                Some(BrowseTarget::Artist(id))
+            }
+            "channel" if id.starts_with("UC") => Some(BrowseTarget::Channel(id)),
+            "c" | "user" if !is_music && !id.is_empty() => Some(BrowseTarget::ChannelUrl),
+            handle if handle.starts_with('@') && !is_music => Some(BrowseTarget::ChannelUrl),
+            _ => None,
+        }
+    }
+
+    async fn browse(&self, request: Value, context: &Value, prefer_music: bool) -> Option<Value> {
+        for client in self.all_clients(prefer_music) {
+            match client
+                .browse(request.clone(), context, self.oauth.clone())
+                .await
+            {
+                Ok(Some(body)) => return Some(body),
+                Ok(None) => continue,
+                Err(e) => tracing::warn!("Browse error with {}: {}", client.name(), e),
+            }
+        }
+        None
+    }
+
     fn prioritize_clients<'a>(
         &'a self,
         clients: &'a [Arc<dyn YouTubeClient>],
@@ -406,6 +476,7 @@ impl SourcePlugin for YouTubeSource {
         self.search_prefixes
             .iter()
             .any(|p| identifier.starts_with(p))
+            || self.isrc_prefixes.iter().any(|p| identifier.starts_with(p))
             || self.rec_prefixes.iter().any(|p| identifier.starts_with(p))
             || self.url_regex.is_match(identifier)
     }
@@ -414,11 +485,37 @@ impl SourcePlugin for YouTubeSource {
         self.search_prefixes.iter().map(|s| s.as_str()).collect()
     }
 
+    fn isrc_prefixes(&self) -> Vec<&str> {
+        self.isrc_prefixes.iter().map(|s| s.as_str()).collect()
+    }
+
+    fn rec_prefixes(&self) -> Vec<&str> {
+        self.rec_prefixes.iter().map(|s| s.as_str()).collect()
+    }
+
     async fn load(
         &self,
         identifier: &str,
         _routeplanner: Option<Arc<dyn crate::routeplanner::RoutePlanner>>,
     ) -> LoadResult {
+        let result = self.load_identifier(identifier).await;
+        if let LoadResult::Track(track) = &result {
+            self.chapters.remember(track);
+        }
+        result
+    }
+
+    async fn load_search(
+        &self,
+        query: &str,
+        types: &[String],
+        _routeplanner: Option<Arc<dyn crate::routeplanner::RoutePlanner>>,
+    ) -> Option<SearchResult> {
+        let prefix = self
+            .search_prefixes
+            .iter()
+            .find(|p| query.starts_with(*p))?;
+
         let visitor_data = self.visitor_data.read().await.clone();
         let context = if let Some(vd) = visitor_data {
             json!({ "visitorData": vd })
@@ -426,29 +523,8 @@ impl SourcePlugin for YouTubeSource {
             json!({})
         };
 
-        if let Some(prefix) = self
-            .search_prefixes
-            .iter()
-            .find(|p| identifier.starts_with(*p))
-        {
-            return self.handle_search(identifier, prefix, &context).await;
-        }
-
-        if let Some(prefix) = self
-            .rec_prefixes
-            .iter()
-            .find(|p| identifier.starts_with(*p))
-        {
-            return self
-                .handle_recommendations(identifier, prefix, &context)
-                .await;
-        }
-
-        if self.url_regex.is_match(identifier) {
-            return self.handle_url(identifier, &context).await;
-        }
-
-        LoadResult::Empty {}
+        self.handle_load_search(&query[prefix.len()..], prefix, types, &context)
+            .await
     }
 
     async fn get_track(
@@ -462,20 +538,67 @@ impl SourcePlugin for YouTubeSource {
 
         let clients_to_try = self.prioritize_clients(&self.playback_clients, is_music_url);
         let clients = clients_to_try.into_iter().cloned().collect();
+        let chapters = self.chapters.get(&id);
 
         Some(Box::new(track::YoutubeTrack {
             identifier: id,
             clients,
             oauth: self.oauth.clone(),
             cipher_manager: self.cipher_manager.clone(),
+            chapters,
             visitor_data,
             local_addr: routeplanner.and_then(|rp| rp.get_address()),
             proxy: None,
+            live_edge: Default::default(),
         }))
     }
 }
 
 impl YouTubeSource {
+    async fn load_identifier(&self, identifier: &str) -> LoadResult {
+        let visitor_data = self.visitor_data.read().await.clone();
+        let context = if let Some(vd) = visitor_data {
+            json!({ "visitorData": vd })
+        } else {
+            json!({})
+        };
+
+        if let Some(prefix) = self
+            .search_prefixes
+            .iter()
+            .find(|p| identifier.starts_with(*p))
+        {
+            return self.handle_search(identifier, prefix, &context).await;
+        }
+
+        // YouTube Music finds songs by their ISRC, but the results carry none,
+        // so mirrors score them like any other search.
+        if let Some(prefix) = self
+            .isrc_prefixes
+            .iter()
+            .find(|p| identifier.starts_with(*p))
+        {
+            let query = format!("ytmsearch:\"{}\"", &identifier[prefix.len()..]);
+            return self.handle_search(&query, "ytmsearch:", &context).await;
+        }
+
+        if let Some(prefix) = self
+            .rec_prefixes
+            .iter()
+            .find(|p| identifier.starts_with(*p))
+        {
+            return self
+                .handle_recommendations(identifier, prefix, &context)
+                .await;
+        }
+
+        if self.url_regex.is_match(identifier) {
+            return self.handle_url(identifier, &context).await;
+        }
+
+        LoadResult::Empty {}
+    }
+
     async fn handle_search(&self, identifier: &str, prefix: &str, context: &Value) -> LoadResult {
         let prefer_music = prefix == "ytmsearch:";
         let query = &identifier[prefix.len()..];
@@ -552,6 +675,35 @@ impl YouTubeSource {
         LoadResult::Empty {}
     }
 
+    /// Only WEB and WEB_REMIX can run an unfiltered search; `ytmsearch:` tries
+    /// YouTube Music first since that is where albums and artists come from.
+    async fn handle_load_search(
+        &self,
+        query: &str,
+        prefix: &str,
+        types: &[String],
+        context: &Value,
+    ) -> Option<SearchResult> {
+        for client in self.all_clients(prefix == "ytmsearch:") {
+            match client.search_raw(query, context, self.oauth.clone()).await {
+                Ok(Some(body)) => {
+                    let result = extractor::extract_search_result(&body, types, self.name());
+                    if !(result.tracks.is_empty()
+                        && result.albums.is_empty()
+                        && result.artists.is_empty()
+                        && result.playlists.is_empty())
+                    {
+                        return Some(result);
+                    }
+                }
+                Ok(None) => continue,
+                Err(e) => tracing::warn!("Search error with {}: {}", client.name(), e),
+            }
+        }
+
+        None
+    }
+
     async fn handle_recommendations(
         &self,
         identifier: &str,
@@ -595,6 +747,13 @@ impl YouTubeSource {
 
         // Playlist handling
         if let Some(playlist_id) = self.extract_playlist_id(identifier) {
+            // `watch?v=X&list=Y` and `youtu.be/X?list=Y` point at a track inside the playlist.
+            let selected_id = crate::common::utils::query_param(identifier, "v").or_else(|| {
+                identifier
+                    .contains("youtu.be/")
+                    .then(|| self.extract_id(identifier))
+            });
+
             // Prioritize resolve clients; prefer Android first for reliability.
             let mut playlist_clients: Vec<&Arc<dyn YouTubeClient>> = Vec::new();
             if let Some(android) = self.resolve_clients.iter().find(|c| c.name() == "Android") {
@@ -619,7 +778,7 @@ impl YouTubeSource {
                     .await
                 {
                     Ok(Some((tracks, title))) => {
-                        return LoadResult::Playlist(PlaylistData {
+                        let mut playlist = PlaylistData {
                             info: PlaylistInfo {
                                 name: title,
                                 selected_track: -1,
@@ -631,13 +790,21 @@ impl YouTubeSource {
                                 "totalTracks": tracks.len()
                             }),
                             tracks,
-                        });
+                        };
+                        if let Some(video_id) = &selected_id {
+                            playlist.select(|t| &t.info.identifier == video_id);
+                        }
+                        return LoadResult::Playlist(playlist);
                     }
                     _ => continue,
                 }
             }
         }
 
+        if let Some(target) = self.browse_target(identifier) {
+            return self.handle_browse(identifier, target, context).await;
+        }
+
         let id = self.extract_id(identifier);
         let resolve_clients: Vec<&Arc<dyn YouTubeClient>> = self.resolve_clients.iter().collect();
 
@@ -682,4 +849,126 @@ impl YouTubeSource {
 
         LoadResult::Empty {}
     }
+
+    async fn handle_browse(
+        &self,
+        identifier: &str,
+        target: BrowseTarget,
+        context: &Value,
+    ) -> LoadResult {
+        match target {
+            BrowseTarget::Album(id) => {
+                let Some(body) = self.browse(json!({ "browseId": id }), context, true).await else {
+                    return LoadResult::Empty {};
+                };
+                let Some((mut tracks, title)) = extractor::extract_from_browse(&body, self.name())
+                else {
+                    return LoadResult::Empty {};
+                };
+                extractor::fill_album_metadata(&body, &mut tracks);
+                LoadResult::Playlist(PlaylistData {
+                    info: PlaylistInfo {
+                        name: title,
+                        selected_track: -1,
+                    },
+                    plugin_info: json!({
+                        "type": "album",
+                        "url": format!("https://music.youtube.com/browse/{}", id),
+                        "artworkUrl": tracks.first().and_then(|t| t.info.artwork_url.clone()),
+                        "author": tracks.first().map(|t| t.info.author.clone()),
+                        "totalTracks": tracks.len()
+                    }),
+                    tracks,
+                })
+            }
+            BrowseTarget::Artist(id) => {
+                let Some(body) = self.browse(json!({ "browseId": id }), context, true).await else {
+                    return LoadResult::Empty {};
+                };
+                let Some((tracks, name)) = extractor::extract_from_browse(&body, self.name())
+                else {
+                    return LoadResult::Empty {};
+                };
+                LoadResult::Playlist(PlaylistData {
+                    info: PlaylistInfo {
+                        name: format!("{}'s Top Tracks", name),
+                        selected_track: -1,
+                    },
+                    plugin_info: json!({
+                        "type": "artist",
+                        "url": format!("https://music.youtube.com/channel/{}", id),
+                        "artworkUrl": tracks.first().and_then(|t| t.info.artwork_url.clone()),
+                        "author": name,
+                        "totalTracks": tracks.len()
+                    }),
+                    tracks,
+                })
+            }
+            BrowseTarget::Channel(id) => self.load_channel(&id, context).await,
+            BrowseTarget::ChannelUrl => {
+                for client in self.all_clients(false) {
+                    match client
+                        .resolve_browse_id(identifier, context, self.oauth.clone())
+                        .await
+                    {
+                        Ok(Some(id)) if id.starts_with("UC") => {
+                            return self.load_channel(&id, context).await;
+                        }
+                        Ok(_) => continue,
+                        Err(e) => tracing::warn!("Resolve error with {}: {}", client.name(), e),
+                    }
+                }
+                LoadResult::Empty {}
+            }
+        }
+    }
+
+    /// Loads a channel through its uploads playlist (`UC...` -> `UU...`),
+    /// newest first, following continuations up to `channel_load_limit`.
+    async fn load_channel(&self, channel_id: &str, context: &Value) -> LoadResult {
+        let uploads = format!("VLUU{}", &channel_id[2..]);
+        let Some(body) = self
+            .browse(json!({ "browseId": uploads }), context, false)
+            .await
+        else {
+            return LoadResult::Empty {};
+        };
+        let Some((mut tracks, title)) = extractor::extract_from_browse(&body, self.name()) else {
+            return LoadResult::Empty {};
+        };
+
+        let mut token = extractor::continuation_token(&body);
+        while tracks.len() < self.channel_load_limit
+            && let Some(continuation) = token.take()
+        {
+            let Some(page) = self
+                .browse(json!({ "continuation": continuation }), context, false)
+                .await
+            else {
+                break;
+            };
+            let (more, next) = extractor::extract_continuation(&page, self.name());
+            if more.is_empty() {
+                break;
+            }
+            tracks.extend(more);
+            token = next;
+        }
+        tracks.truncate(self.channel_load_limit);
+
+        LoadResult::Playlist(PlaylistData {
+            info: PlaylistInfo {
+                name: title,
+                selected_track: -1,
+            },
+            plugin_info: json!({
+                "type": "artist",
+                "url": format!("https://www.youtube.com/channel/{}", channel_id),
+                "artworkUrl": tracks.first().and_then(|t| t.info.artwork_url.clone()),
+                "author": tracks.first().map(|t| t.info.author.clone()),
+                "totalTracks": tracks.len()
+            }),
+            tracks,
+        })
+    }
 }
@@ -1,15 +1,22 @@
 use std::{net::IpAddr, sync::Arc};
 
+use symphonia::core::io::MediaSource;
 use tracing::{debug, error, info, warn};
 
 use crate::{
-    audio::{AudioFrame, processor::DecoderCommand},
+    audio::{AudioFrame, playback::LiveEdge, processor::DecoderCommand},
     config::HttpProxyConfig,
+    protocol::tracks::Chapter,
     sources::{
         plugin::{DecoderOutput, PlayableTrack},
         youtube::{
             cipher::YouTubeCipherManager,
             clients::YouTubeClient,
+            hls::{
+                HlsReader,
+                live::{LiveSource, build_client, is_live_playlist, run_live},
+                resolver::resolve_media_playlist,
+            },
             oauth::YouTubeOAuth,
             utils::{create_reader, detect_audio_kind},
         },
@@ -21,9 +28,13 @@ pub struct YoutubeTrack {
     pub clients: Vec<Arc<dyn YouTubeClient>>,
     pub oauth: Arc<YouTubeOAuth>,
     pub cipher_manager: Arc<YouTubeCipherManager>,
+    /// Chapters parsed when the video was loaded.
+    pub chapters: Vec<Chapter>,
     pub visitor_data: Option<String>,
     pub local_addr: Option<IpAddr>,
     pub proxy: Option<HttpProxyConfig>,
+    /// Set once the video turns out to be a live stream.
+    pub live_edge: Arc<LiveEdge>,
 }
 
 impl PlayableTrack for YoutubeTrack {
@@ -39,6 +50,7 @@ impl PlayableTrack for YoutubeTrack {
         let visitor_data_for_task = self.visitor_data.clone();
         let proxy_bg = self.proxy.clone();
         let local_addr_bg = self.local_addr;
+        let live_edge = self.live_edge.clone();
 
         tokio::spawn(async move {
             let context = serde_json::json!({ "visitorData": visitor_data_for_task });
@@ -104,19 +116,62 @@ impl PlayableTrack for YoutubeTrack {
                 };
 
                 let is_hls = url.contains(".m3u8") || url.contains("/playlist");
+
+                // The media playlist fetched to tell live from VOD is handed to
+                // whichever reader plays it.
+                let mut hls_playlist = None;
+                if is_hls && let Ok(hls_client) = build_client(local_addr_bg, proxy_bg.clone()) {
+                    match resolve_media_playlist(&hls_client, &url).await {
+                        Ok((media_url, text)) if is_live_playlist(&text) => {
+                            info!(
+                                "YoutubeTrack: '{}' is live, playing from a rolling window",
+                                identifier_async
+                            );
+                            let source = LiveSource {
+                                client: hls_client,
+                                manifest_url: url,
+                                playlist: Some((media_url, text)),
+                                cipher_manager: Some(cipher_manager_async.clone()),
+                                player_url: None,
+                                edge: live_edge.clone(),
+                            };
+                            run_live(
+                                format!("youtube-live-{}", identifier_async),
+                                source,
+                                config.clone(),
+                                tx.clone(),
+                                cmd_rx.clone(),
+                                err_tx.clone(),
+                            )
+                            .await;
+                            return;
+                        }
+                        Ok((media_url, text)) => hls_playlist = Some((hls_client, media_url, text)),
+                        Err(e) => debug!("YoutubeTrack: HLS playlist resolve failed: {}", e),
+                    }
+                }
+
                 let url_clone = url.clone();
                 let cipher_clone = cipher_manager_async.clone();
                 let proxy_clone = proxy_bg.clone();
                 let client_name_inner = client_name.clone();
 
-                let reader_res = tokio::task::spawn_blocking(move || {
-                    create_reader(
+                let reader_res = tokio::task::spawn_blocking(move || match hls_playlist {
+                    Some((hls_client, media_url, text)) => HlsReader::from_playlist(
+                        hls_client,
+                        &media_url,
+                        &text,
+                        Some(cipher_clone),
+                        None,
+                    )
+                    .map(|reader| Box::new(reader) as Box<dyn MediaSource>),
+                    None => create_reader(
                         &url_clone,
                         &client_name_inner,
                         local_addr_bg,
                         proxy_clone,
                         cipher_clone,
-                    )
+                    ),
                 })
                 .await
                 .expect("YoutubeTrack: reader spawn_blocking failed");
@@ -138,31 +193,38 @@ impl PlayableTrack for YoutubeTrack {
 
                 let config_for_processor = config.clone();
                 let (done_tx, mut done_rx) = tokio::sync::oneshot::channel::<Result<(), String>>();
-                let identifier_for_thread = identifier_async.clone();
-
-                std::thread::Builder::new()
-                    .name(format!("youtube-decoder-{}", identifier_async))
-                    .spawn(move || {
-                        let result = match crate::audio::processor::AudioProcessor::new(
-                            reader,
-                            Some(kind),
-                            tx_clone,
-                            inner_cmd_rx,
-                            Some(err_tx_clone.clone()),
-                            config_for_processor,
-                        ) {
-                            Ok(mut processor) => processor.run().map_err(|e| e.to_string()),
-                            Err(e) => {
-                                error!(
-                                    "YoutubeTrack: AudioProcessor initialization failed for {}: {}",
-                                    identifier_for_thread, e
-                                );
-                                Err(format!("Failed to initialize processor: {}", e))
-                            }
-                        };
-                        let _ = done_tx.send(result);
-                    })
-                    .expect("failed to spawn youtube decoder thread");
+
+                let processor_res = tokio::task::spawn_blocking(move || {
+                    crate::audio::processor::AudioProcessor::new(
+                        reader,
+                        Some(kind),
+                        tx_clone,
+                        inner_cmd_rx,
+                        Some(err_tx_clone),
+                        config_for_processor,
+                    )
+                })
+                .await
+                .expect("YoutubeTrack: processor spawn_blocking failed");
+
+                match processor_res {
+                    Ok(processor) => {
+                        crate::audio::worker::submit(
+                            format!("youtube-decoder-{}", identifier_async),
+                            processor,
+                            move |res| {
+                                let _ = done_tx.send(res.map_err(|e| e.to_string()));
+                            },
+                        );
+                    }
+                    Err(e) => {
+                        error!(
+                            "YoutubeTrack: AudioProcessor initialization failed for {}: {}",
+                            identifier_async, e
+                        );
+                        let _ = done_tx.send(Err(format!("Failed to initialize processor: {}", e)));
+                    }
+                }
 
                 if current_seek_ms > 0 {
                     let _ = inner_cmd_tx.send(DecoderCommand::Seek(current_seek_ms));
@@ -226,4 +288,12 @@ impl PlayableTrack for YoutubeTrack {
 
         (rx, cmd_tx, err_rx)
     }
+
+    fn chapters(&self) -> Vec<Chapter> {
+        self.chapters.clone()
+    }
+
+    fn live_edge(&self) -> Option<Arc<LiveEdge>> {
+        Some(self.live_edge.clone())
+    }
 }
@@ -231,6 +231,7 @@ async fn send_initial_state(socket: &mut WebSocket, session: &Arc<Session>, resu
                         .unwrap_or(player.position),
                     connected: !player.voice.token.is_empty(),
                     ping: player.ping.load(Relaxed),
+                    live_latency: player.track_handle.as_ref().and_then(|h| h.live_latency()),
                 },
             };
             session.send_message(&update);